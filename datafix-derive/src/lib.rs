@@ -0,0 +1,187 @@
+//! Derive macros for `datafix`. You should use these through the `derive` feature of `datafix` instead of depending on this crate directly.
+
+extern crate proc_macro;
+
+mod parse;
+
+use parse::{Data, Field, Input, Variant, VariantFields, unraw};
+use proc_macro::TokenStream;
+
+/// Derives `DefaultCodec` for a struct with named fields or an enum, generating the same codecs you would write by hand.
+///
+/// For structs, each field is encoded under its own name using the `DefaultCodec` of its type through a `MapCodecBuilder`.
+/// Fields support the following attributes:
+/// * `#[datafix(rename = "name")]` encodes the field under a different key.
+/// * `#[datafix(optional)]` marks an `Option<T>` field as optional, which omits the key when the value is `None`.
+///   The type must be written as `Option<T>` or a path to it such as `core::option::Option<T>`.
+///
+/// Raw identifiers are encoded without their `r#` prefix, so a field named `r#type` uses the key `"type"`.
+///
+/// For enums, the name of each variant is used as its tag. Unit variants are encoded as just the tag,
+/// and struct variants encode their fields the same way structs do. Tuple variants are not supported.
+/// Variants can be renamed with `#[datafix(rename = "name")]`, and the tagging is chosen with a container attribute:
+/// * No attribute uses external tagging, like `{"Variant": {...}}`, see `Codecs::externally_tagged`.
+/// * `#[datafix(tag = "type")]` uses internal tagging, like `{"type": "Variant", ...}`, see `Codecs::internally_tagged`.
+/// * `#[datafix(tag = "t", content = "c")]` uses adjacent tagging, like `{"t": "Variant", "c": {...}}`,
+///   see `Codecs::adjacently_tagged`.
+#[proc_macro_derive(Codec, attributes(datafix))]
+pub fn derive_codec(input: TokenStream) -> TokenStream {
+    match Input::parse(input).and_then(|input| expand(&input)) {
+        Ok(output) => output.parse().expect("generated code should be valid"),
+        Err(message) => format!("compile_error!({:?});", message)
+            .parse()
+            .expect("generated error should be valid"),
+    }
+}
+
+fn expand(input: &Input) -> Result<String, String> {
+    match &input.data {
+        Data::Struct(fields) => expand_struct(input, fields.as_deref()),
+        Data::Enum(variants) => expand_enum(input, variants),
+    }
+}
+
+fn expand_struct(input: &Input, fields: Option<&[Field]>) -> Result<String, String> {
+    let fields = fields.ok_or("`Codec` can only be derived for structs with named fields")?;
+
+    Ok(format!(
+        "{impl_header} {{
+            fn codec() -> impl ::datafix::serialization::Codec<Self, DatafixOps> {{
+                use ::datafix::serialization::CodecAdapters as _;
+                {builder}
+            }}
+        }}",
+        impl_header = input.impl_header(&[]),
+        builder = record_codec("Self", fields, |name| format!("&s.{name}"))?,
+    ))
+}
+
+fn expand_enum(input: &Input, variants: &[Variant]) -> Result<String, String> {
+    if variants.is_empty() {
+        return Err("`Codec` can not be derived for enums without variants".into());
+    }
+
+    let mut tag_arms = String::new();
+    let mut codecs = String::new();
+    // Unit variants are marked as such, so they are encoded as just the tag.
+    let mut unit_tags = String::new();
+    let mut unit_count = 0;
+    for variant in variants {
+        let name = &variant.name;
+        let tag = variant.attributes.rename.as_deref().unwrap_or(unraw(name));
+        tag_arms.push_str(&format!("Self::{name} {{ .. }} => {tag:?},"));
+
+        let codec = match &variant.fields {
+            VariantFields::Named(fields) if !fields.is_empty() => {
+                record_codec(&format!("Self::{name}"), fields, |field| {
+                    format!(
+                        "match s {{
+                            Self::{name} {{ {field}, .. }} => {field},
+                            #[allow(unreachable_patterns)]
+                            _ => ::core::unreachable!(),
+                        }}"
+                    )
+                })?
+            }
+            VariantFields::Named(_) => {
+                unit_tags.push_str(&format!("{tag:?},"));
+                unit_count += 1;
+                format!(
+                    "::datafix::serialization::Codecs::unit().xmap(|_| Self::{name} {{}}, |_| ())"
+                )
+            }
+            VariantFields::Unit => {
+                unit_tags.push_str(&format!("{tag:?},"));
+                unit_count += 1;
+                format!("::datafix::serialization::Codecs::unit().xmap(|_| Self::{name}, |_| ())")
+            }
+            VariantFields::Unnamed => {
+                return Err(format!(
+                    "`Codec` can not be derived for tuple variants such as `{name}`, use a struct variant instead"
+                ));
+            }
+        };
+        codecs.push_str(&format!("({tag:?}, {codec}.dynamic()),"));
+    }
+
+    let tag_of = format!("|value: &Self| match value {{ {tag_arms} }}");
+    let dispatch = match (&input.attributes.tag, &input.attributes.content) {
+        (None, None) => {
+            format!(
+                "::datafix::serialization::Codecs::externally_tagged({tag_of}, [{codecs}])
+                    .unit_variants([{unit_tags}] as [&str; {unit_count}])"
+            )
+        }
+        (Some(tag), None) => format!(
+            "::datafix::serialization::Codecs::internally_tagged({tag:?}, {tag_of}, [{codecs}])"
+        ),
+        (Some(tag), Some(content)) => format!(
+            "::datafix::serialization::Codecs::adjacently_tagged({tag:?}, {content:?}, {tag_of}, [{codecs}])
+                .unit_variants([{unit_tags}] as [&str; {unit_count}])"
+        ),
+        (None, Some(_)) => {
+            return Err("`content` can only be used together with `tag`".into());
+        }
+    };
+
+    Ok(format!(
+        "{impl_header} {{
+            fn codec() -> impl ::datafix::serialization::Codec<Self, DatafixOps> {{
+                use ::datafix::serialization::CodecAdapters as _;
+                {dispatch}
+            }}
+        }}",
+        impl_header = input.impl_header(&["Self: 'static", "DatafixOps: 'static"]),
+    ))
+}
+
+/// Builds a `MapCodecBuilder` chain for `fields`, constructed with `constructor`.
+/// `getter` returns an expression that borrows the field from `s`.
+fn record_codec(
+    constructor: &str,
+    fields: &[Field],
+    getter: impl Fn(&str) -> String,
+) -> Result<String, String> {
+    if fields.len() > 32 {
+        return Err("`Codec` can only be derived for up to 32 fields".into());
+    }
+
+    let mut chain = String::new();
+    for field in fields {
+        chain.push_str(&format!(
+            ".field({})",
+            field_codec(field, &getter(&field.name))?
+        ));
+    }
+    let names = fields
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(format!(
+        "::datafix::serialization::MapCodecBuilder::new()
+            {chain}
+            .build(|{names}| {constructor} {{ {names} }})"
+    ))
+}
+
+fn field_codec(field: &Field, getter: &str) -> Result<String, String> {
+    let name = &field.name;
+    let key = field.attributes.rename.as_deref().unwrap_or(unraw(name));
+    if field.attributes.optional {
+        let inner = field.option_inner_type().ok_or_else(|| {
+            format!("field `{name}` is marked as optional but its type is not an `Option<T>`")
+        })?;
+        Ok(format!(
+            "<{inner} as ::datafix::serialization::DefaultCodec<DatafixOps>>::codec()
+                .optional_field_of({key:?}, |s: &Self| {getter})"
+        ))
+    } else {
+        Ok(format!(
+            "<{ty} as ::datafix::serialization::DefaultCodec<DatafixOps>>::codec()
+                .field_of({key:?}, |s: &Self| {getter})",
+            ty = field.ty
+        ))
+    }
+}
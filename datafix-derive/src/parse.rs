@@ -0,0 +1,445 @@
+use proc_macro::{Delimiter, Group, TokenStream, TokenTree};
+
+/// The parsed form of the item a derive macro is applied to.
+pub struct Input {
+    pub name: String,
+    pub generics: Vec<GenericParam>,
+    pub where_clause: Option<String>,
+    pub attributes: Attributes,
+    pub data: Data,
+}
+
+pub enum Data {
+    /// A struct, containing its named fields or `None` if the struct does not have named fields.
+    Struct(Option<Vec<Field>>),
+    Enum(Vec<Variant>),
+}
+
+pub struct Variant {
+    pub name: String,
+    pub attributes: Attributes,
+    pub fields: VariantFields,
+}
+
+pub enum VariantFields {
+    Unit,
+    Named(Vec<Field>),
+    Unnamed,
+}
+
+pub enum GenericKind {
+    Lifetime,
+    Type,
+    Const,
+}
+
+pub struct GenericParam {
+    pub name: String,
+    pub declaration: String,
+    pub kind: GenericKind,
+}
+
+pub struct Field {
+    pub name: String,
+    pub ty: String,
+    pub ty_tokens: Vec<TokenTree>,
+    pub attributes: Attributes,
+}
+
+/// The arguments of the `#[datafix(...)]` attributes on a container, variant or field.
+#[derive(Default)]
+pub struct Attributes {
+    pub rename: Option<String>,
+    pub optional: bool,
+    pub tag: Option<String>,
+    pub content: Option<String>,
+}
+
+impl Input {
+    pub fn parse(input: TokenStream) -> Result<Input, String> {
+        let tokens = input.into_iter().collect::<Vec<_>>();
+        let mut cursor = Cursor { tokens, index: 0 };
+
+        let attributes = cursor.attributes()?;
+        cursor.visibility();
+        let is_enum = match cursor.ident().as_deref() {
+            Some("struct") => false,
+            Some("enum") => true,
+            _ => return Err("`Codec` can only be derived for structs and enums".into()),
+        };
+        let name = cursor.ident().ok_or("expected the name of the type")?;
+        let generics = cursor.generics()?;
+        let mut where_clause = cursor.where_clause();
+        let data = match cursor.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                if is_enum {
+                    Data::Enum(parse_variants(group)?)
+                } else {
+                    Data::Struct(Some(parse_named_fields(group)?))
+                }
+            }
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                where_clause = cursor.where_clause();
+                Data::Struct(None)
+            }
+            _ => Data::Struct(None),
+        };
+
+        Ok(Input {
+            name,
+            generics,
+            where_clause,
+            attributes,
+            data,
+        })
+    }
+
+    /// Returns the header of a `DefaultCodec` implementation for this type, using `DatafixOps` as the ops type parameter.
+    /// Any `extra_predicates` are added to the where clause.
+    pub fn impl_header(&self, extra_predicates: &[&str]) -> String {
+        let mut declarations = Vec::new();
+        for param in &self.generics {
+            if let GenericKind::Lifetime = param.kind {
+                declarations.push(param.declaration.clone());
+            }
+        }
+        declarations.push("DatafixOps: ::datafix::serialization::CodecOps".into());
+        for param in &self.generics {
+            if !matches!(param.kind, GenericKind::Lifetime) {
+                declarations.push(param.declaration.clone());
+            }
+        }
+
+        let names = self
+            .generics
+            .iter()
+            .map(|param| param.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut predicates = self
+            .generics
+            .iter()
+            .filter(|param| matches!(param.kind, GenericKind::Type))
+            .map(|param| {
+                format!(
+                    "{}: ::datafix::serialization::DefaultCodec<DatafixOps>",
+                    param.name
+                )
+            })
+            .chain(
+                extra_predicates
+                    .iter()
+                    .map(|predicate| predicate.to_string()),
+            )
+            .collect::<Vec<_>>();
+        if let Some(where_clause) = &self.where_clause {
+            predicates.push(where_clause.clone());
+        }
+
+        format!(
+            "impl<{}> ::datafix::serialization::DefaultCodec<DatafixOps> for {}<{}> where {}",
+            declarations.join(", "),
+            self.name,
+            names,
+            predicates.join(", ")
+        )
+    }
+}
+
+impl Field {
+    /// Returns the type inside of the `Option` if the type of this field is an `Option`, written as `Option<T>`
+    /// or a path such as `core::option::Option<T>`. Types that only contain an `Option`, such as `Vec<Option<T>>`,
+    /// are not an `Option`.
+    pub fn option_inner_type(&self) -> Option<String> {
+        let tokens = &self.ty_tokens;
+        let start = tokens.iter().position(|token| is_punct(token, '<'))?;
+        let (name, path) = tokens[..start].split_last()?;
+        if !matches!(name, TokenTree::Ident(ident) if ident.to_string() == "Option") {
+            return None;
+        }
+        let is_option_path = path.iter().all(|token| match token {
+            TokenTree::Ident(ident) => {
+                matches!(ident.to_string().as_str(), "std" | "core" | "option")
+            }
+            token => is_punct(token, ':'),
+        });
+        let (last, arguments) = tokens[start + 1..].split_last()?;
+        if !is_option_path || !is_punct(last, '>') || arguments.is_empty() {
+            return None;
+        }
+
+        // The `>` closing the arguments must be the last token, so `Option<A> + B` is rejected.
+        let mut depth = 0;
+        let mut previous = None;
+        for token in arguments {
+            if is_punct(token, '<') {
+                depth += 1;
+            } else if is_punct(token, '>') && previous != Some('-') {
+                if depth == 0 {
+                    return None;
+                }
+                depth -= 1;
+            }
+            previous = match token {
+                TokenTree::Punct(punct) => Some(punct.as_char()),
+                _ => None,
+            };
+        }
+        Some(stringify(arguments))
+    }
+}
+
+/// Removes the `r#` prefix of a raw identifier, which is not part of the name used as a key or tag.
+pub fn unraw(name: &str) -> &str {
+    name.strip_prefix("r#").unwrap_or(name)
+}
+
+struct Cursor {
+    tokens: Vec<TokenTree>,
+    index: usize,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<&TokenTree> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<TokenTree> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(TokenTree::Ident(ident)) => {
+                let ident = ident.to_string();
+                self.index += 1;
+                Some(ident)
+            }
+            _ => None,
+        }
+    }
+
+    fn peek_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(TokenTree::Ident(ident)) if ident.to_string() == name)
+    }
+
+    /// Parses all outer attributes, returning the parsed `#[datafix(...)]` attributes.
+    fn attributes(&mut self) -> Result<Attributes, String> {
+        let mut attributes = Attributes::default();
+        while matches!(self.peek(), Some(token) if is_punct(token, '#')) {
+            self.index += 1;
+            let Some(TokenTree::Group(group)) = self.next() else {
+                return Err("expected an attribute".into());
+            };
+            let mut inner = group.stream().into_iter();
+            match (inner.next(), inner.next()) {
+                (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(args)))
+                    if ident.to_string() == "datafix" =>
+                {
+                    parse_datafix_attribute(args, &mut attributes)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn visibility(&mut self) {
+        if self.peek_ident("pub") {
+            self.index += 1;
+            if matches!(self.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+            {
+                self.index += 1;
+            }
+        }
+    }
+
+    fn generics(&mut self) -> Result<Vec<GenericParam>, String> {
+        if !matches!(self.peek(), Some(token) if is_punct(token, '<')) {
+            return Ok(Vec::new());
+        }
+        self.index += 1;
+        let mut depth = 0;
+        let mut tokens = Vec::new();
+        loop {
+            let token = self.next().ok_or("unterminated generics")?;
+            if is_punct(&token, '<') {
+                depth += 1;
+            } else if is_punct(&token, '>') {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            }
+            tokens.push(token);
+        }
+
+        let mut params = Vec::new();
+        for param in split_top_level(&tokens, ',') {
+            if param.is_empty() {
+                continue;
+            }
+            // Default values are not allowed in the generics of an `impl`, so they are removed.
+            let declaration = split_top_level(&param, '=').swap_remove(0);
+            let (name, kind) = if is_punct(&declaration[0], '\'') {
+                (stringify(&declaration[..2]), GenericKind::Lifetime)
+            } else if matches!(&declaration[0], TokenTree::Ident(ident) if ident.to_string() == "const")
+            {
+                (declaration[1].to_string(), GenericKind::Const)
+            } else {
+                (declaration[0].to_string(), GenericKind::Type)
+            };
+            params.push(GenericParam {
+                name,
+                declaration: stringify(&declaration),
+                kind,
+            });
+        }
+        Ok(params)
+    }
+
+    fn where_clause(&mut self) -> Option<String> {
+        if !self.peek_ident("where") {
+            return None;
+        }
+        self.index += 1;
+        let mut tokens = Vec::new();
+        while let Some(token) = self.peek() {
+            if matches!(token, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace)
+                || is_punct(token, ';')
+            {
+                break;
+            }
+            tokens.push(token.clone());
+            self.index += 1;
+        }
+        Some(stringify(&tokens).trim_end_matches(',').to_string())
+    }
+}
+
+fn parse_named_fields(group: Group) -> Result<Vec<Field>, String> {
+    let tokens = group.stream().into_iter().collect::<Vec<_>>();
+    let mut fields = Vec::new();
+    for field in split_top_level(&tokens, ',') {
+        if field.is_empty() {
+            continue;
+        }
+        let mut cursor = Cursor {
+            tokens: field,
+            index: 0,
+        };
+        let attributes = cursor.attributes()?;
+        cursor.visibility();
+        let name = cursor.ident().ok_or("expected a field name")?;
+        if !matches!(cursor.next(), Some(token) if is_punct(&token, ':')) {
+            return Err(format!("expected a type for field `{name}`"));
+        }
+        let ty_tokens = cursor.tokens[cursor.index..].to_vec();
+        fields.push(Field {
+            name,
+            ty: stringify(&ty_tokens),
+            ty_tokens,
+            attributes,
+        });
+    }
+    Ok(fields)
+}
+
+fn parse_variants(group: Group) -> Result<Vec<Variant>, String> {
+    let tokens = group.stream().into_iter().collect::<Vec<_>>();
+    let mut variants = Vec::new();
+    for variant in split_top_level(&tokens, ',') {
+        if variant.is_empty() {
+            continue;
+        }
+        let mut cursor = Cursor {
+            tokens: variant,
+            index: 0,
+        };
+        let attributes = cursor.attributes()?;
+        let name = cursor.ident().ok_or("expected a variant name")?;
+        let fields = match cursor.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                VariantFields::Named(parse_named_fields(group)?)
+            }
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                VariantFields::Unnamed
+            }
+            _ => VariantFields::Unit,
+        };
+        variants.push(Variant {
+            name,
+            attributes,
+            fields,
+        });
+    }
+    Ok(variants)
+}
+
+fn parse_datafix_attribute(args: Group, attributes: &mut Attributes) -> Result<(), String> {
+    let tokens = args.stream().into_iter().collect::<Vec<_>>();
+    for arg in split_top_level(&tokens, ',') {
+        match arg.as_slice() {
+            [] => {}
+            [TokenTree::Ident(ident)] if ident.to_string() == "optional" => {
+                attributes.optional = true;
+            }
+            [TokenTree::Ident(ident), eq, TokenTree::Literal(literal)] if is_punct(eq, '=') => {
+                let value = Some(unquote(&literal.to_string())?);
+                match ident.to_string().as_str() {
+                    "rename" => attributes.rename = value,
+                    "tag" => attributes.tag = value,
+                    "content" => attributes.content = value,
+                    other => return Err(format!("unknown datafix attribute `{other}`")),
+                }
+            }
+            _ => {
+                return Err(format!("unknown datafix attribute `{}`", stringify(&arg)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits tokens by a separator, ignoring any separators nested inside of angle brackets.
+fn split_top_level(tokens: &[TokenTree], separator: char) -> Vec<Vec<TokenTree>> {
+    let mut parts = vec![Vec::new()];
+    let mut depth = 0i32;
+    let mut previous = None;
+    for token in tokens {
+        if is_punct(token, '<') {
+            depth += 1;
+        } else if is_punct(token, '>') && !previous.is_some_and(|p| p == '-' || p == '=') {
+            depth -= 1;
+        } else if depth == 0 && is_punct(token, separator) {
+            parts.push(Vec::new());
+            previous = None;
+            continue;
+        }
+        previous = match token {
+            TokenTree::Punct(punct) => Some(punct.as_char()),
+            _ => None,
+        };
+        parts.last_mut().unwrap().push(token.clone());
+    }
+    parts
+}
+
+fn is_punct(token: &TokenTree, c: char) -> bool {
+    matches!(token, TokenTree::Punct(punct) if punct.as_char() == c)
+}
+
+fn stringify(tokens: &[TokenTree]) -> String {
+    tokens.iter().cloned().collect::<TokenStream>().to_string()
+}
+
+fn unquote(literal: &str) -> Result<String, String> {
+    let inner = literal
+        .strip_prefix('"')
+        .and_then(|literal| literal.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a string literal, found `{literal}`"))?;
+    Ok(inner.replace("\\\"", "\"").replace("\\\\", "\\"))
+}
@@ -0,0 +1,105 @@
+//! Compares codec round-trips against building and reading the same `JsonValue` by hand.
+//!
+//! Run with `cargo bench -p datafix`. Each case reports the average time of one encode and decode.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use datafix::serialization::{Codec, CodecAdapters, DefaultCodec, MapCodecBuilder, json::JsonOps};
+use json::JsonValue;
+
+const ITERATIONS: u32 = 100_000;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Player {
+    name: String,
+    level: i32,
+    health: f64,
+}
+
+fn player_codec() -> impl Codec<Player, JsonOps> {
+    MapCodecBuilder::new()
+        .field(String::codec().field_of("name", |p: &Player| &p.name))
+        .field(i32::codec().field_of("level", |p: &Player| &p.level))
+        .field(f64::codec().field_of("health", |p: &Player| &p.health))
+        .build(|name, level, health| Player {
+            name,
+            level,
+            health,
+        })
+}
+
+fn encode_player_by_hand(player: &Player) -> JsonValue {
+    json::object! {
+        "name": player.name.clone(),
+        "level": player.level,
+        "health": player.health,
+    }
+}
+
+fn decode_player_by_hand(value: &JsonValue) -> Option<Player> {
+    Some(Player {
+        name: value["name"].as_str()?.to_string(),
+        level: value["level"].as_i32()?,
+        health: value["health"].as_f64()?,
+    })
+}
+
+fn bench(name: &str, mut f: impl FnMut()) -> Duration {
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let average = start.elapsed() / ITERATIONS;
+    println!("{:<24} {:>10.1?}", name, average);
+    average
+}
+
+fn main() {
+    let number = 12.5;
+    let codec = f64::codec();
+    bench("f64 codec", || {
+        let encoded = codec.encode_start(&JsonOps, black_box(&number)).unwrap();
+        black_box(codec.decode_start(&JsonOps, &encoded).unwrap());
+    });
+    bench("f64 by hand", || {
+        let encoded = JsonValue::from(*black_box(&number));
+        black_box(encoded.as_f64().unwrap());
+    });
+
+    let player = Player {
+        name: "Steve".to_string(),
+        level: 30,
+        health: 17.5,
+    };
+    let codec = player_codec();
+    bench("record codec", || {
+        let encoded = codec.encode_start(&JsonOps, black_box(&player)).unwrap();
+        black_box(codec.decode_start(&JsonOps, &encoded).unwrap());
+    });
+    bench("record by hand", || {
+        let encoded = encode_player_by_hand(black_box(&player));
+        black_box(decode_player_by_hand(&encoded).unwrap());
+    });
+
+    let list: Vec<i32> = (0..100).collect();
+    let codec = i32::codec().list_of();
+    bench("list codec", || {
+        let encoded = codec.encode_start(&JsonOps, black_box(&list)).unwrap();
+        black_box(codec.decode_start(&JsonOps, &encoded).unwrap());
+    });
+    bench("list by hand", || {
+        let encoded = JsonValue::Array(black_box(&list).iter().map(|x| (*x).into()).collect());
+        black_box(
+            encoded
+                .members()
+                .map(|x| x.as_i32().unwrap())
+                .collect::<Vec<_>>(),
+        );
+    });
+}
@@ -0,0 +1,78 @@
+use core::ops::Deref;
+
+use alloc::vec::Vec;
+
+/// A [`Vec`] that is guaranteed to contain at least one element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NonEmptyVec<T> {
+    inner: Vec<T>,
+}
+
+impl<T> NonEmptyVec<T> {
+    /// Creates a new [`NonEmptyVec`] containing a single element.
+    pub fn new(first: T) -> Self {
+        NonEmptyVec {
+            inner: [first].into(),
+        }
+    }
+
+    /// Creates a new [`NonEmptyVec`] from a [`Vec`], returning `None` if the [`Vec`] is empty.
+    pub fn from_vec(vec: Vec<T>) -> Option<Self> {
+        if vec.is_empty() {
+            None
+        } else {
+            Some(NonEmptyVec { inner: vec })
+        }
+    }
+
+    /// Returns the first element. This never fails since the list always has at least one element.
+    pub fn first(&self) -> &T {
+        &self.inner[0]
+    }
+
+    /// Returns the last element. This never fails since the list always has at least one element.
+    pub fn last(&self) -> &T {
+        &self.inner[self.inner.len() - 1]
+    }
+
+    /// Appends an element to the end of the list.
+    pub fn push(&mut self, value: T) {
+        self.inner.push(value);
+    }
+
+    /// Returns a mutable reference to the element at the index, if it exists.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.inner.get_mut(index)
+    }
+
+    /// Returns the underlying [`Vec`].
+    pub fn into_vec(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T> Deref for NonEmptyVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> From<NonEmptyVec<T>> for Vec<T> {
+    fn from(value: NonEmptyVec<T>) -> Self {
+        value.inner
+    }
+}
+
+impl<T> TryFrom<Vec<T>> for NonEmptyVec<T> {
+    type Error = Vec<T>;
+
+    fn try_from(value: Vec<T>) -> Result<Self, Self::Error> {
+        if value.is_empty() {
+            Err(value)
+        } else {
+            Ok(NonEmptyVec { inner: value })
+        }
+    }
+}
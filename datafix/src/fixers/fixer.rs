@@ -0,0 +1,80 @@
+use alloc::{boxed::Box, collections::btree_map::BTreeMap, sync::Arc};
+
+use crate::serialization::CodecOps;
+
+use super::TypeRewriteRule;
+
+/// A registry of [`TypeRewriteRule`]s keyed by the version they upgrade data to.
+///
+/// Rules are registered once with a [`DataFixerBuilder`] and can then be applied to many values.
+/// Cloning a `DataFixer` is cheap, since the rules are shared.
+pub struct DataFixer<O: CodecOps> {
+    rules: BTreeMap<u32, Arc<dyn TypeRewriteRule<O>>>,
+}
+
+impl<O: CodecOps> DataFixer<O> {
+    /// Returns the newest version a rule is registered for, or 0 if there are no rules.
+    pub fn current_version(&self) -> u32 {
+        self.rules.keys().next_back().copied().unwrap_or(0)
+    }
+
+    /// Upgrades `value` from `from_version` to the current version by applying every newer rule in order.
+    pub fn update(&self, ops: &O, mut value: O::T, from_version: u32) -> O::T {
+        // No rule can be newer than the last version.
+        let Some(next_version) = from_version.checked_add(1) else {
+            return value;
+        };
+        for (_, rule) in self.rules.range(next_version..) {
+            value = ops.repair(value, rule.as_ref());
+        }
+        value
+    }
+}
+
+impl<O: CodecOps> Clone for DataFixer<O> {
+    fn clone(&self) -> Self {
+        DataFixer {
+            rules: self.rules.clone(),
+        }
+    }
+}
+
+impl<O: CodecOps> FromIterator<(u32, Box<dyn TypeRewriteRule<O>>)> for DataFixer<O> {
+    fn from_iter<I: IntoIterator<Item = (u32, Box<dyn TypeRewriteRule<O>>)>>(iter: I) -> Self {
+        DataFixer {
+            rules: iter
+                .into_iter()
+                .map(|(version, rule)| (version, Arc::from(rule)))
+                .collect(),
+        }
+    }
+}
+
+/// Builds a [`DataFixer`] by registering rules one version at a time.
+pub struct DataFixerBuilder<O: CodecOps> {
+    rules: BTreeMap<u32, Arc<dyn TypeRewriteRule<O>>>,
+}
+
+impl<O: CodecOps> DataFixerBuilder<O> {
+    pub fn new() -> Self {
+        DataFixerBuilder {
+            rules: BTreeMap::new(),
+        }
+    }
+
+    /// Registers `rule` as the rule that upgrades data to `version`, replacing any rule already registered for it.
+    pub fn rule(mut self, version: u32, rule: impl TypeRewriteRule<O> + 'static) -> Self {
+        self.rules.insert(version, Arc::new(rule));
+        self
+    }
+
+    pub fn build(self) -> DataFixer<O> {
+        DataFixer { rules: self.rules }
+    }
+}
+
+impl<O: CodecOps> Default for DataFixerBuilder<O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
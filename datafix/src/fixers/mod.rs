@@ -1,9 +1,12 @@
+use alloc::boxed::Box;
 use core::marker::PhantomData;
 
 mod types;
 pub use types::*;
 mod rules;
 pub use rules::*;
+mod fixer;
+pub use fixer::*;
 
 use crate::serialization::CodecOps;
 
@@ -23,12 +26,33 @@ pub trait TypeRewriteRule<O: CodecOps> {
     }
 }
 
+impl<O: CodecOps, R: TypeRewriteRule<O> + ?Sized> TypeRewriteRule<O> for &R {
+    fn fix_data(&self, ops: O, value: O::T) -> O::T {
+        (**self).fix_data(ops, value)
+    }
+
+    fn fix_type(&self, ty: Type) -> Type {
+        (**self).fix_type(ty)
+    }
+}
+
+impl<O: CodecOps, R: TypeRewriteRule<O> + ?Sized> TypeRewriteRule<O> for Box<R> {
+    fn fix_data(&self, ops: O, value: O::T) -> O::T {
+        (**self).fix_data(ops, value)
+    }
+
+    fn fix_type(&self, ty: Type) -> Type {
+        (**self).fix_type(ty)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::{boxed::Box, vec::Vec};
     use json::JsonValue;
 
     use crate::{
-        fixers::{Type, TypeRewriteRule},
+        fixers::{ArrayType, DataFixerBuilder, ObjectType, Type, TypeRewriteRule},
         serialization::{CodecOps, json::JsonOps},
     };
 
@@ -89,6 +113,66 @@ mod tests {
         })
     }
 
+    #[test]
+    pub fn rename_field_in_list() {
+        let value = json::array![{ "name": "a", "hp": 1 }, { "name": "b", "hp": 2 }];
+
+        let rule = Rules::apply_to_list(Rules::sequence([
+            Box::new(Rules::rename_field("hp", "health")) as Box<dyn TypeRewriteRule<JsonOps>>,
+            Box::new(Rules::remove_field("name")),
+            Box::new(Rules::new_field(
+                "lives",
+                |ctx| ctx.create_int(3),
+                |_ctx| Type::Int,
+            )),
+        ]));
+
+        let fixed = JsonOps.repair(value, rule);
+        assert_eq!(
+            fixed,
+            json::array![{ "health": 1, "lives": 3 }, { "health": 2, "lives": 3 }]
+        );
+
+        let ty = Type::Array(ArrayType::new(Type::Object(
+            ObjectType::new().field("hp", Type::Int),
+        )));
+        let rule: Box<dyn TypeRewriteRule<JsonOps>> =
+            Box::new(Rules::apply_to_list(Rules::rename_field("hp", "health")));
+        let Type::Array(array) = rule.fix_type(ty) else {
+            panic!("expected an array type");
+        };
+        let Type::Object(object) = array.ty() else {
+            panic!("expected an object type");
+        };
+        assert!(object.get("health").is_ok());
+        assert!(object.get("hp").is_err());
+    }
+
+    #[test]
+    pub fn data_fixer() {
+        let fixer = DataFixerBuilder::new()
+            .rule(
+                2,
+                Rules::new_field("b", |ctx| ctx.create_int(2), |_| Type::Int),
+            )
+            .rule(1, Rules::rename_field("x", "a"))
+            .build();
+        assert_eq!(fixer.current_version(), 2);
+
+        let updated = fixer.update(&JsonOps, json::object! { "x": 1 }, 0);
+        assert_eq!(updated, json::object! { "a": 1, "b": 2 });
+        let updated = fixer.update(&JsonOps, json::object! { "x": 1 }, 1);
+        assert_eq!(updated, json::object! { "x": 1, "b": 2 });
+        let updated = fixer.clone().update(&JsonOps, json::object! { "x": 1 }, 2);
+        assert_eq!(updated, json::object! { "x": 1 });
+
+        let fixer = DataFixerBuilder::new()
+            .rule(u32::MAX, Rules::rename_field("x", "a"))
+            .build();
+        let updated = fixer.update(&JsonOps, json::object! { "x": 1 }, u32::MAX);
+        assert_eq!(updated, json::object! { "x": 1 });
+    }
+
     #[test]
     pub fn field_removal_rule() {
         let mut object = JsonValue::new_object();
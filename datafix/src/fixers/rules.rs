@@ -1,10 +1,14 @@
 use core::marker::PhantomData;
 
-use alloc::string::{String, ToString};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
 
-use crate::serialization::{CodecOps, Dynamic, MapView, MapViewMut};
+use crate::serialization::{CodecOps, Dynamic, ListView, MapView, MapViewMut};
 
-use super::{Type, TypeRewriteRule};
+use super::{ArrayType, Type, TypeRewriteRule};
 
 pub struct Rules;
 
@@ -29,6 +33,33 @@ impl Rules {
         }
     }
 
+    /// Creates a rule that moves the value of the field `from` into the field `to`, replacing any value already in `to`.
+    pub fn rename_field<O: CodecOps>(from: &str, to: &str) -> impl TypeRewriteRule<O> {
+        RenameFieldRule {
+            from: from.to_string(),
+            to: to.to_string(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a rule that applies `rule` to every element of a list.
+    pub fn apply_to_list<O: CodecOps>(rule: impl TypeRewriteRule<O>) -> impl TypeRewriteRule<O> {
+        ApplyRuleToListRule {
+            rule,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a rule that applies each of `rules` in order.
+    /// This is like chaining [`TypeRewriteRule::and_then`], but the number of rules can be decided at runtime.
+    pub fn sequence<O: CodecOps>(
+        rules: impl IntoIterator<Item = Box<dyn TypeRewriteRule<O>>>,
+    ) -> impl TypeRewriteRule<O> {
+        SequenceRule {
+            rules: rules.into_iter().collect(),
+        }
+    }
+
     pub fn apply_to_field<O: CodecOps>(
         field_name: &str,
         rule: impl TypeRewriteRule<O>,
@@ -112,10 +143,10 @@ impl<O: CodecOps, R: TypeRewriteRule<O>> TypeRewriteRule<O> for ApplyRuleToField
     }
 
     fn fix_type(&self, mut ty: Type) -> Type {
-        if let Type::Object(object) = &mut ty {
-            if let Ok(field) = object.remove(&self.field_name) {
-                object.insert(&self.field_name, self.rule.fix_type(field));
-            }
+        if let Type::Object(object) = &mut ty
+            && let Ok(field) = object.remove(&self.field_name)
+        {
+            object.insert(&self.field_name, self.rule.fix_type(field));
         }
         ty
     }
@@ -145,3 +176,72 @@ impl<O: CodecOps> TypeRewriteRule<O> for RemoveFieldRule<O> {
         ty
     }
 }
+
+pub struct RenameFieldRule<O: CodecOps> {
+    from: String,
+    to: String,
+    _phantom: PhantomData<O>,
+}
+
+impl<O: CodecOps> TypeRewriteRule<O> for RenameFieldRule<O> {
+    fn fix_data(&self, ops: O, mut value: O::T) -> O::T {
+        if let Ok(mut obj) = ops.get_map_mut(&mut value)
+            && let Ok(field) = obj.remove(&self.from)
+        {
+            obj.set(&self.to, field);
+        }
+        value
+    }
+
+    fn fix_type(&self, mut ty: Type) -> Type {
+        if let Type::Object(obj) = &mut ty
+            && let Ok(field) = obj.remove(&self.from)
+        {
+            obj.insert(&self.to, field);
+        }
+        ty
+    }
+}
+
+pub struct ApplyRuleToListRule<O: CodecOps, R: TypeRewriteRule<O>> {
+    rule: R,
+    _phantom: PhantomData<O>,
+}
+
+impl<O: CodecOps, R: TypeRewriteRule<O>> TypeRewriteRule<O> for ApplyRuleToListRule<O, R> {
+    fn fix_data(&self, ops: O, value: O::T) -> O::T {
+        let Ok(list) = ops.get_list(&value) else {
+            return value;
+        };
+        let elements = list
+            .into_iter()
+            .map(|element| self.rule.fix_data(ops.clone(), element))
+            .collect::<Vec<_>>();
+        ops.create_list(elements)
+    }
+
+    fn fix_type(&self, ty: Type) -> Type {
+        match ty {
+            Type::Array(array) => {
+                Type::Array(ArrayType::new(self.rule.fix_type(array.ty().clone())))
+            }
+            ty => ty,
+        }
+    }
+}
+
+pub struct SequenceRule<O: CodecOps> {
+    rules: Vec<Box<dyn TypeRewriteRule<O>>>,
+}
+
+impl<O: CodecOps> TypeRewriteRule<O> for SequenceRule<O> {
+    fn fix_data(&self, ops: O, value: O::T) -> O::T {
+        self.rules
+            .iter()
+            .fold(value, |value, rule| rule.fix_data(ops.clone(), value))
+    }
+
+    fn fix_type(&self, ty: Type) -> Type {
+        self.rules.iter().fold(ty, |ty, rule| rule.fix_type(ty))
+    }
+}
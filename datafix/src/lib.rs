@@ -44,10 +44,19 @@
 #![no_std]
 
 extern crate alloc;
+// Lets the paths generated by the derive macros resolve inside of this crate.
+extern crate self as datafix;
+#[cfg(feature = "std")]
+extern crate std;
 
+/// The module that contains collection types with invariants that codecs can enforce, such as `NonEmptyVec`.
+pub mod collections;
 /// The module responsible for types that fix up data.
 pub mod fixers;
 /// The module that contains `DataError`, the error value returned by methods related to this crate when things go wrong.
 pub mod result;
 /// The module responsible for transforming data between different types and representations.
 pub mod serialization;
+/// The module that contains helpers for testing codecs, such as [`testing::assert_round_trip`].
+#[cfg(feature = "test-util")]
+pub mod testing;
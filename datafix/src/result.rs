@@ -3,15 +3,54 @@ use core::{
     fmt::{Debug, Display},
 };
 
-use alloc::string::String;
+use alloc::{boxed::Box, string::String, vec::Vec};
 
 use crate::serialization::Context;
 
+/// The kinds of errors that can occur when working with data.
+/// Built-in codecs and ops construct the most specific variant they can, so errors can be matched on.
+#[derive(Clone)]
 pub enum DataError {
-    UnexpectedType { expected: String },
-    KeyNotFoundInMap { key: String },
-    ListIndexOutOfBounds { list_length: usize, index: usize },
-    Custom { message: String },
+    /// A value was not of the expected type, and the actual type is unknown.
+    UnexpectedType {
+        expected: String,
+    },
+    /// A value was not of the expected type.
+    TypeMismatch {
+        expected: String,
+        found: String,
+    },
+    KeyNotFoundInMap {
+        key: String,
+    },
+    ListIndexOutOfBounds {
+        list_length: usize,
+        index: usize,
+    },
+    /// A value was of the right type, but outside of the range of accepted values.
+    OutOfRange {
+        message: String,
+    },
+    /// A string selecting a variant, such as the tag of a [`Codecs::string_dispatch`], didn't match any of the known variants.
+    ///
+    /// [`Codecs::string_dispatch`]: crate::serialization::Codecs::string_dispatch
+    UnknownVariant {
+        found: String,
+        expected: Box<[String]>,
+    },
+    /// Decoding failed after `count` errors were recorded into the [`Context`] instead of being returned.
+    Collected {
+        count: usize,
+    },
+    /// Every alternative of a codec such as [`CodecAdapters::try_else`] failed, with the error of each alternative in order.
+    ///
+    /// [`CodecAdapters::try_else`]: crate::serialization::CodecAdapters::try_else
+    AllFailed {
+        sources: Vec<DataError>,
+    },
+    Custom {
+        message: String,
+    },
 }
 
 impl DataError {
@@ -27,6 +66,19 @@ impl DataError {
         }
     }
 
+    pub fn type_mismatch(expected: &str, found: &str) -> DataError {
+        DataError::TypeMismatch {
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+
+    pub fn out_of_range(message: &str) -> DataError {
+        DataError::OutOfRange {
+            message: message.into(),
+        }
+    }
+
     pub fn key_not_found(key: &str) -> DataError {
         DataError::KeyNotFoundInMap { key: key.into() }
     }
@@ -34,6 +86,31 @@ impl DataError {
     pub fn list_index_out_of_bounds(index: usize, list_length: usize) -> DataError {
         DataError::ListIndexOutOfBounds { list_length, index }
     }
+
+    /// Creates an error for a variant name that isn't one of `expected`, such as from the `from_ops_to_codec`
+    /// function of a [`Codecs::dispatch`](crate::serialization::Codecs::dispatch).
+    pub fn unknown_variant<S: Into<String>>(
+        found: &str,
+        expected: impl IntoIterator<Item = S>,
+    ) -> DataError {
+        DataError::UnknownVariant {
+            found: found.into(),
+            expected: expected.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Combines the errors of two alternatives that both failed. Errors that are already combined are flattened,
+    /// so a chain of alternatives produces a single list of errors.
+    pub fn all_failed(first: DataError, second: DataError) -> DataError {
+        let mut sources = Vec::new();
+        for error in [first, second] {
+            match error {
+                DataError::AllFailed { sources: nested } => sources.extend(nested),
+                error => sources.push(error),
+            }
+        }
+        DataError::AllFailed { sources }
+    }
 }
 
 impl Error for DataError {}
@@ -42,12 +119,33 @@ impl Display for DataError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             DataError::UnexpectedType { expected } => write!(f, "Expected type {}", expected),
+            DataError::TypeMismatch { expected, found } => {
+                write!(f, "Expected type {}, found {}", expected, found)
+            }
             DataError::KeyNotFoundInMap { key } => write!(f, "Expected key {} in map", key),
             DataError::ListIndexOutOfBounds { list_length, index } => write!(
                 f,
                 "List index {} out of bounds for length {}",
                 index, list_length
             ),
+            DataError::OutOfRange { message } => write!(f, "{}", message),
+            DataError::UnknownVariant { found, expected } => write!(
+                f,
+                "unknown variant \"{}\", expected one of [{}]",
+                found,
+                expected.join(", ")
+            ),
+            DataError::Collected { count } => write!(f, "{} errors occurred while decoding", count),
+            DataError::AllFailed { sources } => {
+                write!(f, "every alternative failed: [")?;
+                for (index, source) in sources.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", source)?;
+                }
+                write!(f, "]")
+            }
             DataError::Custom { message } => write!(f, "{}", message),
         }
     }
@@ -62,14 +160,19 @@ impl Debug for DataError {
 
 pub type DataResult<T> = Result<T, DataError>;
 
+#[derive(Clone)]
 pub struct CodecError {
     error: DataError,
-    span: Context,
+    // Boxed to keep `Result<T, CodecError>` small.
+    span: Box<Context>,
 }
 
 impl CodecError {
     pub fn new(error: DataError, span: Context) -> Self {
-        CodecError { error, span }
+        CodecError {
+            error,
+            span: Box::new(span.without_hook()),
+        }
     }
 
     pub fn error(&self) -> &DataError {
@@ -91,4 +194,17 @@ impl Debug for CodecError {
     }
 }
 
+impl Display for CodecError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let path = self.span.path();
+        if path.is_empty() {
+            Display::fmt(&self.error, f)
+        } else {
+            write!(f, "{}: {}", path, self.error)
+        }
+    }
+}
+
+impl Error for CodecError {}
+
 pub type CodecResult<T> = Result<T, CodecError>;
@@ -1,18 +1,47 @@
-use core::{fmt::Debug, marker::PhantomData, ops::RangeBounds};
+#[cfg(not(feature = "std"))]
+use core::cell::OnceCell;
+#[cfg(feature = "net")]
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use core::{
+    any::Any,
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    num::{
+        NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU8, NonZeroU16, NonZeroU32,
+        NonZeroU64,
+    },
+    ops::{Bound, RangeBounds, RangeInclusive},
+    str::FromStr,
+    time::Duration,
+};
+#[cfg(feature = "path")]
+use std::path::PathBuf;
 
 use alloc::{
+    borrow::{Cow, ToOwned},
     boxed::Box,
-    collections::btree_map::BTreeMap,
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
     format,
     string::{String, ToString},
     sync::Arc,
+    vec,
     vec::Vec,
 };
 use either::Either;
+#[cfg(feature = "std")]
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{OnceLock, Weak},
+};
 
 use crate::{
+    collections::NonEmptyVec,
+    fixers::DataFixer,
     result::{DataError, DataResult},
-    serialization::{Codec, CodecAdapters, CodecOps, Context, DefaultCodec, ListView, MapView},
+    serialization::{
+        Codec, CodecAdapters, CodecOps, Codecs, Context, DefaultCodec, Dynamic, ListView, MapView,
+        MapViewMut, Schema, SchemaField,
+    },
 };
 
 #[derive(Clone, Debug)]
@@ -26,6 +55,10 @@ impl<O: CodecOps> Codec<String, O> for StringCodec {
     fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<String> {
         ops.get_string(value)
     }
+
+    fn schema(&self) -> Schema {
+        Schema::String
+    }
 }
 
 impl<O: CodecOps> DefaultCodec<O> for String {
@@ -35,780 +68,5268 @@ impl<O: CodecOps> DefaultCodec<O> for String {
 }
 
 #[derive(Clone, Debug)]
-pub(crate) struct BoolCodec;
+pub(crate) struct CowStrCodec;
 
-impl<O: CodecOps> Codec<bool, O> for BoolCodec {
-    fn encode(&self, ops: &O, value: &bool, _ctx: &mut Context) -> DataResult<O::T> {
-        Ok(ops.create_boolean(value))
+impl<O: CodecOps> Codec<Cow<'static, str>, O> for CowStrCodec {
+    fn encode(&self, ops: &O, value: &Cow<'static, str>, _ctx: &mut Context) -> DataResult<O::T> {
+        Ok(ops.create_string(value))
     }
 
-    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<bool> {
-        ops.get_boolean(value)
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<Cow<'static, str>> {
+        Ok(Cow::Owned(ops.get_str(value)?.into_owned()))
     }
-}
 
-impl<O: CodecOps> DefaultCodec<O> for bool {
-    fn codec() -> impl Codec<Self, O> {
-        BoolCodec
+    fn schema(&self) -> Schema {
+        Schema::String
     }
 }
 
-pub(crate) struct ListCodec<T, C: Codec<T, O>, O: CodecOps> {
+pub(crate) struct CowCodec<T: ToOwned + ?Sized + 'static, C: Codec<T::Owned, O>, O: CodecOps> {
     pub(crate) inner: C,
-    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+    pub(crate) _phantom: PhantomData<fn() -> (Cow<'static, T>, O)>,
 }
 
-impl<T, C: Codec<T, O>, O: CodecOps> Codec<Vec<T>, O> for ListCodec<T, C, O> {
-    fn encode(&self, ops: &O, value: &Vec<T>, ctx: &mut Context) -> DataResult<O::T> {
-        let mut list = Vec::new();
-        for element in value.iter().enumerate() {
-            ctx.push_array(element.0);
-            list.push(self.inner.encode(ops, element.1, ctx)?);
-            ctx.pop();
+impl<T: ToOwned + ?Sized + 'static, C: Codec<T::Owned, O>, O: CodecOps> Codec<Cow<'static, T>, O>
+    for CowCodec<T, C, O>
+{
+    fn encode(&self, ops: &O, value: &Cow<'static, T>, ctx: &mut Context) -> DataResult<O::T> {
+        match value {
+            Cow::Owned(owned) => self.inner.encode(ops, owned, ctx),
+            Cow::Borrowed(borrowed) => self.inner.encode(ops, &(*borrowed).to_owned(), ctx),
         }
-        Ok(ops.create_list(list))
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Vec<T>> {
-        let list = ops.get_list(value)?;
-        let mut vec = Vec::new();
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Cow<'static, T>> {
+        self.inner.decode(ops, value, ctx).map(Cow::Owned)
+    }
 
-        for item in list.into_iter().enumerate() {
-            ctx.push_array(item.0);
-            vec.push(self.inner.decode(ops, &item.1, ctx)?);
-            ctx.pop();
-        }
-        Ok(vec)
+    fn schema(&self) -> Schema {
+        self.inner.schema()
     }
 }
 
-pub(crate) struct XMapCodec<OLT, NT, C, F1, F2, O: CodecOps>
-where
-    C: Codec<OLT, O>,
-    F1: Fn(&OLT) -> NT,
-    F2: Fn(&NT) -> OLT,
-{
-    pub(crate) inner: C,
-    pub(crate) f1: F1,
-    pub(crate) f2: F2,
-    pub(crate) _phantom: PhantomData<fn() -> (OLT, NT, O)>,
-}
+#[derive(Clone, Debug)]
+pub(crate) struct BoolCodec;
 
-impl<OLT, NT, C, F1, F2, O: CodecOps> Codec<NT, O> for XMapCodec<OLT, NT, C, F1, F2, O>
-where
-    C: Codec<OLT, O>,
-    F1: Fn(&OLT) -> NT,
-    F2: Fn(&NT) -> OLT,
-{
-    fn encode(&self, ops: &O, value: &NT, ctx: &mut Context) -> DataResult<O::T> {
-        self.inner.encode(ops, &(self.f2)(value), ctx)
+impl<O: CodecOps> Codec<bool, O> for BoolCodec {
+    fn encode(&self, ops: &O, value: &bool, _ctx: &mut Context) -> DataResult<O::T> {
+        Ok(ops.create_boolean(value))
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<NT> {
-        Ok((self.f1)(&self.inner.decode(ops, value, ctx)?))
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<bool> {
+        ops.get_boolean(value)
     }
-}
 
-pub(crate) struct FlatXMapCodec<OLT, NT, C, F1, F2, O: CodecOps>
-where
-    C: Codec<OLT, O>,
-    F1: Fn(&OLT) -> DataResult<NT>,
-    F2: Fn(&NT) -> DataResult<OLT>,
-{
-    pub(crate) inner: C,
-    pub(crate) f1: F1,
-    pub(crate) f2: F2,
-    pub(crate) _phantom: PhantomData<fn() -> (OLT, NT, O)>,
+    fn schema(&self) -> Schema {
+        Schema::Boolean
+    }
 }
 
-impl<OLT, NT, C, F1, F2, O: CodecOps> Codec<NT, O> for FlatXMapCodec<OLT, NT, C, F1, F2, O>
-where
-    C: Codec<OLT, O>,
-    F1: Fn(&OLT) -> DataResult<NT>,
-    F2: Fn(&NT) -> DataResult<OLT>,
-{
-    fn encode(&self, ops: &O, value: &NT, ctx: &mut Context) -> DataResult<O::T> {
-        self.inner.encode(ops, &(self.f2)(value)?, ctx)
+impl<O: CodecOps> DefaultCodec<O> for bool {
+    fn codec() -> impl Codec<Self, O> {
+        BoolCodec
     }
+}
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<NT> {
-        (self.f1)(&self.inner.decode(ops, value, ctx)?)
+#[derive(Clone, Debug)]
+pub(crate) struct CharCodec;
+
+impl<O: CodecOps> Codec<char, O> for CharCodec {
+    fn encode(&self, ops: &O, value: &char, _ctx: &mut Context) -> DataResult<O::T> {
+        let mut buf = [0; 4];
+        Ok(ops.create_string(value.encode_utf8(&mut buf)))
     }
-}
 
-pub(crate) struct PairCodec<L, R, Lc: Codec<L, O>, Rc: Codec<R, O>, O: CodecOps> {
-    pub(crate) left: Lc,
-    pub(crate) right: Rc,
-    pub(crate) _phantom: PhantomData<fn() -> (L, R, O)>,
-}
-impl<L, R, Lc: Codec<L, O>, Rc: Codec<R, O>, O: CodecOps> Codec<(L, R), O>
-    for PairCodec<L, R, Lc, Rc, O>
-{
-    fn encode(&self, ops: &O, value: &(L, R), ctx: &mut Context) -> DataResult<O::T> {
-        Ok(ops.create_map([
-            ("left".to_string(), self.left.encode(ops, &value.0, ctx)?),
-            ("right".to_string(), self.right.encode(ops, &value.1, ctx)?),
-        ]))
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<char> {
+        let string = ops.get_str(value)?;
+        let mut chars = string.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(DataError::new_custom(&format!(
+                "expected a string of exactly 1 character, found \"{}\"",
+                string
+            ))),
+        }
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<(L, R)> {
-        let obj = ops.get_map(value)?;
-        let left = obj.get("left")?;
-        let p1 = self.left.decode(ops, left, ctx)?;
-        let right = obj.get("right")?;
-        let p2 = self.right.decode(ops, right, ctx)?;
-        Ok((p1, p2))
+    fn schema(&self) -> Schema {
+        Schema::String
     }
 }
 
-pub(crate) struct BoundedCodec<T: PartialOrd, C: Codec<T, O>, R: RangeBounds<T>, O: CodecOps> {
-    pub(crate) codec: C,
-    pub(crate) range: R,
-    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+impl<O: CodecOps> DefaultCodec<O> for char {
+    fn codec() -> impl Codec<Self, O> {
+        CharCodec
+    }
 }
 
-impl<T: PartialOrd + Debug, C: Codec<T, O>, R: RangeBounds<T>, O: CodecOps> Codec<T, O>
-    for BoundedCodec<T, C, R, O>
-{
-    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
-        if !self.range.contains(value) {
-            Err(DataError::new_custom(&alloc::format!(
-                "value must be in bounds of {:?} to {:?}",
-                self.range.start_bound(),
-                self.range.end_bound()
-            )))
+#[derive(Clone, Debug)]
+pub(crate) struct DurationMillisCodec;
+
+impl<O: CodecOps> Codec<Duration, O> for DurationMillisCodec {
+    fn encode(&self, ops: &O, value: &Duration, _ctx: &mut Context) -> DataResult<O::T> {
+        let millis = i64::try_from(value.as_millis())
+            .map_err(|_| DataError::out_of_range("duration is too large to be encoded"))?;
+        let nanos = value.subsec_nanos() % 1_000_000;
+        if nanos == 0 {
+            Ok(ops.create_long(&millis))
         } else {
-            self.codec.encode(ops, value, ctx)
+            Ok(ops.create_double(&(millis as f64 + nanos as f64 / 1_000_000.0)))
         }
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
-        let decoded = self.codec.decode(ops, value, ctx)?;
-        if self.range.contains(&decoded) {
-            Ok(decoded)
-        } else {
-            Err(DataError::new_custom(&alloc::format!(
-                "value must be in bounds of {:?} to {:?}",
-                self.range.start_bound(),
-                self.range.end_bound()
-            )))
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<Duration> {
+        let millis = ops.get_double(value)?;
+        if !millis.is_finite() || millis < 0.0 || millis >= u64::MAX as f64 {
+            return Err(DataError::out_of_range(&format!(
+                "{} is not a valid duration in milliseconds",
+                millis
+            )));
         }
+        let whole = millis.trunc();
+        let nanos = ((millis - whole) * 1_000_000.0).round() as u64;
+        Ok(Duration::from_millis(whole as u64) + Duration::from_nanos(nanos))
     }
-}
 
-pub struct DynamicCodec<T, O: CodecOps> {
-    pub(crate) codec: Box<dyn Codec<T, O>>,
+    fn schema(&self) -> Schema {
+        Schema::number()
+    }
 }
 
-impl<T, O: CodecOps> Codec<T, O> for DynamicCodec<T, O> {
-    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
-        self.codec.as_ref().encode(ops, value, ctx)
+#[derive(Clone, Debug)]
+pub(crate) struct DurationSecsCodec;
+
+impl<O: CodecOps> Codec<Duration, O> for DurationSecsCodec {
+    fn encode(&self, ops: &O, value: &Duration, _ctx: &mut Context) -> DataResult<O::T> {
+        Ok(ops.create_double(&value.as_secs_f64()))
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
-        self.codec.as_ref().decode(ops, value, ctx)
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<Duration> {
+        let secs = ops.get_double(value)?;
+        Duration::try_from_secs_f64(secs).map_err(|_| {
+            DataError::out_of_range(&format!("{} is not a valid duration in seconds", secs))
+        })
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::number()
     }
 }
 
-pub struct ArcCodec<T, O: CodecOps> {
-    pub(crate) codec: Arc<dyn Codec<T, O>>,
+/// Encodes a value as a string using its [`Display`] implementation, and decodes it using its [`FromStr`] implementation.
+pub(crate) struct FromStrCodec<T> {
+    pub(crate) _phantom: PhantomData<fn() -> T>,
 }
 
-impl<T, O: CodecOps> Clone for ArcCodec<T, O> {
-    fn clone(&self) -> Self {
-        Self {
-            codec: self.codec.clone(),
-        }
+impl<T: Display + FromStr, O: CodecOps> Codec<T, O> for FromStrCodec<T>
+where
+    T::Err: Display,
+{
+    fn encode(&self, ops: &O, value: &T, _ctx: &mut Context) -> DataResult<O::T> {
+        Ok(ops.create_string(&value.to_string()))
     }
-}
 
-impl<T, O: CodecOps> Codec<T, O> for ArcCodec<T, O> {
-    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
-        self.codec.as_ref().encode(ops, value, ctx)
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<T> {
+        let string = ops.get_str(value)?;
+        string.parse().map_err(|e: T::Err| {
+            DataError::new_custom(&format!("failed to parse \"{}\": {}", string, e))
+        })
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
-        self.codec.as_ref().decode(ops, value, ctx)
+    fn schema(&self) -> Schema {
+        Schema::String
     }
 }
 
-pub struct FnCodec<T, O: CodecOps> {
-    pub(crate) encode: Box<dyn Fn(&O, &T, &mut Context) -> DataResult<O::T>>,
-    pub(crate) decode: Box<dyn Fn(&O, &O::T, &mut Context) -> DataResult<T>>,
+pub(crate) struct LenientNumberCodec<N, C: Codec<N, O>, O: CodecOps> {
+    pub(crate) inner: C,
+    pub(crate) _phantom: PhantomData<fn() -> (N, O)>,
 }
 
-impl<T, O: CodecOps> Codec<T, O> for FnCodec<T, O> {
-    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
-        (self.encode)(ops, value, ctx)
+impl<N: Display + FromStr, C: Codec<N, O>, O: CodecOps> Codec<N, O> for LenientNumberCodec<N, C, O>
+where
+    N::Err: Display,
+{
+    fn encode(&self, ops: &O, value: &N, ctx: &mut Context) -> DataResult<O::T> {
+        self.inner.encode(ops, value, ctx)
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
-        (self.decode)(ops, value, ctx)
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<N> {
+        if ops.get_str(value).is_ok() {
+            FromStrCodec {
+                _phantom: PhantomData,
+            }
+            .decode(ops, value, ctx)
+        } else {
+            self.inner.decode(ops, value, ctx)
+        }
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::OneOf(vec![self.inner.schema(), Schema::String])
     }
 }
 
-pub struct BoxCodec<T, O: CodecOps, C: Codec<T, O>> {
-    pub(crate) inner: C,
-    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+macro_rules! make_from_str_codec {
+    ($($t:ty)*) => {
+        $(impl<O: CodecOps> DefaultCodec<O> for $t {
+            fn codec() -> impl Codec<Self, O> {
+                FromStrCodec {
+                    _phantom: PhantomData,
+                }
+            }
+        })*
+    };
 }
 
-impl<T, O: CodecOps, C: Codec<T, O>> Codec<Box<T>, O> for BoxCodec<T, O, C> {
-    fn encode(&self, ops: &O, value: &Box<T>, ctx: &mut Context) -> DataResult<O::T> {
-        self.inner.encode(ops, value, ctx)
+// 128-bit integers can not be represented exactly by every format, so they are encoded as decimal strings.
+make_from_str_codec! {
+    i128 u128
+}
+
+#[cfg(feature = "net")]
+make_from_str_codec! {
+    IpAddr Ipv4Addr Ipv6Addr SocketAddr SocketAddrV4 SocketAddrV6
+}
+
+/// Encodes a path as a string. Paths that are not valid UTF-8 can not be represented exactly, so encoding them fails
+/// instead of silently replacing the invalid parts.
+#[cfg(feature = "path")]
+#[derive(Clone, Debug)]
+pub(crate) struct PathBufCodec;
+
+#[cfg(feature = "path")]
+impl<O: CodecOps> Codec<PathBuf, O> for PathBufCodec {
+    fn encode(&self, ops: &O, value: &PathBuf, _ctx: &mut Context) -> DataResult<O::T> {
+        let string = value.to_str().ok_or_else(|| {
+            DataError::new_custom(&format!(
+                "path \"{}\" is not valid UTF-8",
+                value.to_string_lossy()
+            ))
+        })?;
+        Ok(ops.create_string(string))
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Box<T>> {
-        self.inner.decode(ops, value, ctx).map(|x| Box::new(x))
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<PathBuf> {
+        Ok(PathBuf::from(ops.get_str(value)?.as_ref()))
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::String
     }
 }
 
-pub struct TryElseCodec<T, O: CodecOps, Lc: Codec<T, O>, Rc: Codec<T, O>> {
-    pub(crate) lc: Lc,
-    pub(crate) rc: Rc,
-    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+#[cfg(feature = "path")]
+impl<O: CodecOps> DefaultCodec<O> for PathBuf {
+    fn codec() -> impl Codec<Self, O> {
+        PathBufCodec
+    }
 }
 
-impl<T, O: CodecOps, Lc: Codec<T, O>, Rc: Codec<T, O>> Codec<T, O> for TryElseCodec<T, O, Lc, Rc> {
-    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
-        let t1 = self.lc.encode(ops, value, ctx);
-        match t1 {
-            Err(_) => {
-                let t2 = self.rc.encode(ops, value, ctx);
-                match t2 {
-                    Ok(v) => Ok(v),
-                    Err(e) => Err(e),
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes a byte array as a padded base64 string using the standard alphabet.
+#[derive(Clone, Debug)]
+pub(crate) struct Base64Codec;
+
+impl Base64Codec {
+    fn encode_base64(bytes: &[u8]) -> String {
+        let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let b = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+            let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(BASE64_ALPHABET[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+                } else {
+                    out.push('=');
                 }
             }
-            Ok(v) => Ok(v),
         }
+        out
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
-        let t1 = self.lc.decode(ops, value, ctx);
-        match t1 {
-            Err(_) => {
-                let t2 = self.rc.decode(ops, value, ctx);
-                match t2 {
-                    Ok(v) => Ok(v),
-                    Err(e) => Err(e),
-                }
+    fn decode_base64(string: &str) -> DataResult<Vec<u8>> {
+        let input = string.as_bytes();
+        if !input.len().is_multiple_of(4) {
+            return Err(DataError::new_custom(
+                "base64 string length must be a multiple of 4",
+            ));
+        }
+        let mut out = Vec::with_capacity(input.len() / 4 * 3);
+        for (index, chunk) in input.chunks(4).enumerate() {
+            let is_last = index == input.len() / 4 - 1;
+            let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+            if padding > 2 || (padding > 0 && !is_last) {
+                return Err(DataError::new_custom("invalid padding in base64 string"));
             }
-            Ok(v) => Ok(v),
+            let mut n = 0u32;
+            for c in &chunk[..4 - padding] {
+                let Some(value) = BASE64_ALPHABET.iter().position(|x| x == c) else {
+                    return Err(DataError::new_custom(&format!(
+                        "invalid character '{}' in base64 string",
+                        *c as char
+                    )));
+                };
+                n = (n << 6) | value as u32;
+            }
+            n <<= 6 * padding as u32;
+            out.extend_from_slice(&[(n >> 16) as u8, (n >> 8) as u8, n as u8][..3 - padding]);
         }
+        Ok(out)
     }
+}
 
-    fn debug(&self) -> String {
-        format!("({}) orelse ({})", self.lc.debug(), self.rc.debug())
+impl<O: CodecOps> Codec<Vec<u8>, O> for Base64Codec {
+    fn encode(&self, ops: &O, value: &Vec<u8>, _ctx: &mut Context) -> DataResult<O::T> {
+        Ok(ops.create_string(&Self::encode_base64(value)))
     }
-}
 
-pub struct EitherCodec<T, O: CodecOps, T2, Lc: Codec<T, O>, Rc: Codec<T2, O>> {
-    pub(crate) lc: Lc,
-    pub(crate) rc: Rc,
-    pub(crate) _phantom: PhantomData<fn() -> (T, O, T2)>,
-}
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<Vec<u8>> {
+        Self::decode_base64(&ops.get_str(value)?)
+    }
 
-impl<T, O: CodecOps, T2, Lc: Codec<T, O>, Rc: Codec<T2, O>> Codec<Either<T, T2>, O>
-    for EitherCodec<T, O, T2, Lc, Rc>
-{
-    fn encode(&self, ops: &O, value: &Either<T, T2>, ctx: &mut Context) -> DataResult<O::T> {
-        match value {
-            Either::Left(value) => self.lc.encode(ops, value, ctx),
-            Either::Right(value) => self.rc.encode(ops, value, ctx),
-        }
+    fn schema(&self) -> Schema {
+        Schema::String
     }
+}
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Either<T, T2>> {
-        match self.lc.decode(ops, value, ctx) {
-            Ok(v) => Ok(Either::Left(v)),
-            Err(_) => match self.rc.decode(ops, value, ctx) {
-                Ok(v) => Ok(Either::Right(v)),
-                Err(e) => Err(e),
-            },
-        }
+/// Encodes each of `elements` with `encode` under its index, and creates a list of the results.
+fn encode_list<E, O: CodecOps>(
+    ops: &O,
+    elements: impl IntoIterator<Item = E>,
+    ctx: &mut Context,
+    mut encode: impl FnMut(E, &mut Context) -> DataResult<O::T>,
+) -> DataResult<O::T> {
+    // Elements are encoded as `create_list` consumes them, so no intermediate list is built.
+    // The first error stops the iterator and is returned once `create_list` is done.
+    let mut error = None;
+    let list = ops.create_list(
+        elements
+            .into_iter()
+            .enumerate()
+            .map_while(|(index, element)| {
+                ctx.push_array(index);
+                match encode(element, ctx) {
+                    Ok(encoded) => {
+                        ctx.pop();
+                        Some(encoded)
+                    }
+                    Err(e) => {
+                        error = Some(e);
+                        None
+                    }
+                }
+            }),
+    );
+    match error {
+        Some(e) => Err(e),
+        None => Ok(list),
     }
 }
 
-pub struct OrElseCodec<T, O: CodecOps, C: Codec<T, O>, F: Fn() -> T> {
-    pub(crate) codec: C,
-    pub(crate) default: F,
+pub(crate) struct ListCodec<T, C: Codec<T, O>, O: CodecOps> {
+    pub(crate) inner: C,
     pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
 }
 
-impl<T, O: CodecOps, C: Codec<T, O>, F: Fn() -> T> Codec<T, O> for OrElseCodec<T, O, C, F> {
-    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
-        self.codec.encode(ops, value, ctx)
+impl<T, C: Codec<T, O>, O: CodecOps> Codec<Vec<T>, O> for ListCodec<T, C, O> {
+    fn encode(&self, ops: &O, value: &Vec<T>, ctx: &mut Context) -> DataResult<O::T> {
+        encode_list(ops, value, ctx, |element, ctx| {
+            self.inner.encode(ops, element, ctx)
+        })
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
-        Ok(self
-            .codec
-            .decode(ops, value, ctx)
-            .unwrap_or_else(|_| (self.default)()))
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Vec<T>> {
+        let list = ops.get_list(value)?;
+        let mut vec = Vec::with_capacity(list.len());
+
+        // Elements are read by reference, since `ListView::into_iter` may clone the whole list.
+        for index in 0..list.len() {
+            ctx.push_array(index);
+            vec.push(self.inner.decode(ops, list.get(index)?, ctx)?);
+            ctx.pop();
+        }
+        Ok(vec)
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::List(Box::new(self.inner.schema()))
     }
 }
 
-pub struct DispatchCodec<
-    T,
-    O: CodecOps,
-    E: Fn(&T) -> DataResult<DynamicCodec<T, O>>,
-    F: Fn(&O, &O::T) -> DataResult<DynamicCodec<T, O>>,
-> {
-    pub(crate) from_type_to_codec: E,
-    pub(crate) from_ops_to_codec: F,
-    pub(crate) _phantom: PhantomData<(T, O)>,
+pub(crate) struct BoundedListCodec<T, C: Codec<T, O>, R: RangeBounds<usize>, O: CodecOps> {
+    pub(crate) inner: C,
+    pub(crate) range: R,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
 }
 
-impl<
-    T,
-    O: CodecOps,
-    E: Fn(&T) -> DataResult<DynamicCodec<T, O>>,
-    F: Fn(&O, &O::T) -> DataResult<DynamicCodec<T, O>>,
-> Codec<T, O> for DispatchCodec<T, O, E, F>
+impl<T, C: Codec<T, O>, R: RangeBounds<usize>, O: CodecOps> BoundedListCodec<T, C, R, O> {
+    fn check_length(&self, length: usize) -> DataResult<()> {
+        if self.range.contains(&length) {
+            Ok(())
+        } else {
+            Err(DataError::out_of_range(&format!(
+                "list length {} must be in range {}",
+                length,
+                describe_range(&self.range)
+            )))
+        }
+    }
+}
+
+impl<T, C: Codec<T, O>, R: RangeBounds<usize>, O: CodecOps> Codec<Vec<T>, O>
+    for BoundedListCodec<T, C, R, O>
 {
-    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
-        (self.from_type_to_codec)(value)?.encode(ops, value, ctx)
+    fn encode(&self, ops: &O, value: &Vec<T>, ctx: &mut Context) -> DataResult<O::T> {
+        self.check_length(value.len())?;
+        encode_list(ops, value.iter(), ctx, |element, ctx| {
+            self.inner.encode(ops, element, ctx)
+        })
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
-        (self.from_ops_to_codec)(ops, value)?.decode(ops, value, ctx)
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Vec<T>> {
+        let list = ops.get_list(value)?;
+        // The length is checked before any element is read, so an oversized list is rejected cheaply.
+        self.check_length(list.len())?;
+        let mut vec = Vec::with_capacity(list.len());
+        for index in 0..list.len() {
+            ctx.push_array(index);
+            vec.push(self.inner.decode(ops, list.get(index)?, ctx)?);
+            ctx.pop();
+        }
+        Ok(vec)
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::List(Box::new(self.inner.schema()))
     }
 }
 
-pub struct ConstantCodec<T: Clone + PartialEq + Debug, C: Codec<T, O>, O: CodecOps> {
-    pub(crate) codec: C,
-    pub(crate) constant: T,
-    pub(crate) _phantom: PhantomData<O>,
+pub(crate) struct NonEmptyListCodec<T, C: Codec<T, O>, O: CodecOps> {
+    pub(crate) inner: C,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
 }
 
-impl<T: Clone + PartialEq + Debug, C: Codec<T, O>, O: CodecOps> Codec<T, O>
-    for ConstantCodec<T, C, O>
-{
-    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<<O as CodecOps>::T> {
-        if value != &self.constant {
-            return Err(DataError::new_custom(&format!(
-                "expected constant {:?}, found {:?}",
-                self.constant, value
-            )));
-        }
-        self.codec.encode(ops, value, ctx)
+impl<T, C: Codec<T, O>, O: CodecOps> Codec<NonEmptyVec<T>, O> for NonEmptyListCodec<T, C, O> {
+    fn encode(&self, ops: &O, value: &NonEmptyVec<T>, ctx: &mut Context) -> DataResult<O::T> {
+        encode_list(ops, value.iter(), ctx, |element, ctx| {
+            self.inner.encode(ops, element, ctx)
+        })
     }
 
-    fn decode(&self, ops: &O, value: &<O as CodecOps>::T, ctx: &mut Context) -> DataResult<T> {
-        let value = self.codec.decode(ops, value, ctx)?;
-        if value != self.constant {
-            return Err(DataError::new_custom(&format!(
-                "expected constant {:?}, found {:?}",
-                self.constant, value
-            )));
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<NonEmptyVec<T>> {
+        let mut vec = Vec::new();
+        for (index, item) in ops.get_list(value)?.into_iter().enumerate() {
+            ctx.push_array(index);
+            vec.push(self.inner.decode(ops, &item, ctx)?);
+            ctx.pop();
         }
-        Ok(value)
+        NonEmptyVec::from_vec(vec)
+            .ok_or_else(|| DataError::new_custom("list must contain at least 1 element"))
     }
 
-    fn debug(&self) -> String {
-        format!("Constant({:?})", self.constant)
+    fn schema(&self) -> Schema {
+        Schema::List(Box::new(self.inner.schema()))
     }
 }
 
-macro_rules! make_numeric_codec {
-    (
-        $({$t:ty, $struct_name:ident, $get_name:ident, $make_name:ident})*
-        $(;)?
-    ) => {
-        $(pub struct $struct_name<O: CodecOps> {
-            _phantom: PhantomData<fn() -> O>,
+impl<T: DefaultCodec<O>, O: CodecOps> DefaultCodec<O> for NonEmptyVec<T> {
+    fn codec() -> impl Codec<Self, O> {
+        NonEmptyListCodec {
+            inner: T::codec(),
+            _phantom: PhantomData,
         }
+    }
+}
 
-        impl<O: CodecOps> Codec<$t, O> for $struct_name<O> {
-            fn encode(&self, ops: &O, value: &$t, _ctx: &mut Context) -> DataResult<O::T> {
-                Ok(ops.$make_name(value))
-            }
+/// A collection of unique values that a [`SetCodec`] can encode and decode.
+pub trait SetCollection<T> {
+    /// Creates an empty set.
+    fn empty() -> Self;
+    /// Inserts a value into the set, returning `false` if the value was already present.
+    fn insert_value(&mut self, value: T) -> bool;
+    /// Returns an iterator over the values of the set.
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+}
 
-            fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<$t> {
-                ops.$get_name(value)
+impl<T: Ord> SetCollection<T> for BTreeSet<T> {
+    fn empty() -> Self {
+        BTreeSet::new()
+    }
+
+    fn insert_value(&mut self, value: T) -> bool {
+        self.insert(value)
+    }
+
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Eq + core::hash::Hash, S: core::hash::BuildHasher + Default> SetCollection<T>
+    for HashSet<T, S>
+{
+    fn empty() -> Self {
+        HashSet::default()
+    }
+
+    fn insert_value(&mut self, value: T) -> bool {
+        self.insert(value)
+    }
+
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+}
+
+pub struct SetCodec<T, C: Codec<T, O>, S: SetCollection<T>, O: CodecOps> {
+    pub(crate) inner: C,
+    pub(crate) strict: bool,
+    pub(crate) _phantom: PhantomData<fn() -> (T, S, O)>,
+}
+
+impl<T, C: Codec<T, O>, S: SetCollection<T>, O: CodecOps> SetCodec<T, C, S, O> {
+    /// Makes decoding fail if the list contains duplicate elements, instead of silently merging them.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+}
+
+impl<T, C: Codec<T, O>, S: SetCollection<T>, O: CodecOps> Codec<S, O> for SetCodec<T, C, S, O> {
+    fn encode(&self, ops: &O, value: &S, ctx: &mut Context) -> DataResult<O::T> {
+        encode_list(ops, value.values(), ctx, |element, ctx| {
+            self.inner.encode(ops, element, ctx)
+        })
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<S> {
+        let mut set = S::empty();
+        for (index, item) in ops.get_list(value)?.into_iter().enumerate() {
+            ctx.push_array(index);
+            let decoded = self.inner.decode(ops, &item, ctx)?;
+            if !set.insert_value(decoded) && self.strict {
+                return Err(DataError::new_custom("set contains duplicate elements"));
             }
+            ctx.pop();
         }
+        Ok(set)
+    }
 
-        impl<O: CodecOps> DefaultCodec<O> for $t {
-            fn codec() -> impl Codec<Self, O> {
-                $struct_name {
-                    _phantom: PhantomData,
-                }
-            }
-        })*
-    };
+    fn schema(&self) -> Schema {
+        Schema::List(Box::new(self.inner.schema()))
+    }
 }
 
-make_numeric_codec! {
-    {f32, F32Codec, get_float, create_float}
-    {f64, F64Codec, get_double, create_double}
+impl<T: DefaultCodec<O> + Ord, O: CodecOps> DefaultCodec<O> for BTreeSet<T> {
+    fn codec() -> impl Codec<Self, O> {
+        Codecs::btree_set(T::codec())
+    }
+}
 
-    {i8, I8Codec, get_byte, create_byte}
-    {i16, I16Codec, get_short, create_short}
-    {i32, I32Codec, get_int, create_int}
-    {i64, I64Codec, get_long, create_long}
+#[cfg(feature = "std")]
+impl<T: DefaultCodec<O> + Eq + core::hash::Hash, O: CodecOps, S: core::hash::BuildHasher + Default>
+    DefaultCodec<O> for HashSet<T, S>
+{
+    fn codec() -> impl Codec<Self, O> {
+        SetCodec {
+            inner: T::codec(),
+            strict: false,
+            _phantom: PhantomData,
+        }
+    }
 }
 
-macro_rules! make_unsigned_codec {
-    (
-        $(
-            {$from:ty; $to:ty}
-        )*
-    ) => {
-        $(impl<O: CodecOps> DefaultCodec<O> for $to {
-            fn codec() -> impl Codec<Self, O> {
-                <$from>::codec().xmap(|x| *x as $to, |x| *x as $from)
-            }
-        })*
-    };
+impl<T: DefaultCodec<O>, O: CodecOps> DefaultCodec<O> for Vec<T> {
+    fn codec() -> impl Codec<Self, O> {
+        T::codec().list_of()
+    }
 }
 
-make_unsigned_codec! {
-    {i8; u8}
-    {i16; u16}
-    {i32; u32}
-    {i64; u64}
+pub(crate) struct ArrayCodec<T, C: Codec<T, O>, O: CodecOps, const N: usize> {
+    pub(crate) inner: C,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
 }
 
-pub struct UntypedMapCodec<T, O: CodecOps, C: Codec<T, O>> {
-    codec: C,
-    _phantom: PhantomData<(T, O, C)>,
+impl<T, C: Codec<T, O>, O: CodecOps, const N: usize> Codec<[T; N], O> for ArrayCodec<T, C, O, N> {
+    fn encode(&self, ops: &O, value: &[T; N], ctx: &mut Context) -> DataResult<O::T> {
+        encode_list(ops, value.iter(), ctx, |element, ctx| {
+            self.inner.encode(ops, element, ctx)
+        })
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<[T; N]> {
+        let list = ops.get_list(value)?;
+        if list.len() != N {
+            return Err(DataError::new_custom(&format!(
+                "expected a list of {} elements, found {} elements",
+                N,
+                list.len()
+            )));
+        }
+        let mut vec = Vec::with_capacity(N);
+        for index in 0..N {
+            ctx.push_array(index);
+            vec.push(self.inner.decode(ops, list.get(index)?, ctx)?);
+            ctx.pop();
+        }
+        vec.try_into()
+            .map_err(|_| DataError::new_custom("array length mismatch"))
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::List(Box::new(self.inner.schema()))
+    }
 }
 
-impl<T, O: CodecOps, C: Codec<T, O>> Codec<BTreeMap<String, T>, O> for UntypedMapCodec<T, O, C> {
-    fn encode(&self, ops: &O, value: &BTreeMap<String, T>, ctx: &mut Context) -> DataResult<O::T> {
-        let entries = value
-            .iter()
-            .map(|x| (x.0.clone(), self.codec.encode(ops, x.1, ctx).unwrap()))
-            .collect::<Vec<_>>();
-        Ok(ops.create_map(entries))
+impl<T: DefaultCodec<O>, O: CodecOps, const N: usize> DefaultCodec<O> for [T; N] {
+    fn codec() -> impl Codec<Self, O> {
+        T::codec().array_of()
+    }
+}
+
+/// Encodes `None` as [`CodecOps::create_none`], which is `null` in JSON, and `Some` as the inner value.
+///
+/// An inner value that encodes to the same value as `None` decodes back as `None`. This is the case for
+/// `Some(None)` of a nested `Option` in every format, and for `Some(())` or `Some` of an empty record in formats
+/// without a null value, such as NBT.
+pub(crate) struct OptionCodec<T, C: Codec<T, O>, O: CodecOps> {
+    pub(crate) inner: C,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, C: Codec<T, O>, O: CodecOps> Codec<Option<T>, O> for OptionCodec<T, C, O> {
+    fn encode(&self, ops: &O, value: &Option<T>, ctx: &mut Context) -> DataResult<O::T> {
+        match value {
+            Some(value) => self.inner.encode(ops, value, ctx),
+            None => Ok(ops.create_none()),
+        }
     }
 
-    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<BTreeMap<String, T>> {
-        let mut map = BTreeMap::new();
-        let Ok(view) = ops.get_map(value) else {
-            return Ok(map);
-        };
-        for key in view.keys() {
-            let value = view.get(&key)?;
-            let decode = self.codec.decode(ops, value, ctx)?;
-            map.insert(key, decode);
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Option<T>> {
+        if ops.get_none(value).is_ok() {
+            Ok(None)
+        } else {
+            self.inner.decode(ops, value, ctx).map(Some)
         }
-        Ok(map)
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::OneOf(vec![Schema::Unit, self.inner.schema()])
     }
 }
 
-impl<T: DefaultCodec<O>, O: CodecOps> DefaultCodec<O> for BTreeMap<String, T> {
+impl<T: DefaultCodec<O>, O: CodecOps> DefaultCodec<O> for Option<T> {
     fn codec() -> impl Codec<Self, O> {
-        UntypedMapCodec {
-            codec: T::codec(),
+        OptionCodec {
+            inner: T::codec(),
             _phantom: PhantomData,
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use alloc::{
-        boxed::Box,
-        collections::btree_map::BTreeMap,
-        string::{String, ToString},
-        vec,
-    };
-    use json::JsonValue;
+pub(crate) struct XMapCodec<OLT, NT, C, F1, F2, O: CodecOps>
+where
+    C: Codec<OLT, O>,
+    F1: Fn(&OLT) -> NT,
+    F2: Fn(&NT) -> OLT,
+{
+    pub(crate) inner: C,
+    pub(crate) f1: F1,
+    pub(crate) f2: F2,
+    pub(crate) _phantom: PhantomData<fn() -> (OLT, NT, O)>,
+}
 
-    use crate::{
-        result::DataError,
-        serialization::{
-            Codec, CodecAdapters, CodecOps, Codecs, DefaultCodec, MapCodecBuilder,
-            builtins::codecs::{ArcCodec, DynamicCodec},
-            json::JsonOps,
-        },
-    };
+impl<OLT, NT, C, F1, F2, O: CodecOps> Codec<NT, O> for XMapCodec<OLT, NT, C, F1, F2, O>
+where
+    C: Codec<OLT, O>,
+    F1: Fn(&OLT) -> NT,
+    F2: Fn(&NT) -> OLT,
+{
+    fn encode(&self, ops: &O, value: &NT, ctx: &mut Context) -> DataResult<O::T> {
+        self.inner.encode(ops, &(self.f2)(value), ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<NT> {
+        Ok((self.f1)(&self.inner.decode(ops, value, ctx)?))
+    }
+
+    fn schema(&self) -> Schema {
+        self.inner.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.inner.record_keys()
+    }
+}
+
+pub(crate) struct CheckedXMapCodec<OLT, NT, C, F1, F2, O: CodecOps>
+where
+    C: Codec<OLT, O>,
+    F1: Fn(&OLT) -> NT,
+    F2: Fn(&NT) -> OLT,
+{
+    pub(crate) inner: XMapCodec<OLT, NT, C, F1, F2, O>,
+}
+
+impl<OLT, NT, C, F1, F2, O: CodecOps> Codec<NT, O> for CheckedXMapCodec<OLT, NT, C, F1, F2, O>
+where
+    C: Codec<OLT, O>,
+    F1: Fn(&OLT) -> NT,
+    F2: Fn(&NT) -> OLT,
+    NT: PartialEq + Debug,
+{
+    fn encode(&self, ops: &O, value: &NT, ctx: &mut Context) -> DataResult<O::T> {
+        self.inner.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<NT> {
+        let decoded = self.inner.decode(ops, value, ctx)?;
+        #[cfg(debug_assertions)]
+        {
+            let round_trip = (self.inner.f1)(&(self.inner.f2)(&decoded));
+            if round_trip != decoded {
+                let path = ctx.path();
+                panic!(
+                    "xmap functions are not inverses at {}: {:?} was mapped back to {:?}",
+                    if path.is_empty() { "the root" } else { &path },
+                    decoded,
+                    round_trip
+                );
+            }
+        }
+        Ok(decoded)
+    }
+
+    fn schema(&self) -> Schema {
+        self.inner.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.inner.record_keys()
+    }
+}
+
+pub(crate) struct FlatXMapCodec<OLT, NT, C, F1, F2, O: CodecOps>
+where
+    C: Codec<OLT, O>,
+    F1: Fn(&OLT) -> DataResult<NT>,
+    F2: Fn(&NT) -> DataResult<OLT>,
+{
+    pub(crate) inner: C,
+    pub(crate) f1: F1,
+    pub(crate) f2: F2,
+    pub(crate) _phantom: PhantomData<fn() -> (OLT, NT, O)>,
+}
+
+impl<OLT, NT, C, F1, F2, O: CodecOps> Codec<NT, O> for FlatXMapCodec<OLT, NT, C, F1, F2, O>
+where
+    C: Codec<OLT, O>,
+    F1: Fn(&OLT) -> DataResult<NT>,
+    F2: Fn(&NT) -> DataResult<OLT>,
+{
+    fn encode(&self, ops: &O, value: &NT, ctx: &mut Context) -> DataResult<O::T> {
+        self.inner.encode(ops, &(self.f2)(value)?, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<NT> {
+        (self.f1)(&self.inner.decode(ops, value, ctx)?)
+    }
+
+    fn schema(&self) -> Schema {
+        self.inner.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.inner.record_keys()
+    }
+}
+
+pub(crate) struct PairCodec<L, R, Lc: Codec<L, O>, Rc: Codec<R, O>, O: CodecOps> {
+    pub(crate) left: Lc,
+    pub(crate) right: Rc,
+    pub(crate) left_key: String,
+    pub(crate) right_key: String,
+    pub(crate) _phantom: PhantomData<fn() -> (L, R, O)>,
+}
+impl<L, R, Lc: Codec<L, O>, Rc: Codec<R, O>, O: CodecOps> Codec<(L, R), O>
+    for PairCodec<L, R, Lc, Rc, O>
+{
+    fn encode(&self, ops: &O, value: &(L, R), ctx: &mut Context) -> DataResult<O::T> {
+        ctx.push_field(&self.left_key);
+        let left = self.left.encode(ops, &value.0, ctx)?;
+        ctx.pop();
+        ctx.push_field(&self.right_key);
+        let right = self.right.encode(ops, &value.1, ctx)?;
+        ctx.pop();
+        Ok(ops.create_map([
+            (self.left_key.clone(), left),
+            (self.right_key.clone(), right),
+        ]))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<(L, R)> {
+        let obj = ops.get_map(value)?;
+        ctx.push_field(&self.left_key);
+        let p1 = self.left.decode(ops, obj.get(&self.left_key)?, ctx)?;
+        ctx.pop();
+        ctx.push_field(&self.right_key);
+        let p2 = self.right.decode(ops, obj.get(&self.right_key)?, ctx)?;
+        ctx.pop();
+        Ok((p1, p2))
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::Record(vec![
+            SchemaField {
+                name: self.left_key.clone(),
+                schema: self.left.schema(),
+                required: true,
+            },
+            SchemaField {
+                name: self.right_key.clone(),
+                schema: self.right.schema(),
+                required: true,
+            },
+        ])
+    }
+}
+
+/// Encodes a range as a map with `start` and `end` keys, checking that `start <= end` when encoding and decoding.
+pub(crate) struct RangeCodec<T, R, C: Codec<T, O>, O: CodecOps> {
+    pub(crate) inner: C,
+    pub(crate) new: fn(T, T) -> R,
+    pub(crate) bounds: fn(&R) -> (&T, &T),
+    pub(crate) _phantom: PhantomData<fn() -> (T, R, O)>,
+}
+
+impl<T: PartialOrd + Debug, R, C: Codec<T, O>, O: CodecOps> RangeCodec<T, R, C, O> {
+    fn check_bounds(start: &T, end: &T) -> DataResult<()> {
+        if start <= end {
+            Ok(())
+        } else {
+            Err(DataError::out_of_range(&format!(
+                "range start {:?} must not be greater than its end {:?}",
+                start, end
+            )))
+        }
+    }
+}
+
+impl<T: PartialOrd + Debug, R, C: Codec<T, O>, O: CodecOps> Codec<R, O> for RangeCodec<T, R, C, O> {
+    fn encode(&self, ops: &O, value: &R, ctx: &mut Context) -> DataResult<O::T> {
+        let (start, end) = (self.bounds)(value);
+        Self::check_bounds(start, end)?;
+        ctx.push_field("start");
+        let start = self.inner.encode(ops, start, ctx)?;
+        ctx.pop();
+        ctx.push_field("end");
+        let end = self.inner.encode(ops, end, ctx)?;
+        ctx.pop();
+        Ok(ops.create_map([("start".to_string(), start), ("end".to_string(), end)]))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<R> {
+        let obj = ops.get_map(value)?;
+        ctx.push_field("start");
+        let start = self.inner.decode(ops, obj.get("start")?, ctx)?;
+        ctx.pop();
+        ctx.push_field("end");
+        let end = self.inner.decode(ops, obj.get("end")?, ctx)?;
+        ctx.pop();
+        Self::check_bounds(&start, &end)?;
+        Ok((self.new)(start, end))
+    }
+}
+
+/// Formats a range in interval notation, such as `[1, 30)` for `1..30` or `[1, inf)` for `1..`.
+fn describe_range<T: Debug>(range: &impl RangeBounds<T>) -> String {
+    let start = match range.start_bound() {
+        Bound::Included(start) => format!("[{:?}", start),
+        Bound::Excluded(start) => format!("({:?}", start),
+        Bound::Unbounded => "(-inf".to_string(),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => format!("{:?}]", end),
+        Bound::Excluded(end) => format!("{:?})", end),
+        Bound::Unbounded => "inf)".to_string(),
+    };
+    format!("{}, {}", start, end)
+}
+
+pub(crate) struct BoundedCodec<T: PartialOrd, C: Codec<T, O>, R: RangeBounds<T>, O: CodecOps> {
+    pub(crate) codec: C,
+    pub(crate) range: R,
+    pub(crate) message: Option<String>,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T: PartialOrd + Debug, C: Codec<T, O>, R: RangeBounds<T>, O: CodecOps>
+    BoundedCodec<T, C, R, O>
+{
+    fn check(&self, value: &T) -> DataResult<()> {
+        if self.range.contains(value) {
+            return Ok(());
+        }
+        Err(DataError::out_of_range(&match &self.message {
+            Some(message) => message.clone(),
+            None => format!(
+                "value {:?} must be in range {}",
+                value,
+                describe_range(&self.range)
+            ),
+        }))
+    }
+}
+
+impl<T: PartialOrd + Debug + 'static, C: Codec<T, O>, R: RangeBounds<T>, O: CodecOps> Codec<T, O>
+    for BoundedCodec<T, C, R, O>
+{
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        self.check(value)?;
+        self.codec.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let decoded = self.codec.decode(ops, value, ctx)?;
+        self.check(&decoded)?;
+        Ok(decoded)
+    }
+
+    fn schema(&self) -> Schema {
+        match self.codec.schema() {
+            Schema::Number { integer, .. } => Schema::Number {
+                integer,
+                minimum: numeric_bound(self.range.start_bound()),
+                maximum: numeric_bound(self.range.end_bound()),
+            },
+            schema => schema,
+        }
+    }
+}
+
+/// Converts a bound of a primitive number into a bound of an `f64` for a [`Schema::Number`].
+/// Bounds of any other type are reported as [`Bound::Unbounded`].
+fn numeric_bound<T: Any>(bound: Bound<&T>) -> Bound<f64> {
+    match bound {
+        Bound::Included(value) => primitive_f64(value).map_or(Bound::Unbounded, Bound::Included),
+        Bound::Excluded(value) => primitive_f64(value).map_or(Bound::Unbounded, Bound::Excluded),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+fn primitive_f64<T: Any>(value: &T) -> Option<f64> {
+    let value: &dyn Any = value;
+    macro_rules! downcast {
+        ($($ty:ty),*) => {
+            $(if let Some(value) = value.downcast_ref::<$ty>() {
+                return Some(*value as f64);
+            })*
+        };
+    }
+    downcast!(
+        i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+    );
+    None
+}
+
+pub(crate) struct ClampedCodec<T: Ord + Clone, C: Codec<T, O>, O: CodecOps> {
+    pub(crate) codec: C,
+    pub(crate) range: RangeInclusive<T>,
+    pub(crate) _phantom: PhantomData<fn() -> O>,
+}
+
+impl<T: Ord + Clone, C: Codec<T, O>, O: CodecOps> Codec<T, O> for ClampedCodec<T, C, O> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        self.codec.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let decoded = self.codec.decode(ops, value, ctx)?;
+        Ok(decoded.clamp(self.range.start().clone(), self.range.end().clone()))
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+}
+
+pub(crate) struct ValidateCodec<T, C: Codec<T, O>, F: Fn(&T) -> Result<(), String>, O: CodecOps> {
+    pub(crate) codec: C,
+    pub(crate) validator: F,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, C: Codec<T, O>, F: Fn(&T) -> Result<(), String>, O: CodecOps> Codec<T, O>
+    for ValidateCodec<T, C, F, O>
+{
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        (self.validator)(value).map_err(|e| DataError::new_custom(&e))?;
+        self.codec.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let decoded = self.codec.decode(ops, value, ctx)?;
+        (self.validator)(&decoded).map_err(|e| DataError::new_custom(&e))?;
+        Ok(decoded)
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+}
+
+pub(crate) struct StringTransformCodec<T, C: Codec<T, O>, O: CodecOps> {
+    pub(crate) codec: C,
+    pub(crate) transform: fn(&str) -> String,
+    /// Whether values are also transformed when encoding, not only when decoding.
+    pub(crate) on_encode: bool,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T: AsRef<str> + From<String>, C: Codec<T, O>, O: CodecOps> Codec<T, O>
+    for StringTransformCodec<T, C, O>
+{
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        if !self.on_encode {
+            return self.codec.encode(ops, value, ctx);
+        }
+        let transformed = T::from((self.transform)(value.as_ref()));
+        self.codec.encode(ops, &transformed, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let decoded = self.codec.decode(ops, value, ctx)?;
+        Ok(T::from((self.transform)(decoded.as_ref())))
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+}
+
+pub struct DynamicCodec<T, O: CodecOps> {
+    pub(crate) codec: Box<dyn Codec<T, O>>,
+}
+
+impl<T, O: CodecOps> Codec<T, O> for DynamicCodec<T, O> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        self.codec.as_ref().encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        self.codec.as_ref().decode(ops, value, ctx)
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.codec.record_keys()
+    }
+}
+
+pub struct ArcCodec<T, O: CodecOps> {
+    pub(crate) codec: Arc<dyn Codec<T, O>>,
+}
+
+impl<T, O: CodecOps> Clone for ArcCodec<T, O> {
+    fn clone(&self) -> Self {
+        Self {
+            codec: self.codec.clone(),
+        }
+    }
+}
+
+impl<T, O: CodecOps> Codec<T, O> for ArcCodec<T, O> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        self.codec.as_ref().encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        self.codec.as_ref().decode(ops, value, ctx)
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.codec.record_keys()
+    }
+}
+
+/// Like [`ArcCodec`], but the codec is `Send + Sync`, so it can be shared across threads.
+#[cfg(feature = "std")]
+pub struct SyncArcCodec<T, O: CodecOps> {
+    pub(crate) codec: Arc<dyn Codec<T, O> + Send + Sync>,
+}
+
+#[cfg(feature = "std")]
+impl<T, O: CodecOps> Clone for SyncArcCodec<T, O> {
+    fn clone(&self) -> Self {
+        Self {
+            codec: self.codec.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, O: CodecOps> Codec<T, O> for SyncArcCodec<T, O> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        self.codec.as_ref().encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        self.codec.as_ref().decode(ops, value, ctx)
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.codec.record_keys()
+    }
+}
+
+/// The codec passed to the builder of [`Codecs::recursive_sync`], which refers back to the codec being built.
+/// It only holds a weak reference, so the recursive codec is freed once every [`SyncArcCodec`] to it is dropped.
+#[cfg(feature = "std")]
+pub struct SyncRecursiveCodec<T, O: CodecOps> {
+    pub(crate) codec: Arc<OnceLock<Weak<dyn Codec<T, O> + Send + Sync>>>,
+}
+
+#[cfg(feature = "std")]
+impl<T, O: CodecOps> SyncRecursiveCodec<T, O> {
+    fn get(&self) -> DataResult<Arc<dyn Codec<T, O> + Send + Sync>> {
+        self.codec
+            .get()
+            .and_then(Weak::upgrade)
+            .ok_or_else(|| DataError::new_custom("recursive codec used before initialization"))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, O: CodecOps> Clone for SyncRecursiveCodec<T, O> {
+    fn clone(&self) -> Self {
+        Self {
+            codec: self.codec.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, O: CodecOps> Codec<T, O> for SyncRecursiveCodec<T, O> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        self.get()?.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        self.get()?.decode(ops, value, ctx)
+    }
+}
+
+/// The cell holding a [`LazyCodec`] once it's built, which is thread-safe when `std` is available.
+#[cfg(feature = "std")]
+pub(crate) type LazyCell<C> = OnceLock<C>;
+#[cfg(not(feature = "std"))]
+pub(crate) type LazyCell<C> = OnceCell<C>;
+
+pub struct LazyCodec<T, O: CodecOps, C: Codec<T, O>, F: Fn() -> C> {
+    pub(crate) init: F,
+    pub(crate) codec: LazyCell<C>,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, O: CodecOps, C: Codec<T, O>, F: Fn() -> C> LazyCodec<T, O, C, F> {
+    fn inner(&self) -> &C {
+        self.codec.get_or_init(&self.init)
+    }
+}
+
+impl<T, O: CodecOps, C: Codec<T, O>, F: Fn() -> C> Codec<T, O> for LazyCodec<T, O, C, F> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        self.inner().encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        self.inner().decode(ops, value, ctx)
+    }
+
+    fn schema(&self) -> Schema {
+        self.inner().schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.inner().record_keys()
+    }
+}
+
+pub struct FnCodec<T, O: CodecOps> {
+    pub(crate) encode: Box<dyn Fn(&O, &T, &mut Context) -> DataResult<O::T>>,
+    pub(crate) decode: Box<dyn Fn(&O, &O::T, &mut Context) -> DataResult<T>>,
+}
+
+impl<T, O: CodecOps> Codec<T, O> for FnCodec<T, O> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        (self.encode)(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        (self.decode)(ops, value, ctx)
+    }
+}
+
+pub struct BoxCodec<T, O: CodecOps, C: Codec<T, O>> {
+    pub(crate) inner: C,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, O: CodecOps, C: Codec<T, O>> Codec<Box<T>, O> for BoxCodec<T, O, C> {
+    fn encode(&self, ops: &O, value: &Box<T>, ctx: &mut Context) -> DataResult<O::T> {
+        self.inner.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Box<T>> {
+        self.inner.decode(ops, value, ctx).map(|x| Box::new(x))
+    }
+
+    fn schema(&self) -> Schema {
+        self.inner.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.inner.record_keys()
+    }
+}
+
+pub struct RcCodec<T, O: CodecOps, C: Codec<T, O>> {
+    pub(crate) inner: C,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, O: CodecOps, C: Codec<T, O>> Codec<alloc::rc::Rc<T>, O> for RcCodec<T, O, C> {
+    fn encode(&self, ops: &O, value: &alloc::rc::Rc<T>, ctx: &mut Context) -> DataResult<O::T> {
+        self.inner.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<alloc::rc::Rc<T>> {
+        self.inner.decode(ops, value, ctx).map(alloc::rc::Rc::new)
+    }
+
+    fn schema(&self) -> Schema {
+        self.inner.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.inner.record_keys()
+    }
+}
+
+pub struct ArcValueCodec<T, O: CodecOps, C: Codec<T, O>> {
+    pub(crate) inner: C,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, O: CodecOps, C: Codec<T, O>> Codec<Arc<T>, O> for ArcValueCodec<T, O, C> {
+    fn encode(&self, ops: &O, value: &Arc<T>, ctx: &mut Context) -> DataResult<O::T> {
+        self.inner.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Arc<T>> {
+        self.inner.decode(ops, value, ctx).map(Arc::new)
+    }
+
+    fn schema(&self) -> Schema {
+        self.inner.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.inner.record_keys()
+    }
+}
+
+/// Undoes what a failed alternative left in `ctx`, so the next one starts from the same state,
+/// and returns the errors it failed with. When collecting, the errors the alternative recorded
+/// are taken back and returned in place of the [`DataError::Collected`] that counted them.
+fn recover_alternative(
+    ctx: &mut Context,
+    depth: usize,
+    errors: usize,
+    error: DataError,
+) -> Vec<DataError> {
+    ctx.truncate(depth);
+    let mut sources: Vec<DataError> = ctx
+        .take_errors_since(errors)
+        .into_iter()
+        .map(|error| error.error().clone())
+        .collect();
+    match error {
+        DataError::Collected { .. } => {}
+        DataError::AllFailed { sources: nested } => sources.extend(nested),
+        error => sources.push(error),
+    }
+    sources
+}
+
+pub struct TryElseCodec<T, O: CodecOps, Lc: Codec<T, O>, Rc: Codec<T, O>> {
+    pub(crate) lc: Lc,
+    pub(crate) rc: Rc,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, O: CodecOps, Lc: Codec<T, O>, Rc: Codec<T, O>> Codec<T, O> for TryElseCodec<T, O, Lc, Rc> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        let depth = ctx.depth();
+        let t1 = self.lc.encode(ops, value, ctx);
+        match t1 {
+            Err(e1) => {
+                ctx.truncate(depth);
+                let t2 = self.rc.encode(ops, value, ctx);
+                match t2 {
+                    Ok(v) => Ok(v),
+                    Err(e2) => {
+                        ctx.truncate(depth);
+                        Err(DataError::all_failed(e1, e2))
+                    }
+                }
+            }
+            Ok(v) => Ok(v),
+        }
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let depth = ctx.depth();
+        let errors = ctx.error_count();
+        let e1 = match self.lc.decode(ops, value, ctx) {
+            Ok(v) => return Ok(v),
+            Err(e1) => recover_alternative(ctx, depth, errors, e1),
+        };
+        let e2 = match self.rc.decode(ops, value, ctx) {
+            Ok(v) => return Ok(v),
+            Err(e2) => recover_alternative(ctx, depth, errors, e2),
+        };
+        Err(DataError::AllFailed {
+            sources: [e1, e2].concat(),
+        })
+    }
+
+    fn debug(&self) -> String {
+        format!("({}) orelse ({})", self.lc.debug(), self.rc.debug())
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::OneOf(vec![self.lc.schema(), self.rc.schema()])
+    }
+}
+
+pub struct AnyOfCodec<T, O: CodecOps> {
+    pub(crate) codecs: Vec<DynamicCodec<T, O>>,
+    pub(crate) select: Option<Box<dyn Fn(&T) -> usize>>,
+}
+
+impl<T, O: CodecOps> AnyOfCodec<T, O> {
+    /// Picks the codec used to encode a value by its index, instead of always encoding with the first codec.
+    /// Encoding fails if `select` returns an index past the last codec.
+    pub fn encode_with(mut self, select: impl Fn(&T) -> usize + 'static) -> Self {
+        self.select = Some(Box::new(select));
+        self
+    }
+}
+
+impl<T, O: CodecOps> Codec<T, O> for AnyOfCodec<T, O> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        let index = self
+            .select
+            .as_ref()
+            .map(|select| select(value))
+            .unwrap_or(0);
+        match self.codecs.get(index) {
+            Some(codec) => codec.encode(ops, value, ctx),
+            None => Err(DataError::new_custom(&format!(
+                "any_of selected codec {} to encode with, but there are only {} codecs",
+                index,
+                self.codecs.len()
+            ))),
+        }
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let depth = ctx.depth();
+        let errors = ctx.error_count();
+        let mut sources = Vec::new();
+        for codec in &self.codecs {
+            match codec.decode(ops, value, ctx) {
+                Ok(v) => return Ok(v),
+                Err(e) => sources.extend(recover_alternative(ctx, depth, errors, e)),
+            }
+        }
+        Err(DataError::AllFailed { sources })
+    }
+
+    fn debug(&self) -> String {
+        let codecs: Vec<String> = self.codecs.iter().map(|codec| codec.debug()).collect();
+        format!("anyof({})", codecs.join(", "))
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::OneOf(self.codecs.iter().map(|codec| codec.schema()).collect())
+    }
+}
+
+pub struct EitherCodec<T, O: CodecOps, T2, Lc: Codec<T, O>, Rc: Codec<T2, O>> {
+    pub(crate) lc: Lc,
+    pub(crate) rc: Rc,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O, T2)>,
+}
+
+impl<T, O: CodecOps, T2, Lc: Codec<T, O>, Rc: Codec<T2, O>> Codec<Either<T, T2>, O>
+    for EitherCodec<T, O, T2, Lc, Rc>
+{
+    fn encode(&self, ops: &O, value: &Either<T, T2>, ctx: &mut Context) -> DataResult<O::T> {
+        match value {
+            Either::Left(value) => self.lc.encode(ops, value, ctx),
+            Either::Right(value) => self.rc.encode(ops, value, ctx),
+        }
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Either<T, T2>> {
+        let depth = ctx.depth();
+        let errors = ctx.error_count();
+        match self.lc.decode(ops, value, ctx) {
+            Ok(v) => Ok(Either::Left(v)),
+            Err(e) => {
+                recover_alternative(ctx, depth, errors, e);
+                self.rc.decode(ops, value, ctx).map(Either::Right)
+            }
+        }
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::OneOf(vec![self.lc.schema(), self.rc.schema()])
+    }
+}
+
+pub(crate) struct TaggedEitherCodec<T, O: CodecOps, T2, Lc: Codec<T, O>, Rc: Codec<T2, O>> {
+    pub(crate) tag_key: String,
+    pub(crate) left_tag: String,
+    pub(crate) right_tag: String,
+    pub(crate) lc: Lc,
+    pub(crate) rc: Rc,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O, T2)>,
+}
+
+impl<T, O: CodecOps, T2, Lc: Codec<T, O>, Rc: Codec<T2, O>> Codec<Either<T, T2>, O>
+    for TaggedEitherCodec<T, O, T2, Lc, Rc>
+{
+    fn encode(&self, ops: &O, value: &Either<T, T2>, ctx: &mut Context) -> DataResult<O::T> {
+        let (tag, mut encoded) = match value {
+            Either::Left(value) => (&self.left_tag, self.lc.encode(ops, value, ctx)?),
+            Either::Right(value) => (&self.right_tag, self.rc.encode(ops, value, ctx)?),
+        };
+        ops.get_map_mut(&mut encoded)?
+            .set(&self.tag_key, ops.create_string(tag));
+        Ok(encoded)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Either<T, T2>> {
+        ctx.push_field(&self.tag_key);
+        let tag = ops.get_string(ops.get_map(value)?.get(&self.tag_key)?)?;
+        if tag != self.left_tag && tag != self.right_tag {
+            return Err(DataError::unknown_variant(
+                &tag,
+                [&self.left_tag, &self.right_tag],
+            ));
+        }
+        ctx.pop();
+
+        let mut content = value.clone();
+        ops.get_map_mut(&mut content)?.remove(&self.tag_key)?;
+        if tag == self.left_tag {
+            Ok(Either::Left(self.lc.decode(ops, &content, ctx)?))
+        } else {
+            Ok(Either::Right(self.rc.decode(ops, &content, ctx)?))
+        }
+    }
+
+    fn schema(&self) -> Schema {
+        let tagged = |schema: Schema, tag: &String| {
+            schema.with_field(&self.tag_key, Schema::Enum(vec![tag.clone()]), true)
+        };
+        Schema::OneOf(vec![
+            tagged(self.lc.schema(), &self.left_tag),
+            tagged(self.rc.schema(), &self.right_tag),
+        ])
+    }
+}
+
+pub(crate) struct ResultCodec<T, E, O: CodecOps, Tc: Codec<T, O>, Ec: Codec<E, O>> {
+    pub(crate) ok: Tc,
+    pub(crate) err: Ec,
+    pub(crate) _phantom: PhantomData<fn() -> (T, E, O)>,
+}
+
+impl<T, E, O: CodecOps, Tc: Codec<T, O>, Ec: Codec<E, O>> Codec<Result<T, E>, O>
+    for ResultCodec<T, E, O, Tc, Ec>
+{
+    fn encode(&self, ops: &O, value: &Result<T, E>, ctx: &mut Context) -> DataResult<O::T> {
+        let (key, encoded) = match value {
+            Ok(value) => {
+                ctx.push_field("ok");
+                ("ok", self.ok.encode(ops, value, ctx)?)
+            }
+            Err(value) => {
+                ctx.push_field("err");
+                ("err", self.err.encode(ops, value, ctx)?)
+            }
+        };
+        ctx.pop();
+        Ok(ops.create_map([(key.to_string(), encoded)]))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Result<T, E>> {
+        let map = ops.get_map(value)?;
+        match (map.get("ok"), map.get("err")) {
+            (Ok(ok), Err(_)) => {
+                ctx.push_field("ok");
+                let decoded = self.ok.decode(ops, ok, ctx)?;
+                ctx.pop();
+                Ok(Ok(decoded))
+            }
+            (Err(_), Ok(err)) => {
+                ctx.push_field("err");
+                let decoded = self.err.decode(ops, err, ctx)?;
+                ctx.pop();
+                Ok(Err(decoded))
+            }
+            (Ok(_), Ok(_)) => Err(DataError::new_custom(
+                "expected only one of the keys \"ok\" or \"err\", found both",
+            )),
+            (Err(_), Err(_)) => Err(DataError::new_custom(
+                "expected one of the keys \"ok\" or \"err\", found neither",
+            )),
+        }
+    }
+}
+
+impl<T: DefaultCodec<O>, E: DefaultCodec<O>, O: CodecOps> DefaultCodec<O> for Result<T, E> {
+    fn codec() -> impl Codec<Self, O> {
+        ResultCodec {
+            ok: T::codec(),
+            err: E::codec(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+pub struct OrElseCodec<T, O: CodecOps, C: Codec<T, O>, F: Fn() -> T> {
+    pub(crate) codec: C,
+    pub(crate) default: F,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, O: CodecOps, C: Codec<T, O>, F: Fn() -> T> Codec<T, O> for OrElseCodec<T, O, C, F> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        self.codec.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let depth = ctx.depth();
+        let errors = ctx.error_count();
+        match self.codec.decode(ops, value, ctx) {
+            Ok(v) => Ok(v),
+            Err(e) => {
+                recover_alternative(ctx, depth, errors, e);
+                Ok((self.default)())
+            }
+        }
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        self.codec.record_keys()
+    }
+}
+
+pub struct DispatchCodec<
+    T,
+    O: CodecOps,
+    E: Fn(&T) -> DataResult<DynamicCodec<T, O>>,
+    F: Fn(&O, &O::T) -> DataResult<DynamicCodec<T, O>>,
+> {
+    pub(crate) from_type_to_codec: E,
+    pub(crate) from_ops_to_codec: F,
+    pub(crate) _phantom: PhantomData<(T, O)>,
+}
+
+impl<
+    T,
+    O: CodecOps,
+    E: Fn(&T) -> DataResult<DynamicCodec<T, O>>,
+    F: Fn(&O, &O::T) -> DataResult<DynamicCodec<T, O>>,
+> Codec<T, O> for DispatchCodec<T, O, E, F>
+{
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        (self.from_type_to_codec)(value)?.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        (self.from_ops_to_codec)(ops, value)?.decode(ops, value, ctx)
+    }
+}
+
+pub(crate) struct VersionedCodec<T, O: CodecOps, C: Codec<T, O>> {
+    pub(crate) version_field: String,
+    pub(crate) fixer: DataFixer<O>,
+    pub(crate) codec: C,
+    pub(crate) _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T, O: CodecOps, C: Codec<T, O>> Codec<T, O> for VersionedCodec<T, O, C> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        let mut encoded = self.codec.encode(ops, value, ctx)?;
+        ops.get_map_mut(&mut encoded)?.set(
+            &self.version_field,
+            ops.create_long(&(self.fixer.current_version() as i64)),
+        );
+        Ok(encoded)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let mut value = value.clone();
+        let version = match ops.get_map_mut(&mut value)?.remove(&self.version_field) {
+            Ok(version) => {
+                ctx.push_field(&self.version_field);
+                let version = ops.get_long(&version)?;
+                let version = u32::try_from(version).map_err(|_| {
+                    DataError::out_of_range(&format!("{} is not a valid version", version))
+                })?;
+                if version > self.fixer.current_version() {
+                    return Err(DataError::out_of_range(&format!(
+                        "version {} is newer than the current version {}",
+                        version,
+                        self.fixer.current_version()
+                    )));
+                }
+                ctx.pop();
+                version
+            }
+            Err(_) => 0,
+        };
+        let value = self.fixer.update(ops, value, version);
+        self.codec.decode(ops, &value, ctx)
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec
+            .schema()
+            .with_field(&self.version_field, Schema::integer(), false)
+    }
+}
+
+pub(crate) struct StringDispatchCodec<T, O: CodecOps, F: Fn(&T) -> &str> {
+    pub(crate) tag_key: String,
+    pub(crate) tag_of: F,
+    pub(crate) variants: BTreeMap<String, DynamicCodec<T, O>>,
+}
+
+impl<T, O: CodecOps, F: Fn(&T) -> &str> StringDispatchCodec<T, O, F> {
+    pub(crate) fn new(
+        tag_key: impl Into<String>,
+        tag_of: F,
+        variants: impl IntoIterator<Item = (impl Into<String>, DynamicCodec<T, O>)>,
+    ) -> Self {
+        StringDispatchCodec {
+            tag_key: tag_key.into(),
+            tag_of,
+            variants: variants
+                .into_iter()
+                .map(|(tag, codec)| (tag.into(), codec))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn variant(&self, tag: &str) -> DataResult<&DynamicCodec<T, O>> {
+        self.variants
+            .get(tag)
+            .ok_or_else(|| DataError::unknown_variant(tag, self.variants.keys()))
+    }
+
+    pub(crate) fn read_tag(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<String> {
+        ctx.push_field(&self.tag_key);
+        let tag = ops.get_string(ops.get_map(value)?.get(&self.tag_key)?)?;
+        ctx.pop();
+        Ok(tag)
+    }
+}
+
+impl<T, O: CodecOps, F: Fn(&T) -> &str> Codec<T, O> for StringDispatchCodec<T, O, F> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        let tag = (self.tag_of)(value);
+        let encoded = self.variant(tag)?.encode(ops, value, ctx)?;
+        // The variant codec owns the tag, so it is decoded along with the rest of the variant's map.
+        let written = ops
+            .get_map(&encoded)?
+            .get(&self.tag_key)
+            .and_then(|written| ops.get_str(written).map(|written| written == tag));
+        if !matches!(written, Ok(true)) {
+            return Err(DataError::new_custom(&format!(
+                "the codec of variant \"{}\" must write its tag under \"{}\"",
+                tag, self.tag_key
+            )));
+        }
+        Ok(encoded)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let tag = self.read_tag(ops, value, ctx)?;
+        self.variant(&tag)?.decode(ops, value, ctx)
+    }
+}
+
+pub(crate) struct InternallyTaggedCodec<T, O: CodecOps, F: Fn(&T) -> &str> {
+    pub(crate) dispatch: StringDispatchCodec<T, O, F>,
+}
+
+impl<T, O: CodecOps, F: Fn(&T) -> &str> Codec<T, O> for InternallyTaggedCodec<T, O, F> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        let tag = (self.dispatch.tag_of)(value);
+        let mut encoded = self.dispatch.variant(tag)?.encode(ops, value, ctx)?;
+        ops.get_map_mut(&mut encoded)?
+            .set(&self.dispatch.tag_key, ops.create_string(tag));
+        Ok(encoded)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let tag = self.dispatch.read_tag(ops, value, ctx)?;
+        let codec = self.dispatch.variant(&tag)?;
+        let mut content = value.clone();
+        ops.get_map_mut(&mut content)?
+            .remove(&self.dispatch.tag_key)?;
+        codec.decode(ops, &content, ctx)
+    }
+}
+
+pub struct AdjacentlyTaggedCodec<T, O: CodecOps, F: Fn(&T) -> &str> {
+    pub(crate) dispatch: StringDispatchCodec<T, O, F>,
+    pub(crate) content_key: String,
+    pub(crate) unit_variants: BTreeSet<String>,
+}
+
+impl<T, O: CodecOps, F: Fn(&T) -> &str> AdjacentlyTaggedCodec<T, O, F> {
+    /// Marks the variants with these tags as unit variants, which are encoded as just the tag, without content.
+    /// Every other variant always stores its content, even if it encodes to a unit value.
+    pub fn unit_variants(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.unit_variants.extend(tags.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl<T, O: CodecOps, F: Fn(&T) -> &str> Codec<T, O> for AdjacentlyTaggedCodec<T, O, F> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        let tag = (self.dispatch.tag_of)(value);
+        let codec = self.dispatch.variant(tag)?;
+        let mut entries = vec![(self.dispatch.tag_key.clone(), ops.create_string(tag))];
+        if !self.unit_variants.contains(tag) {
+            ctx.push_field(&self.content_key);
+            let content = codec.encode(ops, value, ctx)?;
+            ctx.pop();
+            entries.push((self.content_key.clone(), content));
+        }
+        Ok(ops.create_map(entries))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let tag = self.dispatch.read_tag(ops, value, ctx)?;
+        let codec = self.dispatch.variant(&tag)?;
+        if self.unit_variants.contains(&tag) {
+            return codec.decode(ops, &ops.create_unit(), ctx);
+        }
+        ctx.push_field(&self.content_key);
+        let decoded = codec.decode(ops, ops.get_map(value)?.get(&self.content_key)?, ctx)?;
+        ctx.pop();
+        Ok(decoded)
+    }
+}
+
+pub struct ExternallyTaggedCodec<T, O: CodecOps, F: Fn(&T) -> &str> {
+    pub(crate) dispatch: StringDispatchCodec<T, O, F>,
+    pub(crate) unit_variants: BTreeSet<String>,
+}
+
+impl<T, O: CodecOps, F: Fn(&T) -> &str> ExternallyTaggedCodec<T, O, F> {
+    /// Marks the variants with these tags as unit variants, which are encoded as just the tag string.
+    /// Every other variant is always encoded as a map, even if its content encodes to a unit value.
+    pub fn unit_variants(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.unit_variants.extend(tags.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl<T, O: CodecOps, F: Fn(&T) -> &str> Codec<T, O> for ExternallyTaggedCodec<T, O, F> {
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        let tag = (self.dispatch.tag_of)(value);
+        let codec = self.dispatch.variant(tag)?;
+        if self.unit_variants.contains(tag) {
+            return Ok(ops.create_string(tag));
+        }
+        ctx.push_field(tag);
+        let content = codec.encode(ops, value, ctx)?;
+        ctx.pop();
+        Ok(ops.create_map([(tag.to_string(), content)]))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        if let Ok(tag) = ops.get_str(value) {
+            let codec = self.dispatch.variant(&tag)?;
+            if !self.unit_variants.contains(tag.as_ref()) {
+                return Err(DataError::new_custom(&format!(
+                    "variant \"{}\" is not a unit variant and must be encoded as a map",
+                    tag
+                )));
+            }
+            return codec.decode(ops, &ops.create_unit(), ctx);
+        }
+        let map = ops.get_map(value)?;
+        let keys = map.keys();
+        let [tag] = keys.as_slice() else {
+            return Err(DataError::new_custom(&format!(
+                "expected a map with exactly one tag, found {} keys",
+                keys.len()
+            )));
+        };
+        let codec = self.dispatch.variant(tag)?;
+        ctx.push_field(tag);
+        let decoded = codec.decode(ops, map.get(tag)?, ctx)?;
+        ctx.pop();
+        Ok(decoded)
+    }
+}
+
+pub struct ConstantCodec<T: Clone + PartialEq + Debug, C: Codec<T, O>, O: CodecOps> {
+    pub(crate) codec: C,
+    pub(crate) constant: T,
+    pub(crate) _phantom: PhantomData<fn() -> O>,
+}
+
+impl<T: Clone + PartialEq + Debug, C: Codec<T, O>, O: CodecOps> Codec<T, O>
+    for ConstantCodec<T, C, O>
+{
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<<O as CodecOps>::T> {
+        if value != &self.constant {
+            return Err(DataError::new_custom(&format!(
+                "expected constant {:?}, found {:?}",
+                self.constant, value
+            )));
+        }
+        self.codec.encode(ops, value, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &<O as CodecOps>::T, ctx: &mut Context) -> DataResult<T> {
+        let value = self.codec.decode(ops, value, ctx)?;
+        if value != self.constant {
+            return Err(DataError::new_custom(&format!(
+                "expected constant {:?}, found {:?}",
+                self.constant, value
+            )));
+        }
+        Ok(value)
+    }
+
+    fn debug(&self) -> String {
+        format!("Constant({:?})", self.constant)
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+}
+
+pub(crate) struct StringEnumCodec<T> {
+    pub(crate) variants: Vec<(String, T)>,
+    pub(crate) case_insensitive: bool,
+}
+
+impl<T: Clone + PartialEq + Debug, O: CodecOps> Codec<T, O> for StringEnumCodec<T> {
+    fn encode(&self, ops: &O, value: &T, _ctx: &mut Context) -> DataResult<O::T> {
+        let (name, _) = self
+            .variants
+            .iter()
+            .find(|(_, variant)| variant == value)
+            .ok_or_else(|| {
+                DataError::new_custom(&format!("{:?} is not one of the variants", value))
+            })?;
+        Ok(ops.create_string(name))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<T> {
+        let string = ops.get_str(value)?;
+        let found = self
+            .variants
+            .iter()
+            .find(|(name, _)| *name == string)
+            .or_else(|| {
+                if !self.case_insensitive {
+                    return None;
+                }
+                let lowercase = string.to_lowercase();
+                self.variants
+                    .iter()
+                    .find(|(name, _)| name.to_lowercase() == lowercase)
+            });
+        match found {
+            Some((_, variant)) => Ok(variant.clone()),
+            None => Err(DataError::unknown_variant(
+                &string,
+                self.variants.iter().map(|(name, _)| name),
+            )),
+        }
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::Enum(self.variants.iter().map(|(name, _)| name.clone()).collect())
+    }
+}
+
+pub(crate) struct IntEnumCodec<N, T, C> {
+    pub(crate) codec: C,
+    pub(crate) variants: Vec<(N, T)>,
+}
+
+impl<N: PartialEq + Display, T: Clone + PartialEq + Debug, C: Codec<N, O>, O: CodecOps> Codec<T, O>
+    for IntEnumCodec<N, T, C>
+{
+    fn encode(&self, ops: &O, value: &T, ctx: &mut Context) -> DataResult<O::T> {
+        let (discriminant, _) = self
+            .variants
+            .iter()
+            .find(|(_, variant)| variant == value)
+            .ok_or_else(|| {
+                DataError::new_custom(&format!("{:?} is not one of the variants", value))
+            })?;
+        self.codec.encode(ops, discriminant, ctx)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        let discriminant = self.codec.decode(ops, value, ctx)?;
+        match self.variants.iter().find(|(d, _)| *d == discriminant) {
+            Some((_, variant)) => Ok(variant.clone()),
+            None => Err(DataError::unknown_variant(
+                &discriminant.to_string(),
+                self.variants.iter().map(|(d, _)| d.to_string()),
+            )),
+        }
+    }
+
+    fn schema(&self) -> Schema {
+        self.codec.schema()
+    }
+}
+
+macro_rules! make_numeric_codec {
+    (
+        $({$t:ty, $struct_name:ident, $get_name:ident, $make_name:ident, $schema:expr})*
+        $(;)?
+    ) => {
+        $(pub struct $struct_name<O: CodecOps> {
+            _phantom: PhantomData<fn() -> O>,
+        }
+
+        impl<O: CodecOps> Codec<$t, O> for $struct_name<O> {
+            fn encode(&self, ops: &O, value: &$t, _ctx: &mut Context) -> DataResult<O::T> {
+                Ok(ops.$make_name(value))
+            }
+
+            fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<$t> {
+                ops.$get_name(value)
+            }
+
+            fn schema(&self) -> Schema {
+                $schema
+            }
+        }
+
+        impl<O: CodecOps> DefaultCodec<O> for $t {
+            fn codec() -> impl Codec<Self, O> {
+                $struct_name {
+                    _phantom: PhantomData,
+                }
+            }
+        })*
+    };
+}
+
+make_numeric_codec! {
+    {f32, F32Codec, get_float, create_float, Schema::number()}
+    {f64, F64Codec, get_double, create_double, Schema::number()}
+
+    {i8, I8Codec, get_byte, create_byte, Schema::integer()}
+    {i16, I16Codec, get_short, create_short, Schema::integer()}
+    {i32, I32Codec, get_int, create_int, Schema::integer()}
+    {i64, I64Codec, get_long, create_long, Schema::integer()}
+}
+
+// Unsigned integers keep their own width. Formats with only signed integers, such as NBT, reject values that don't fit
+// in the signed integer of the same width, while formats that have them, such as JSON, store every value.
+macro_rules! make_unsigned_codec {
+    (
+        $({$t:ty, $struct_name:ident, $get_name:ident, $make_name:ident})*
+    ) => {
+        $(pub struct $struct_name<O: CodecOps> {
+            _phantom: PhantomData<fn() -> O>,
+        }
+
+        impl<O: CodecOps> Codec<$t, O> for $struct_name<O> {
+            fn encode(&self, ops: &O, value: &$t, _ctx: &mut Context) -> DataResult<O::T> {
+                ops.$make_name(value)
+            }
+
+            fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<$t> {
+                ops.$get_name(value)
+            }
+
+            fn schema(&self) -> Schema {
+                Schema::integer()
+            }
+        }
+
+        impl<O: CodecOps> DefaultCodec<O> for $t {
+            fn codec() -> impl Codec<Self, O> {
+                $struct_name {
+                    _phantom: PhantomData,
+                }
+            }
+        })*
+    };
+}
+
+make_unsigned_codec! {
+    {u8, U8Codec, get_unsigned_byte, create_unsigned_byte}
+    {u16, U16Codec, get_unsigned_short, create_unsigned_short}
+    {u32, U32Codec, get_unsigned_int, create_unsigned_int}
+    {u64, U64Codec, get_unsigned_long, create_unsigned_long}
+}
+
+macro_rules! make_non_zero_codec {
+    (
+        $(
+            {$inner:ty; $to:ty}
+        )*
+    ) => {
+        $(impl<O: CodecOps> DefaultCodec<O> for $to {
+            fn codec() -> impl Codec<Self, O> {
+                <$inner>::codec().flat_xmap(
+                    |x| <$to>::new(*x).ok_or_else(|| DataError::out_of_range("value must be non-zero")),
+                    |x| Ok(x.get()),
+                )
+            }
+        })*
+    };
+}
+
+make_non_zero_codec! {
+    {u8; NonZeroU8}
+    {u16; NonZeroU16}
+    {u32; NonZeroU32}
+    {u64; NonZeroU64}
+    {i8; NonZeroI8}
+    {i16; NonZeroI16}
+    {i32; NonZeroI32}
+    {i64; NonZeroI64}
+}
+
+macro_rules! make_tuple_codec {
+    (
+        $({$struct_name:ident; $len:literal; $($t:ident[$c:ident; $idx:tt]),*})*
+    ) => {
+        $(pub(crate) struct $struct_name<$($t, $c: Codec<$t, O>),*, O: CodecOps> {
+            pub(crate) codecs: ($($c),*),
+            pub(crate) _phantom: PhantomData<fn() -> ($($t),*, O)>,
+        }
+
+        impl<$($t, $c: Codec<$t, O>),*, O: CodecOps> Codec<($($t),*), O> for $struct_name<$($t, $c),*, O> {
+            fn encode(&self, ops: &O, value: &($($t),*), ctx: &mut Context) -> DataResult<O::T> {
+                encode_list(ops, 0..$len, ctx, |index, ctx| match index {
+                    $($idx => self.codecs.$idx.encode(ops, &value.$idx, ctx),)*
+                    _ => unreachable!(),
+                })
+            }
+
+            fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<($($t),*)> {
+                let list = ops.get_list(value)?;
+                if list.len() != $len {
+                    return Err(DataError::new_custom(&format!(
+                        "expected a list of {} elements, found {} elements",
+                        $len,
+                        list.len()
+                    )));
+                }
+                Ok(($({
+                    ctx.push_array($idx);
+                    let decoded = self.codecs.$idx.decode(ops, list.get($idx)?, ctx)?;
+                    ctx.pop();
+                    decoded
+                }),*))
+            }
+        })*
+    };
+}
+
+make_tuple_codec! {
+    {Tuple3Codec; 3; A[Ac; 0], B[Bc; 1], C[Cc; 2]}
+    {Tuple4Codec; 4; A[Ac; 0], B[Bc; 1], C[Cc; 2], D[Dc; 3]}
+    {Tuple5Codec; 5; A[Ac; 0], B[Bc; 1], C[Cc; 2], D[Dc; 3], E[Ec; 4]}
+    {Tuple6Codec; 6; A[Ac; 0], B[Bc; 1], C[Cc; 2], D[Dc; 3], E[Ec; 4], F[Fc; 5]}
+    {Tuple7Codec; 7; A[Ac; 0], B[Bc; 1], C[Cc; 2], D[Dc; 3], E[Ec; 4], F[Fc; 5], G[Gc; 6]}
+    {Tuple8Codec; 8; A[Ac; 0], B[Bc; 1], C[Cc; 2], D[Dc; 3], E[Ec; 4], F[Fc; 5], G[Gc; 6], H[Hc; 7]}
+}
+
+pub struct UntypedMapCodec<T, O: CodecOps, C: Codec<T, O>> {
+    codec: C,
+    _phantom: PhantomData<(T, O, C)>,
+}
+
+impl<T, O: CodecOps, C: Codec<T, O>> Codec<BTreeMap<String, T>, O> for UntypedMapCodec<T, O, C> {
+    fn encode(&self, ops: &O, value: &BTreeMap<String, T>, ctx: &mut Context) -> DataResult<O::T> {
+        let mut entries = Vec::with_capacity(value.len());
+        for (key, value) in value {
+            ctx.push_field(key);
+            entries.push((key.clone(), self.codec.encode(ops, value, ctx)?));
+            ctx.pop();
+        }
+        Ok(ops.create_map(entries))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<BTreeMap<String, T>> {
+        let mut map = BTreeMap::new();
+        let Ok(view) = ops.get_map(value) else {
+            return Ok(map);
+        };
+        for (key, value) in view.entries() {
+            let decode = self.codec.decode(ops, value, ctx)?;
+            map.insert(key.to_string(), decode);
+        }
+        Ok(map)
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::Map(Box::new(self.codec.schema()))
+    }
+}
+
+/// Keeps a value as it is, without decoding it into a Rust type.
+pub struct DynamicValueCodec;
+
+impl<O: CodecOps> Codec<Dynamic<O>, O> for DynamicValueCodec {
+    fn encode(&self, _ops: &O, value: &Dynamic<O>, _ctx: &mut Context) -> DataResult<O::T> {
+        Ok(value.value().clone())
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, _ctx: &mut Context) -> DataResult<Dynamic<O>> {
+        Ok(Dynamic::new(value.clone(), ops.clone()))
+    }
+}
+
+impl<O: CodecOps> DefaultCodec<O> for Dynamic<O> {
+    fn codec() -> impl Codec<Self, O> {
+        DynamicValueCodec
+    }
+}
+
+impl<T: DefaultCodec<O>, O: CodecOps> DefaultCodec<O> for BTreeMap<String, T> {
+    fn codec() -> impl Codec<Self, O> {
+        UntypedMapCodec {
+            codec: T::codec(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub struct HashMapCodec<T, O: CodecOps, C: Codec<T, O>, S> {
+    pub(crate) codec: C,
+    pub(crate) decode_key: fn(&str) -> String,
+    pub(crate) encode_key: fn(&str) -> String,
+    pub(crate) _phantom: PhantomData<fn() -> (T, O, S)>,
+}
+
+#[cfg(feature = "std")]
+impl<T, O: CodecOps, C: Codec<T, O>, S> HashMapCodec<T, O, C, S> {
+    pub(crate) fn new(codec: C) -> Self {
+        HashMapCodec {
+            codec,
+            decode_key: ToString::to_string,
+            encode_key: ToString::to_string,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Transforms each key as it is decoded with `to` and as it is encoded with `from`, such as to normalize the casing
+    /// of keys. If two keys decode to the same key, the later one is kept.
+    pub fn key_map(mut self, to: fn(&str) -> String, from: fn(&str) -> String) -> Self {
+        self.decode_key = to;
+        self.encode_key = from;
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, O: CodecOps, C: Codec<T, O>, S: core::hash::BuildHasher + Default>
+    Codec<HashMap<String, T, S>, O> for HashMapCodec<T, O, C, S>
+{
+    fn encode(
+        &self,
+        ops: &O,
+        value: &HashMap<String, T, S>,
+        ctx: &mut Context,
+    ) -> DataResult<O::T> {
+        let mut entries = Vec::with_capacity(value.len());
+        for (key, value) in value {
+            ctx.push_field(key);
+            entries.push(((self.encode_key)(key), self.codec.encode(ops, value, ctx)?));
+            ctx.pop();
+        }
+        Ok(ops.create_map(entries))
+    }
+
+    fn decode(
+        &self,
+        ops: &O,
+        value: &O::T,
+        ctx: &mut Context,
+    ) -> DataResult<HashMap<String, T, S>> {
+        let view = ops.get_map(value)?;
+        let mut map = HashMap::with_capacity_and_hasher(view.len(), S::default());
+        for (key, value) in view.entries() {
+            ctx.push_field(key);
+            let decoded = self.codec.decode(ops, value, ctx)?;
+            ctx.pop();
+            map.insert((self.decode_key)(key), decoded);
+        }
+        Ok(map)
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::Map(Box::new(self.codec.schema()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: DefaultCodec<O>, O: CodecOps, S: core::hash::BuildHasher + Default> DefaultCodec<O>
+    for HashMap<String, T, S>
+{
+    fn codec() -> impl Codec<Self, O> {
+        HashMapCodec::new(T::codec())
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) struct KeyedMapCodec<K, V, O: CodecOps, Kc: Codec<K, O>, Vc: Codec<V, O>, S> {
+    pub(crate) key_codec: Kc,
+    pub(crate) value_codec: Vc,
+    pub(crate) _phantom: PhantomData<fn() -> (K, V, O, S)>,
+}
+
+#[cfg(feature = "std")]
+impl<K, V, O: CodecOps, Kc: Codec<K, O>, Vc: Codec<V, O>, S> KeyedMapCodec<K, V, O, Kc, Vc, S> {
+    fn key_to_string(&self, ops: &O, key: &K, ctx: &mut Context) -> DataResult<String> {
+        let encoded = self.key_codec.encode(ops, key, ctx)?;
+        if let Ok(string) = ops.get_string(&encoded) {
+            Ok(string)
+        } else if let Ok(long) = ops.get_long(&encoded) {
+            Ok(long.to_string())
+        } else if let Ok(double) = ops.get_double(&encoded) {
+            Ok(double.to_string())
+        } else {
+            Err(DataError::new_custom(
+                "map keys must be encoded as a string or a number",
+            ))
+        }
+    }
+
+    fn key_from_string(&self, ops: &O, key: &str, ctx: &mut Context) -> DataResult<K> {
+        let depth = ctx.depth();
+        let err = match self.key_codec.decode(ops, &ops.create_string(key), ctx) {
+            Ok(key) => return Ok(key),
+            Err(e) => e,
+        };
+        // The failed attempt may have left its path behind, which the retry must not be nested under.
+        if let Ok(long) = key.parse::<i64>() {
+            ctx.truncate(depth);
+            self.key_codec.decode(ops, &ops.create_long(&long), ctx)
+        } else if let Ok(double) = key.parse::<f64>() {
+            ctx.truncate(depth);
+            self.key_codec.decode(ops, &ops.create_double(&double), ctx)
+        } else {
+            Err(err)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<
+    K: Eq + core::hash::Hash,
+    V,
+    O: CodecOps,
+    Kc: Codec<K, O>,
+    Vc: Codec<V, O>,
+    S: core::hash::BuildHasher + Default,
+> Codec<HashMap<K, V, S>, O> for KeyedMapCodec<K, V, O, Kc, Vc, S>
+{
+    fn encode(&self, ops: &O, value: &HashMap<K, V, S>, ctx: &mut Context) -> DataResult<O::T> {
+        let mut entries = Vec::with_capacity(value.len());
+        for (key, value) in value {
+            let key = self.key_to_string(ops, key, ctx)?;
+            ctx.push_field(&key);
+            let value = self.value_codec.encode(ops, value, ctx)?;
+            ctx.pop();
+            entries.push((key, value));
+        }
+        Ok(ops.create_map(entries))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<HashMap<K, V, S>> {
+        let view = ops.get_map(value)?;
+        let mut map = HashMap::with_capacity_and_hasher(view.len(), S::default());
+        for (key, value) in view.entries() {
+            ctx.push_field(key);
+            let decoded_key = self.key_from_string(ops, key, ctx)?;
+            let decoded_value = self.value_codec.decode(ops, value, ctx)?;
+            ctx.pop();
+            map.insert(decoded_key, decoded_value);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        num::{NonZeroI64, NonZeroU32},
+        time::Duration,
+    };
+
+    use alloc::{
+        boxed::Box,
+        collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+    use json::JsonValue;
+    #[cfg(feature = "std")]
+    use std::collections::HashMap;
+
+    use crate::{
+        collections::NonEmptyVec,
+        result::DataError,
+        serialization::{
+            Codec, CodecAdapters, CodecOps, Codecs, DefaultCodec, MapCodecBuilder,
+            builtins::codecs::{ArcCodec, DynamicCodec, OptionCodec},
+            json::JsonOps,
+        },
+    };
+
+    #[test]
+    fn f64_codec() {
+        let value = 10.0;
+        let encoded = f64::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = f64::codec().decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn string_codec() {
+        let value = "Hello!".into();
+        let encoded = String::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = String::codec().decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn bool_codec() {
+        let value = true;
+        let encoded = bool::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = bool::codec().decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn numeric_codec() {
+        let value = 10;
+        let encoded = i32::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = i32::codec().decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+
+        let value = 10;
+        let encoded = i64::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = i64::codec().decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn list_codec() {
+        let value = vec![10, 20, 30];
+        let encoded = i32::codec()
+            .list_of()
+            .encode_start(&JsonOps, &value)
+            .unwrap();
+        let decoded = i32::codec()
+            .list_of()
+            .decode_start(&JsonOps, &encoded)
+            .unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn xmap_codec() {
+        let value = 15;
+        let codec = i32::codec().xmap(|x| x * 5, |x| x / 5);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn pair_codec() {
+        let value = (15, "Hello".to_string());
+        let codec = i32::codec().pair(String::codec());
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn bounded_codec() {
+        let value = 15;
+        let codec = i32::codec().bounded(1..30);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+
+        assert!(codec.encode_start(&JsonOps, &75).is_err());
+        assert!(codec.encode_start(&JsonOps, &1).is_ok());
+        assert!(codec.encode_start(&JsonOps, &30).is_err());
+        let error = codec
+            .decode_start(&JsonOps, &json::JsonValue::from(30))
+            .unwrap_err();
+        assert_eq!(
+            error.error().to_string(),
+            "value 30 must be in range [1, 30)"
+        );
+
+        let error = i32::codec()
+            .bounded(..=5)
+            .decode_start(&JsonOps, &json::JsonValue::from(6))
+            .unwrap_err();
+        assert_eq!(
+            error.error().to_string(),
+            "value 6 must be in range (-inf, 5]"
+        );
+
+        let codec = i32::codec().bounded_with_message(1..=100, "level must be between 1 and 100");
+        let error = codec
+            .decode_start(&JsonOps, &json::JsonValue::from(0))
+            .unwrap_err();
+        assert_eq!(error.error().to_string(), "level must be between 1 and 100");
+    }
+
+    #[test]
+    fn dynamic_codec() {
+        let value = 10.0;
+        let encoded = f64::codec()
+            .dynamic()
+            .encode_start(&JsonOps, &value)
+            .unwrap();
+        let decoded = f64::codec()
+            .dynamic()
+            .decode_start(&JsonOps, &encoded)
+            .unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn arc_codec() {
+        let value = 10.0;
+        let encoded = f64::codec().arc().encode_start(&JsonOps, &value).unwrap();
+        let decoded = f64::codec()
+            .dynamic()
+            .decode_start(&JsonOps, &encoded)
+            .unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    pub fn optional_codec() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Wrapper {
+            value: Option<f64>,
+        }
+
+        let codec = MapCodecBuilder::new()
+            .field(f64::codec().optional_field_of("value", |w: &Wrapper| &w.value))
+            .build(|value| Wrapper { value });
+
+        let value = Wrapper { value: None };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    pub fn default_codec() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Wrapper {
+            value: f64,
+        }
+
+        let codec = MapCodecBuilder::new()
+            .field(f64::codec().default_field_of("value", |w: &Wrapper| &w.value, || 12.1))
+            .build(|value| Wrapper { value });
+
+        let value = Wrapper { value: 0.0 };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(value, decoded);
+
+        let empty_obj = JsonValue::new_object();
+        let decoded = codec.decode_start(&JsonOps, &empty_obj).unwrap();
+        assert_eq!(Wrapper { value: 12.1 }, decoded);
+
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::object! { "value": "a" })
+                .is_err()
+        );
+    }
+
+    #[test]
+    pub fn recursive_codec() {
+        #[derive(Clone, PartialEq, Debug)]
+        struct LinkedList {
+            value: i32,
+            next: Option<Box<LinkedList>>,
+        }
+
+        impl LinkedList {
+            pub fn new(value: i32) -> LinkedList {
+                LinkedList { value, next: None }
+            }
+            pub fn seq(self, next: LinkedList) -> Self {
+                LinkedList {
+                    value: self.value,
+                    next: Some(Box::new(next)),
+                }
+            }
+        }
+
+        impl<O: CodecOps> DefaultCodec<O> for LinkedList
+        where
+            ArcCodec<LinkedList, JsonOps>: Codec<LinkedList, O>,
+        {
+            fn codec() -> impl Codec<Self, O> {
+                Codecs::recursive(|codec: DynamicCodec<LinkedList, JsonOps>| {
+                    MapCodecBuilder::new()
+                        .field(i32::codec().field_of("value", |l: &LinkedList| &l.value))
+                        .field(
+                            codec
+                                .boxed()
+                                .optional_field_of("next", |l: &LinkedList| &l.next),
+                        )
+                        .build(|value, next| LinkedList { value, next })
+                })
+            }
+        }
+
+        let value = LinkedList::new(1).seq(LinkedList::new(2).seq(LinkedList::new(3)));
+
+        let codec = LinkedList::codec();
+
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    pub fn dispatch_codec() {
+        #[derive(PartialEq, Debug)]
+        enum UnknownType {
+            Number(f64),
+            String(String),
+        }
+
+        impl UnknownType {
+            pub fn number_codec<O: CodecOps + 'static>() -> DynamicCodec<Self, O> {
+                f64::codec()
+                    .xmap(
+                        |x| UnknownType::Number(*x),
+                        |x| {
+                            let UnknownType::Number(x) = x else {
+                                panic!();
+                            };
+                            *x
+                        },
+                    )
+                    .dynamic()
+            }
+
+            pub fn string_codec<O: CodecOps + 'static>() -> DynamicCodec<Self, O> {
+                String::codec()
+                    .xmap(
+                        |x| UnknownType::String(x.clone()),
+                        |x| {
+                            let UnknownType::String(x) = x else {
+                                unreachable!();
+                            };
+                            x.clone()
+                        },
+                    )
+                    .dynamic()
+            }
+
+            pub fn codec<O: CodecOps + 'static>() -> impl Codec<Self, O> {
+                Codecs::dispatch(
+                    |value| match value {
+                        UnknownType::Number(_) => Ok(UnknownType::number_codec()),
+                        UnknownType::String(_) => Ok(UnknownType::string_codec()),
+                    },
+                    |ops: &O, value: &O::T| {
+                        if ops.get_string(value).is_ok() {
+                            Ok(UnknownType::string_codec())
+                        } else if ops.get_double(value).is_ok() {
+                            Ok(UnknownType::number_codec())
+                        } else {
+                            Err(DataError::unexpected_type("string | number"))
+                        }
+                    },
+                )
+            }
+        }
+
+        let value = UnknownType::Number(10.0);
+        let encoded = UnknownType::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = UnknownType::codec()
+            .decode_start(&JsonOps, &encoded)
+            .unwrap();
+        assert_eq!(value, decoded);
+
+        let value = UnknownType::String("foobar".to_string());
+        let encoded = UnknownType::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = UnknownType::codec()
+            .decode_start(&JsonOps, &encoded)
+            .unwrap();
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn untyped_map_codec() {
+        let mut map = BTreeMap::new();
+        map.insert("x".to_string(), 10.0);
+        map.insert("y".to_string(), 20.0);
+        map.insert("z".to_string(), 30.0);
+
+        let encoded = BTreeMap::codec().encode_start(&JsonOps, &map).unwrap();
+        let decoded = BTreeMap::codec().decode_start(&JsonOps, &encoded).unwrap();
+
+        assert_eq!(map, decoded);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_map_codec() {
+        let mut map = HashMap::new();
+        map.insert("x".to_string(), 10);
+        map.insert("y".to_string(), 20);
+
+        let codec = i32::codec().map_of();
+        let encoded = codec.encode_start(&JsonOps, &map).unwrap();
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(map, decoded);
+
+        let empty = HashMap::new();
+        let encoded = codec.encode_start(&JsonOps, &empty).unwrap();
+        assert_eq!(encoded, JsonValue::new_object());
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), empty);
+
+        let mut invalid = JsonValue::new_object();
+        invalid.insert("x", "not a number").unwrap();
+        assert!(codec.decode_start(&JsonOps, &invalid).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn keyed_map_codec() {
+        let mut map = HashMap::new();
+        map.insert(1u32, "one".to_string());
+        map.insert(20u32, "twenty".to_string());
+
+        let codec = Codecs::map_with_key(u32::codec(), String::codec());
+        let encoded = codec.encode_start(&JsonOps, &map).unwrap();
+        assert_eq!(encoded["20"], "twenty");
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(map, decoded);
+
+        let mut invalid = JsonValue::new_object();
+        invalid.insert("abc", "value").unwrap();
+        assert!(codec.decode_start(&JsonOps, &invalid).is_err());
+    }
+
+    #[test]
+    fn tuple_codec() {
+        let value = (1, "two".to_string(), true);
+        let codec = Codecs::tuple3(i32::codec(), String::codec(), bool::codec());
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::array![1, "two", true]);
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(value, decoded);
+
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::array![1, "two"])
+                .is_err()
+        );
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::array![1, "two", true, 4])
+                .is_err()
+        );
+
+        let value = (1u8, 2u16, 3u32, 4u64, 5i8, 6i16, 7i32, 8i64);
+        let codec = Codecs::tuple8(
+            u8::codec(),
+            u16::codec(),
+            u32::codec(),
+            u64::codec(),
+            i8::codec(),
+            i16::codec(),
+            i32::codec(),
+            i64::codec(),
+        );
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn array_codec() {
+        let value = [1u8, 2, 3];
+        let encoded = <[u8; 3]>::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = <[u8; 3]>::codec().decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(value, decoded);
+
+        let codec = f32::codec().array_of::<3>();
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::array![1.0, 2.0])
+                .is_err()
+        );
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::array![1.0, 2.0, 3.0, 4.0])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn option_codec() {
+        let value = vec![Some(1), None, Some(3)];
+        let codec = Option::<i32>::codec().list_of();
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::array![1, null, 3]);
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(value, decoded);
+
+        // Values without fields aren't mistaken for `None` in JSON.
+        let codec = OptionCodec {
+            inner: Codecs::unit(),
+            _phantom: core::marker::PhantomData,
+        };
+        let encoded = codec.encode_start(&JsonOps, &Some(())).unwrap();
+        assert_eq!(encoded, json::object! {});
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), Some(()));
+        assert_eq!(
+            codec.decode_start(&JsonOps, &JsonValue::Null).unwrap(),
+            None
+        );
+
+        // `Some(None)` is encoded the same as `None`, so it can't survive a round trip.
+        let codec = Option::<Option<i32>>::codec();
+        let encoded = codec.encode_start(&JsonOps, &Some(None)).unwrap();
+        assert_eq!(encoded, JsonValue::Null);
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), None);
+    }
+
+    #[test]
+    fn vec_codec() {
+        let value = vec![vec![1, 2], vec![], vec![3]];
+        let encoded = Vec::<Vec<i32>>::codec()
+            .encode_start(&JsonOps, &value)
+            .unwrap();
+        let decoded = Vec::<Vec<i32>>::codec()
+            .decode_start(&JsonOps, &encoded)
+            .unwrap();
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn char_codec() {
+        let value = 'é';
+        let encoded = char::codec().encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, "é");
+        let decoded = char::codec().decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(value, decoded);
+
+        assert!(char::codec().decode_start(&JsonOps, &"".into()).is_err());
+        assert!(char::codec().decode_start(&JsonOps, &"ab".into()).is_err());
+    }
+
+    #[test]
+    fn non_zero_codec() {
+        let value = NonZeroU32::new(5).unwrap();
+        let encoded = NonZeroU32::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded = NonZeroU32::codec()
+            .decode_start(&JsonOps, &encoded)
+            .unwrap();
+        assert_eq!(value, decoded);
+
+        assert!(
+            NonZeroI64::codec()
+                .decode_start(&JsonOps, &0.into())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn duration_codec() {
+        let codec = Codecs::duration_millis();
+        let value = Duration::from_millis(1500);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, 1500);
+        assert_eq!(value, codec.decode_start(&JsonOps, &encoded).unwrap());
+
+        let value = Duration::from_micros(1250);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(value, codec.decode_start(&JsonOps, &encoded).unwrap());
+        assert!(codec.decode_start(&JsonOps, &(-1).into()).is_err());
+        assert!(codec.encode_start(&JsonOps, &Duration::MAX).is_err());
+
+        let codec = Codecs::duration_secs();
+        let value = Duration::from_millis(2500);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, 2.5);
+        assert_eq!(value, codec.decode_start(&JsonOps, &encoded).unwrap());
+        assert!(codec.decode_start(&JsonOps, &(-1).into()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "net")]
+    fn net_codec() {
+        use core::net::{IpAddr, SocketAddr};
+
+        for value in ["127.0.0.1", "::1", "2001:db8::8a2e:370:7334"] {
+            let value: IpAddr = value.parse().unwrap();
+            let encoded = IpAddr::codec().encode_start(&JsonOps, &value).unwrap();
+            let decoded = IpAddr::codec().decode_start(&JsonOps, &encoded).unwrap();
+            assert_eq!(value, decoded);
+        }
+
+        for value in ["127.0.0.1:8080", "[::1]:25565"] {
+            let value: SocketAddr = value.parse().unwrap();
+            let encoded = SocketAddr::codec().encode_start(&JsonOps, &value).unwrap();
+            let decoded = SocketAddr::codec()
+                .decode_start(&JsonOps, &encoded)
+                .unwrap();
+            assert_eq!(value, decoded);
+        }
+
+        assert!(
+            IpAddr::codec()
+                .decode_start(&JsonOps, &"256.0.0.1".into())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn base64_codec() {
+        let codec = Codecs::bytes_base64();
+        for (bytes, expected) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foobar", "Zm9vYmFy"),
+        ] {
+            let value = bytes.to_vec();
+            let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+            assert_eq!(encoded, expected);
+            let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+            assert_eq!(value, decoded);
+        }
+
+        let value = (0..=255).collect::<Vec<u8>>();
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        assert!(codec.decode_start(&JsonOps, &"Zm9".into()).is_err());
+        assert!(codec.decode_start(&JsonOps, &"Zm9$".into()).is_err());
+        assert!(codec.decode_start(&JsonOps, &"Zg==Zm9v".into()).is_err());
+    }
+
+    #[test]
+    fn result_codec() {
+        let codec = Codecs::result(i32::codec(), String::codec());
+
+        let value = Ok(10);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "ok": 10 });
+        assert_eq!(value, codec.decode_start(&JsonOps, &encoded).unwrap());
+
+        let value = Err("failed".to_string());
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "err": "failed" });
+        assert_eq!(value, codec.decode_start(&JsonOps, &encoded).unwrap());
+
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::object! { "ok": 10, "err": "failed" })
+                .is_err()
+        );
+        assert!(codec.decode_start(&JsonOps, &json::object! {}).is_err());
+    }
+
+    #[test]
+    fn xmap_result_codec() {
+        let codec = String::codec().xmap_result(
+            |x| {
+                x.parse::<i32>()
+                    .map_err(|_| DataError::new_custom("not an integer"))
+            },
+            |x| x.to_string(),
+        );
+        let encoded = codec.encode_start(&JsonOps, &15).unwrap();
+        assert_eq!(encoded, "15");
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), 15);
+        assert!(codec.decode_start(&JsonOps, &"abc".into()).is_err());
+    }
+
+    #[test]
+    fn validate_codec() {
+        let codec = i32::codec().validate(|x| {
+            if x % 2 == 0 {
+                Ok(())
+            } else {
+                Err("value must be even".to_string())
+            }
+        });
+        let encoded = codec.encode_start(&JsonOps, &4).unwrap();
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), 4);
+
+        assert!(codec.encode_start(&JsonOps, &3).is_err());
+        let err = codec.decode_start(&JsonOps, &3.into()).unwrap_err();
+        assert_eq!(err.error().to_string(), "value must be even");
+    }
+
+    #[test]
+    fn bounded_list_codec() {
+        let codec = i32::codec().list_of_bounded(1..=3);
+        let value = vec![1, 2, 3];
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        assert!(codec.encode_start(&JsonOps, &vec![]).is_err());
+        assert!(codec.encode_start(&JsonOps, &vec![1, 2, 3, 4]).is_err());
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::array![1, 2, 3, 4])
+                .is_err()
+        );
+        assert!(codec.decode_start(&JsonOps, &json::array![]).is_err());
+    }
+
+    #[test]
+    fn non_empty_list_codec() {
+        let codec = Codecs::non_empty_list(i32::codec());
+        let mut value = NonEmptyVec::new(1);
+        value.push(2);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::array![1, 2]);
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        assert!(codec.decode_start(&JsonOps, &json::array![]).is_err());
+    }
+
+    #[test]
+    fn set_codec() {
+        let codec = Codecs::btree_set(i32::codec());
+        let value = BTreeSet::from([1, 2, 3]);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let duplicates = json::array![1, 2, 2, 3];
+        assert_eq!(codec.decode_start(&JsonOps, &duplicates).unwrap(), value);
+        let strict = Codecs::btree_set(i32::codec()).strict();
+        assert!(strict.decode_start(&JsonOps, &duplicates).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_set_codec() {
+        use std::collections::HashSet;
+
+        let value = HashSet::from(["a".to_string(), "b".to_string()]);
+        let encoded = HashSet::codec().encode_start(&JsonOps, &value).unwrap();
+        let decoded: HashSet<String> = HashSet::codec().decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(decoded, value);
+
+        let strict = Codecs::hash_set(String::codec()).strict();
+        assert!(
+            strict
+                .decode_start(&JsonOps, &json::array!["a", "a"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn pair_named_codec() {
+        let value = ("key".to_string(), 10);
+        let codec = String::codec().pair_named(i32::codec(), "key", "value");
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "key": "key", "value": 10 });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let default = String::codec().pair(i32::codec());
+        assert!(default.decode_start(&JsonOps, &encoded).is_err());
+    }
+
+    #[test]
+    fn string_dispatch_codec() {
+        #[derive(Debug, PartialEq)]
+        enum Shape {
+            Circle(f64),
+            Square(f64),
+        }
+
+        let codec = Codecs::string_dispatch(
+            "type",
+            |shape: &Shape| match shape {
+                Shape::Circle(_) => "circle",
+                Shape::Square(_) => "square",
+            },
+            [
+                (
+                    "circle",
+                    String::codec()
+                        .pair_named(f64::codec(), "type", "radius")
+                        .xmap(
+                            |(_, radius)| Shape::Circle(*radius),
+                            |shape| match shape {
+                                Shape::Circle(radius) => ("circle".to_string(), *radius),
+                                _ => unreachable!(),
+                            },
+                        )
+                        .dynamic(),
+                ),
+                (
+                    "square",
+                    MapCodecBuilder::new()
+                        .constant_field("type", String::codec(), "square")
+                        .field(f64::codec().fallible_field_of("side", |shape| match shape {
+                            Shape::Square(side) => Ok(side),
+                            _ => Err(DataError::new_custom("expected a square")),
+                        }))
+                        .build(Shape::Square)
+                        .dynamic(),
+                ),
+            ],
+        );
+
+        let value = Shape::Circle(2.0);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "type": "circle", "radius": 2.0 });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let value = Shape::Square(3.0);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "type": "square", "side": 3.0 });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let err = codec
+            .decode_start(&JsonOps, &json::object! { "type": "triangle" })
+            .unwrap_err();
+        assert_eq!(
+            err.error().to_string(),
+            "unknown variant \"triangle\", expected one of [circle, square]"
+        );
+
+        // A variant codec that doesn't write its tag can't be decoded, so encoding it fails.
+        let codec = Codecs::string_dispatch(
+            "type",
+            |_: &f64| "circle",
+            [(
+                "circle",
+                MapCodecBuilder::new()
+                    .field(f64::codec().field_of("radius", |x: &f64| x))
+                    .build(|radius| radius)
+                    .dynamic(),
+            )],
+        );
+        let err = codec.encode_start(&JsonOps, &1.0).unwrap_err();
+        assert_eq!(
+            err.error().to_string(),
+            "the codec of variant \"circle\" must write its tag under \"type\""
+        );
+    }
+
+    #[test]
+    fn tagged_codecs() {
+        #[derive(Debug, PartialEq)]
+        enum Event {
+            Click { x: i32, y: i32 },
+            Key(String),
+        }
+
+        fn tag_of(event: &Event) -> &str {
+            match event {
+                Event::Click { .. } => "click",
+                Event::Key(_) => "key",
+            }
+        }
+
+        fn variants<O: CodecOps + 'static>() -> [(&'static str, DynamicCodec<Event, O>); 2] {
+            [
+                (
+                    "click",
+                    i32::codec()
+                        .pair_named(i32::codec(), "x", "y")
+                        .xmap(
+                            |(x, y)| Event::Click { x: *x, y: *y },
+                            |event| match event {
+                                Event::Click { x, y } => (*x, *y),
+                                _ => unreachable!(),
+                            },
+                        )
+                        .dynamic(),
+                ),
+                (
+                    "key",
+                    MapCodecBuilder::new()
+                        .field(
+                            String::codec().fallible_field_of("key", |e: &Event| match e {
+                                Event::Key(key) => Ok(key),
+                                _ => Err(DataError::new_custom("expected a key event")),
+                            }),
+                        )
+                        .build(Event::Key)
+                        .dynamic(),
+                ),
+            ]
+        }
+
+        let codec = Codecs::internally_tagged("type", tag_of, variants());
+        let value = Event::Key("a".to_string());
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "key": "a", "type": "key" });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let codec = Codecs::adjacently_tagged("t", "c", tag_of, variants());
+        let value = Event::Click { x: 1, y: 2 };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(
+            encoded,
+            json::object! { "t": "click", "c": { "x": 1, "y": 2 } }
+        );
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let value = Event::Key("b".to_string());
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "t": "key", "c": { "key": "b" } });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn tagged_codecs_with_unit_variants() {
+        #[derive(Debug, PartialEq)]
+        enum Choice {
+            Nothing,
+            Maybe(Option<i32>),
+        }
+
+        fn tag_of(choice: &Choice) -> &str {
+            match choice {
+                Choice::Nothing => "nothing",
+                Choice::Maybe(_) => "maybe",
+            }
+        }
+
+        fn variants() -> [(&'static str, DynamicCodec<Choice, JsonOps>); 2] {
+            [
+                (
+                    "nothing",
+                    Codecs::unit().xmap(|_| Choice::Nothing, |_| ()).dynamic(),
+                ),
+                (
+                    "maybe",
+                    Option::<i32>::codec()
+                        .xmap(
+                            |value| Choice::Maybe(*value),
+                            |choice| match choice {
+                                Choice::Maybe(value) => *value,
+                                Choice::Nothing => None,
+                            },
+                        )
+                        .dynamic(),
+                ),
+            ]
+        }
+
+        // A payload that encodes to null is not a unit variant.
+        let codec = Codecs::externally_tagged(tag_of, variants()).unit_variants(["nothing"]);
+        for (value, expected) in [
+            (Choice::Nothing, JsonValue::from("nothing")),
+            (Choice::Maybe(None), json::object! { "maybe": null }),
+            (Choice::Maybe(Some(1)), json::object! { "maybe": 1 }),
+        ] {
+            let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+            assert_eq!(encoded, expected);
+            assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+        }
+        assert!(codec.decode_start(&JsonOps, &"maybe".into()).is_err());
+
+        let codec =
+            Codecs::adjacently_tagged("t", "c", tag_of, variants()).unit_variants(["nothing"]);
+        for (value, expected) in [
+            (Choice::Nothing, json::object! { "t": "nothing" }),
+            (
+                Choice::Maybe(None),
+                json::object! { "t": "maybe", "c": null },
+            ),
+            (
+                Choice::Maybe(Some(1)),
+                json::object! { "t": "maybe", "c": 1 },
+            ),
+        ] {
+            let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+            assert_eq!(encoded, expected);
+            assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+        }
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::object! { "t": "maybe" })
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_struct_codec() {
+        use crate::serialization::Codec as DeriveCodec;
+
+        #[derive(DeriveCodec, Debug, PartialEq)]
+        struct Player {
+            name: String,
+            #[datafix(rename = "hp")]
+            health: i32,
+            #[datafix(optional)]
+            nickname: Option<String>,
+        }
+
+        let codec = Player::codec();
+        let value = Player {
+            name: "steve".to_string(),
+            health: 20,
+            nickname: None,
+        };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "name": "steve", "hp": 20 });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let decoded = codec
+            .decode_start(
+                &JsonOps,
+                &json::object! { "name": "alex", "hp": 10, "nickname": "al" },
+            )
+            .unwrap();
+        assert_eq!(decoded.nickname, Some("al".to_string()));
+
+        #[derive(DeriveCodec, Debug, PartialEq)]
+        struct Marker {}
+
+        let encoded = Marker::codec().encode_start(&JsonOps, &Marker {}).unwrap();
+        assert_eq!(encoded, json::object! {});
+        assert_eq!(
+            Marker::codec().decode_start(&JsonOps, &encoded).unwrap(),
+            Marker {}
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_generic_struct_codec() {
+        use crate::serialization::Codec as DeriveCodec;
+
+        #[derive(DeriveCodec, Debug, PartialEq)]
+        struct Wrapper<T: Clone, U = i32> {
+            value: T,
+            extra: Vec<U>,
+        }
+
+        let codec = Wrapper::<String, i32>::codec();
+        let value = Wrapper {
+            value: "a".to_string(),
+            extra: vec![1, 2],
+        };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "value": "a", "extra": [1, 2] });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_enum_codecs() {
+        use crate::serialization::Codec as DeriveCodec;
+
+        #[derive(DeriveCodec, Debug, PartialEq)]
+        enum External {
+            Empty,
+            Maybe {
+                #[datafix(optional)]
+                value: Option<i32>,
+            },
+            #[datafix(rename = "point")]
+            Point {
+                x: i32,
+                #[datafix(rename = "why")]
+                y: i32,
+            },
+        }
+
+        let codec = External::codec();
+        let encoded = codec.encode_start(&JsonOps, &External::Empty).unwrap();
+        assert_eq!(encoded, JsonValue::String("Empty".to_string()));
+        assert_eq!(
+            codec.decode_start(&JsonOps, &encoded).unwrap(),
+            External::Empty
+        );
+        let value = External::Point { x: 1, y: 2 };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "point": { "x": 1, "why": 2 } });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::object! { "Circle": {} })
+                .is_err()
+        );
+        // Only unit variants are encoded as just the tag, not variants whose fields are all omitted.
+        let value = External::Maybe { value: None };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "Maybe": {} });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        #[derive(DeriveCodec, Debug, PartialEq)]
+        #[datafix(tag = "type")]
+        enum Internal {
+            Empty,
+            Named { name: String },
+        }
+
+        let codec = Internal::codec();
+        let encoded = codec.encode_start(&JsonOps, &Internal::Empty).unwrap();
+        assert_eq!(encoded, json::object! { "type": "Empty" });
+        assert_eq!(
+            codec.decode_start(&JsonOps, &encoded).unwrap(),
+            Internal::Empty
+        );
+        let value = Internal::Named {
+            name: "a".to_string(),
+        };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "type": "Named", "name": "a" });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        #[derive(DeriveCodec, Debug, PartialEq)]
+        #[datafix(tag = "t", content = "c")]
+        enum Adjacent<T> {
+            Empty,
+            Value {
+                value: T,
+            },
+            Maybe {
+                #[datafix(optional)]
+                value: Option<T>,
+            },
+        }
+
+        let codec = Adjacent::<i32>::codec();
+        let encoded = codec.encode_start(&JsonOps, &Adjacent::Empty).unwrap();
+        assert_eq!(encoded, json::object! { "t": "Empty" });
+        assert_eq!(
+            codec.decode_start(&JsonOps, &encoded).unwrap(),
+            Adjacent::Empty
+        );
+        let value = Adjacent::Value { value: 5 };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "t": "Value", "c": { "value": 5 } });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+        let value = Adjacent::Maybe { value: None };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "t": "Maybe", "c": {} });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn error_path() {
+        #[derive(Debug)]
+        struct Player {
+            health: f64,
+        }
+
+        let player = MapCodecBuilder::new()
+            .field(f64::codec().field_of("health", |p: &Player| &p.health))
+            .build(|health| Player { health });
+        let codec = MapCodecBuilder::new()
+            .field(player.list_of().field_of("players", |p: &Vec<Player>| p))
+            .build(|players| players);
+
+        let value = json::object! {
+            "players": [{ "health": 1 }, { "health": 2 }, { "health": "full" }]
+        };
+        let error = codec.decode_start(&JsonOps, &value).unwrap_err();
+        assert_eq!(error.span().path(), "players[2].health");
+        assert_eq!(
+            error.to_string(),
+            "players[2].health: Expected type number, found string"
+        );
+    }
+
+    #[test]
+    fn structured_errors() {
+        let error = f64::codec()
+            .decode_start(&JsonOps, &JsonValue::String("a".to_string()))
+            .unwrap_err();
+        assert!(matches!(
+            error.error(),
+            DataError::TypeMismatch { expected, found } if expected == "number" && found == "string"
+        ));
+
+        let error = i32::codec()
+            .bounded(1..10)
+            .decode_start(&JsonOps, &JsonValue::from(20))
+            .unwrap_err();
+        assert!(matches!(error.error(), DataError::OutOfRange { .. }));
+
+        let codec = MapCodecBuilder::new()
+            .field(i32::codec().field_of("a", |x: &i32| x))
+            .build(|a| a);
+        let error = codec.decode_start(&JsonOps, &json::object! {}).unwrap_err();
+        assert!(matches!(error.error(), DataError::KeyNotFoundInMap { key } if key == "a"));
+    }
+
+    #[test]
+    fn collect_record_errors() {
+        #[derive(Debug)]
+        struct Stats {
+            health: f64,
+            mana: f64,
+        }
+
+        let stats = MapCodecBuilder::new()
+            .field(f64::codec().field_of("health", |s: &Stats| &s.health))
+            .field(f64::codec().field_of("mana", |s: &Stats| &s.mana))
+            .build(|health, mana| Stats { health, mana });
+        let codec = MapCodecBuilder::new()
+            .field(String::codec().field_of("name", |p: &(String, Stats)| &p.0))
+            .field(stats.field_of("stats", |p: &(String, Stats)| &p.1))
+            .build(|name, stats| (name, stats));
+
+        let value = json::object! {
+            "name": 5,
+            "stats": { "health": "full", "mana": "empty" },
+            "extra": true
+        };
+        let errors = codec.decode_start_collecting(&JsonOps, &value).unwrap_err();
+        let messages = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "name: Expected type string, found number",
+                "stats.health: Expected type number, found string",
+                "stats.mana: Expected type number, found string",
+                "Unsupported key \"extra\" in object",
+            ]
+        );
+
+        let error = codec.decode_start(&JsonOps, &value).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "name: Expected type string, found number"
+        );
+
+        let value = json::object! { "name": "a", "stats": { "health": 1, "mana": 2 } };
+        let (name, stats) = codec.decode_start_collecting(&JsonOps, &value).unwrap();
+        assert_eq!(name, "a");
+        assert_eq!(stats.mana, 2.0);
+    }
+
+    #[test]
+    fn json_strings() {
+        let codec = MapCodecBuilder::new()
+            .field(String::codec().field_of("name", |x: &(String, f64)| &x.0))
+            .field(f64::codec().field_of("size", |x: &(String, f64)| &x.1))
+            .build(|name, size| (name, size));
+        let value = codec
+            .from_json_str(r#"{ "name": "a", "size": 2 }"#)
+            .unwrap();
+        assert_eq!(value, ("a".to_string(), 2.0));
+        assert_eq!(
+            codec.to_json_string(&value).unwrap(),
+            r#"{"name":"a","size":2}"#
+        );
+
+        assert_eq!(i32::codec().to_json_string(&5).unwrap(), "5");
+        assert!(f64::codec().from_json_str("{").is_err());
+        assert_eq!(
+            f64::codec().from_json_str("\"a\"").unwrap_err().to_string(),
+            "Expected type number, found string"
+        );
+    }
+
+    #[test]
+    fn field_aliases() {
+        let codec = MapCodecBuilder::new()
+            .field(
+                f64::codec()
+                    .field_of("font_size", |x: &(f64, Option<String>)| &x.0)
+                    .alias("fontSize")
+                    .alias("size"),
+            )
+            .field(
+                String::codec()
+                    .optional_field_of("font", |x: &(f64, Option<String>)| &x.1)
+                    .alias("typeface"),
+            )
+            .build(|size, font| (size, font));
+
+        let decoded = codec
+            .decode_start(&JsonOps, &json::object! { "size": 12, "typeface": "mono" })
+            .unwrap();
+        assert_eq!(decoded, (12.0, Some("mono".to_string())));
+        let decoded = codec
+            .decode_start(&JsonOps, &json::object! { "size": 12, "fontSize": 14 })
+            .unwrap();
+        assert_eq!(decoded, (14.0, None));
+        assert_eq!(
+            codec.encode_start(&JsonOps, &decoded).unwrap(),
+            json::object! { "font_size": 14 }
+        );
+
+        let error = codec
+            .decode_start(&JsonOps, &json::object! { "font": "mono" })
+            .unwrap_err();
+        assert!(matches!(error.error(), DataError::KeyNotFoundInMap { key } if key == "font_size"));
+    }
+
+    #[test]
+    fn versioned_codec() {
+        use crate::fixers::{Rules, Type, TypeRewriteRule};
+
+        let latest = MapCodecBuilder::new()
+            .field(i32::codec().field_of("health", |x: &(i32, i32)| &x.0))
+            .field(i32::codec().field_of("lives", |x: &(i32, i32)| &x.1))
+            .build(|health, lives| (health, lives));
+        let rules: [(u32, Box<dyn TypeRewriteRule<JsonOps>>); 2] = [
+            (1, Box::new(Rules::rename_field("hp", "health"))),
+            (
+                2,
+                Box::new(Rules::new_field(
+                    "lives",
+                    |ctx| ctx.create_int(3),
+                    |_| Type::Int,
+                )),
+            ),
+        ];
+        let codec = Codecs::versioned("version", rules, latest);
+
+        let value = (20, 1);
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(
+            encoded,
+            json::object! { "health": 20, "lives": 1, "version": 2 }
+        );
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let unversioned = json::object! { "hp": 5 };
+        assert_eq!(codec.decode_start(&JsonOps, &unversioned).unwrap(), (5, 3));
+        let version_1 = json::object! { "health": 5, "version": 1 };
+        assert_eq!(codec.decode_start(&JsonOps, &version_1).unwrap(), (5, 3));
+
+        let future = json::object! { "health": 5, "lives": 3, "version": 3 };
+        assert!(matches!(
+            codec.decode_start(&JsonOps, &future).unwrap_err().error(),
+            DataError::OutOfRange { .. }
+        ));
+    }
+
+    #[test]
+    fn lazy_codec() {
+        use core::cell::Cell;
+
+        let constructed = Cell::new(0);
+        let codec = Codecs::lazy(|| {
+            constructed.set(constructed.get() + 1);
+            i32::codec().list_of()
+        });
+        assert_eq!(constructed.get(), 0);
+
+        let value = vec![1, 2, 3];
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+        assert_eq!(constructed.get(), 1);
+    }
+
+    #[test]
+    fn borrowed_strings() {
+        use alloc::borrow::Cow;
+
+        let value = json::JsonValue::from("a long string that is not stored inline");
+        assert!(matches!(JsonOps.get_str(&value), Ok(Cow::Borrowed(_))));
+        assert!(JsonOps.get_str(&json::JsonValue::from(1)).is_err());
+
+        let codec = Codecs::cow_str();
+        let encoded = codec
+            .encode_start(&JsonOps, &Cow::Borrowed("name"))
+            .unwrap();
+        assert_eq!(encoded, json::JsonValue::from("name"));
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), "name");
+    }
+
+    #[test]
+    fn decode_iter() {
+        let value = json::array![1, 2, "three", 4];
+        let codec = i32::codec();
+        let mut iter = codec.decode_iter(&JsonOps, &value).unwrap();
+        assert_eq!(iter.next().unwrap().unwrap(), 1);
+        assert_eq!(iter.next().unwrap().unwrap(), 2);
+        let error = iter.next().unwrap().unwrap_err();
+        assert_eq!(error.span().path(), "[2]");
+        assert_eq!(iter.next().unwrap().unwrap(), 4);
+        assert!(iter.next().is_none());
+
+        let sum = codec
+            .decode_iter(&JsonOps, &json::array![1, 2, 3])
+            .unwrap()
+            .try_fold(0, |sum, x| x.map(|x| sum + x))
+            .unwrap();
+        assert_eq!(sum, 6);
+        assert!(codec.decode_iter(&JsonOps, &json::object! {}).is_err());
+    }
+
+    #[test]
+    fn wide_integers() {
+        let value = u128::MAX;
+        let encoded = u128::codec().encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::JsonValue::from(value.to_string()));
+        assert_eq!(
+            u128::codec().decode_start(&JsonOps, &encoded).unwrap(),
+            value
+        );
+
+        let value = i128::MIN;
+        let encoded = i128::codec().encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(
+            i128::codec().decode_start(&JsonOps, &encoded).unwrap(),
+            value
+        );
+
+        let too_large = json::JsonValue::from("340282366920938463463374607431768211456");
+        assert!(u128::codec().decode_start(&JsonOps, &too_large).is_err());
+        let invalid = json::JsonValue::from("12a");
+        assert!(i128::codec().decode_start(&JsonOps, &invalid).is_err());
+        assert!(
+            u128::codec()
+                .decode_start(&JsonOps, &json::JsonValue::from(1))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn number_as_string() {
+        let codec = Codecs::number_as_string::<u64, _>();
+        let id = 18_446_744_073_709_551_000;
+        let encoded = codec.encode_start(&JsonOps, &id).unwrap();
+        assert_eq!(encoded, json::JsonValue::from("18446744073709551000"));
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), id);
+
+        let codec = Codecs::number_as_string::<f64, _>();
+        let encoded = codec.encode_start(&JsonOps, &-1.5).unwrap();
+        assert_eq!(encoded, json::JsonValue::from("-1.5"));
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), -1.5);
+
+        let error = codec
+            .decode_start(&JsonOps, &json::JsonValue::from("abc"))
+            .unwrap_err();
+        assert!(error.to_string().contains("failed to parse \"abc\""));
+    }
+
+    #[test]
+    fn string_enum() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Priority {
+            Low,
+            High,
+        }
+
+        let variants = [("Low", Priority::Low), ("High", Priority::High)];
+        let codec = Codecs::string_enum(variants.clone(), true);
+        let encoded = codec.encode_start(&JsonOps, &Priority::High).unwrap();
+        assert_eq!(encoded, json::JsonValue::from("High"));
+        for string in ["High", "high", "HIGH"] {
+            let value = json::JsonValue::from(string);
+            assert_eq!(
+                codec.decode_start(&JsonOps, &value).unwrap(),
+                Priority::High
+            );
+        }
+        let error = codec
+            .decode_start(&JsonOps, &json::JsonValue::from("medium"))
+            .unwrap_err();
+        assert_eq!(
+            error.error().to_string(),
+            "unknown variant \"medium\", expected one of [Low, High]"
+        );
+
+        let codec = Codecs::string_enum(variants, false);
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from("high"))
+                .is_err()
+        );
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from("Low"))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn trimmed_strings() {
+        let codec = String::codec().trimmed();
+        let value = json::JsonValue::from("  a  b \n");
+        assert_eq!(codec.decode_start(&JsonOps, &value).unwrap(), "a  b");
+        assert_eq!(
+            codec.encode_start(&JsonOps, &" a ".to_string()).unwrap(),
+            json::JsonValue::from(" a ")
+        );
+        assert_eq!(
+            String::codec()
+                .trimmed_both()
+                .encode_start(&JsonOps, &" a ".to_string())
+                .unwrap(),
+            json::JsonValue::from("a")
+        );
+
+        let codec = String::codec().normalized();
+        assert_eq!(codec.decode_start(&JsonOps, &value).unwrap(), "a b");
+        assert_eq!(
+            codec
+                .encode_start(&JsonOps, &"\ta\t\tb".to_string())
+                .unwrap(),
+            json::JsonValue::from("\ta\t\tb")
+        );
+        let codec = String::codec().normalized_both();
+        assert_eq!(
+            codec
+                .encode_start(&JsonOps, &"\ta\t\tb".to_string())
+                .unwrap(),
+            json::JsonValue::from("a b")
+        );
+    }
+
+    #[test]
+    fn shared_values() {
+        use alloc::{rc::Rc, sync::Arc};
+
+        let codec = i32::codec().rc().list_of();
+        let shared = Rc::new(5);
+        let value = vec![shared.clone(), shared];
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::array![5, 5]);
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let codec = String::codec().arc_value();
+        let value = Arc::new("a".to_string());
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::JsonValue::from("a"));
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn cow_codec() {
+        use alloc::borrow::Cow;
+
+        let codec = Codecs::cow(i32::codec().list_of());
+        let borrowed: Cow<'static, [i32]> = Cow::Borrowed(&[1, 2]);
+        let encoded = codec.encode_start(&JsonOps, &borrowed).unwrap();
+        assert_eq!(encoded, json::array![1, 2]);
+        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert!(matches!(decoded, Cow::Owned(_)));
+        assert_eq!(decoded, borrowed);
+
+        let codec = MapCodecBuilder::new()
+            .field(Codecs::cow(String::codec()).field_of("name", |x: &Cow<'static, str>| x))
+            .build(|name| name);
+        let encoded = codec
+            .encode_start(&JsonOps, &Cow::Owned("a".to_string()))
+            .unwrap();
+        assert_eq!(encoded, json::object! { "name": "a" });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), "a");
+    }
+
+    #[test]
+    fn small_records() {
+        #[derive(Debug, PartialEq)]
+        struct Marker;
+
+        let codec = MapCodecBuilder::new().build(|| Marker);
+        let encoded = codec.encode_start(&JsonOps, &Marker).unwrap();
+        assert_eq!(encoded, json::object! {});
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), Marker);
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::object! { "a": 1 })
+                .is_err()
+        );
+        assert!(codec.decode_start(&JsonOps, &json::array![]).is_err());
+
+        #[derive(Debug, PartialEq)]
+        struct Id {
+            id: i32,
+        }
+
+        let codec = MapCodecBuilder::new()
+            .field(i32::codec().field_of("id", |x: &Id| &x.id))
+            .build(|id| Id { id });
+        let encoded = codec.encode_start(&JsonOps, &Id { id: 1 }).unwrap();
+        assert_eq!(encoded, json::object! { "id": 1 });
+        assert_eq!(
+            codec.decode_start(&JsonOps, &encoded).unwrap(),
+            Id { id: 1 }
+        );
+    }
+
+    #[test]
+    fn large_record() {
+        #[derive(Debug, PartialEq)]
+        struct Large {
+            f1: i32,
+            f2: i32,
+            f3: i32,
+            f4: i32,
+            f5: i32,
+            f6: i32,
+            f7: i32,
+            f8: i32,
+            f9: i32,
+            f10: i32,
+            f11: i32,
+            f12: i32,
+            f13: i32,
+            f14: i32,
+            f15: i32,
+            f16: i32,
+            f17: i32,
+            f18: i32,
+            f19: i32,
+            f20: i32,
+            f21: i32,
+            f22: i32,
+            f23: i32,
+            f24: i32,
+            f25: i32,
+            f26: i32,
+            f27: i32,
+            f28: i32,
+            f29: i32,
+            f30: i32,
+            f31: i32,
+            f32: i32,
+        }
+
+        let codec = MapCodecBuilder::new()
+            .field(i32::codec().field_of("f1", |x: &Large| &x.f1))
+            .field(i32::codec().field_of("f2", |x: &Large| &x.f2))
+            .field(i32::codec().field_of("f3", |x: &Large| &x.f3))
+            .field(i32::codec().field_of("f4", |x: &Large| &x.f4))
+            .field(i32::codec().field_of("f5", |x: &Large| &x.f5))
+            .field(i32::codec().field_of("f6", |x: &Large| &x.f6))
+            .field(i32::codec().field_of("f7", |x: &Large| &x.f7))
+            .field(i32::codec().field_of("f8", |x: &Large| &x.f8))
+            .field(i32::codec().field_of("f9", |x: &Large| &x.f9))
+            .field(i32::codec().field_of("f10", |x: &Large| &x.f10))
+            .field(i32::codec().field_of("f11", |x: &Large| &x.f11))
+            .field(i32::codec().field_of("f12", |x: &Large| &x.f12))
+            .field(i32::codec().field_of("f13", |x: &Large| &x.f13))
+            .field(i32::codec().field_of("f14", |x: &Large| &x.f14))
+            .field(i32::codec().field_of("f15", |x: &Large| &x.f15))
+            .field(i32::codec().field_of("f16", |x: &Large| &x.f16))
+            .field(i32::codec().field_of("f17", |x: &Large| &x.f17))
+            .field(i32::codec().field_of("f18", |x: &Large| &x.f18))
+            .field(i32::codec().field_of("f19", |x: &Large| &x.f19))
+            .field(i32::codec().field_of("f20", |x: &Large| &x.f20))
+            .field(i32::codec().field_of("f21", |x: &Large| &x.f21))
+            .field(i32::codec().field_of("f22", |x: &Large| &x.f22))
+            .field(i32::codec().field_of("f23", |x: &Large| &x.f23))
+            .field(i32::codec().field_of("f24", |x: &Large| &x.f24))
+            .field(i32::codec().field_of("f25", |x: &Large| &x.f25))
+            .field(i32::codec().field_of("f26", |x: &Large| &x.f26))
+            .field(i32::codec().field_of("f27", |x: &Large| &x.f27))
+            .field(i32::codec().field_of("f28", |x: &Large| &x.f28))
+            .field(i32::codec().field_of("f29", |x: &Large| &x.f29))
+            .field(i32::codec().field_of("f30", |x: &Large| &x.f30))
+            .field(i32::codec().field_of("f31", |x: &Large| &x.f31))
+            .field(i32::codec().field_of("f32", |x: &Large| &x.f32))
+            .build(
+                |f1,
+                 f2,
+                 f3,
+                 f4,
+                 f5,
+                 f6,
+                 f7,
+                 f8,
+                 f9,
+                 f10,
+                 f11,
+                 f12,
+                 f13,
+                 f14,
+                 f15,
+                 f16,
+                 f17,
+                 f18,
+                 f19,
+                 f20,
+                 f21,
+                 f22,
+                 f23,
+                 f24,
+                 f25,
+                 f26,
+                 f27,
+                 f28,
+                 f29,
+                 f30,
+                 f31,
+                 f32| Large {
+                    f1,
+                    f2,
+                    f3,
+                    f4,
+                    f5,
+                    f6,
+                    f7,
+                    f8,
+                    f9,
+                    f10,
+                    f11,
+                    f12,
+                    f13,
+                    f14,
+                    f15,
+                    f16,
+                    f17,
+                    f18,
+                    f19,
+                    f20,
+                    f21,
+                    f22,
+                    f23,
+                    f24,
+                    f25,
+                    f26,
+                    f27,
+                    f28,
+                    f29,
+                    f30,
+                    f31,
+                    f32,
+                },
+            );
+        let value = Large {
+            f1: 1,
+            f2: 2,
+            f3: 3,
+            f4: 4,
+            f5: 5,
+            f6: 6,
+            f7: 7,
+            f8: 8,
+            f9: 9,
+            f10: 10,
+            f11: 11,
+            f12: 12,
+            f13: 13,
+            f14: 14,
+            f15: 15,
+            f16: 16,
+            f17: 17,
+            f18: 18,
+            f19: 19,
+            f20: 20,
+            f21: 21,
+            f22: 22,
+            f23: 23,
+            f24: 24,
+            f25: 25,
+            f26: 26,
+            f27: 27,
+            f28: 28,
+            f29: 29,
+            f30: 30,
+            f31: 31,
+            f32: 32,
+        };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded["f32"], 32);
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn flattened_record() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Metadata {
+            author: String,
+            version: i32,
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Document {
+            title: String,
+            metadata: Metadata,
+        }
+
+        let metadata = MapCodecBuilder::new()
+            .field(String::codec().field_of("author", |x: &Metadata| &x.author))
+            .field(i32::codec().field_of("version", |x: &Metadata| &x.version))
+            .build(|author, version| Metadata { author, version });
+        let codec = MapCodecBuilder::new()
+            .field(String::codec().field_of("title", |x: &Document| &x.title))
+            .field(metadata.flatten_field_of(|x: &Document| &x.metadata))
+            .build(|title, metadata| Document { title, metadata });
+
+        let value = Document {
+            title: "a".to_string(),
+            metadata: Metadata {
+                author: "b".to_string(),
+                version: 2,
+            },
+        };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(
+            encoded,
+            json::object! { "title": "a", "author": "b", "version": 2 }
+        );
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let error = codec
+            .decode_start(
+                &JsonOps,
+                &json::object! { "title": "a", "author": "b", "version": 2, "extra": 1 },
+            )
+            .unwrap_err();
+        assert!(error.to_string().contains("Unsupported key \"extra\""));
+
+        let error = codec
+            .decode_start(&JsonOps, &json::object! { "title": "a", "author": "b" })
+            .unwrap_err();
+        assert!(matches!(error.error(), DataError::KeyNotFoundInMap { key } if key == "version"));
+        assert_eq!(error.span().path(), "version");
+    }
+
+    #[test]
+    fn range_codecs() {
+        let codec = Codecs::range(i32::codec());
+        let encoded = codec.encode_start(&JsonOps, &(1..5)).unwrap();
+        assert_eq!(encoded, json::object! { "start": 1, "end": 5 });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), 1..5);
+        let error = codec
+            .decode_start(&JsonOps, &json::object! { "start": 5, "end": 1 })
+            .unwrap_err();
+        assert!(matches!(error.error(), DataError::OutOfRange { .. }));
+        let reversed = core::ops::Range { start: 5, end: 1 };
+        assert!(codec.encode_start(&JsonOps, &reversed).is_err());
+
+        let codec = Codecs::range_inclusive(f64::codec());
+        let encoded = codec.encode_start(&JsonOps, &(0.5..=0.5)).unwrap();
+        assert_eq!(encoded, json::object! { "start": 0.5, "end": 0.5 });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), 0.5..=0.5);
+        let error = codec
+            .decode_start(&JsonOps, &json::object! { "start": 1 })
+            .unwrap_err();
+        assert_eq!(error.span().path(), "end");
+    }
+
+    #[test]
+    #[cfg(feature = "path")]
+    fn path_codec() {
+        use std::path::PathBuf;
+
+        let value = PathBuf::from("config/settings.json");
+        let encoded = PathBuf::codec().encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::JsonValue::from("config/settings.json"));
+        assert_eq!(
+            PathBuf::codec().decode_start(&JsonOps, &encoded).unwrap(),
+            value
+        );
+
+        #[cfg(unix)]
+        {
+            use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+            let invalid = PathBuf::from(OsStr::from_bytes(b"a\xff"));
+            assert!(PathBuf::codec().encode_start(&JsonOps, &invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn or_default() {
+        let codec = i32::codec().or_default();
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from("a"))
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from(5))
+                .unwrap(),
+            5
+        );
+
+        let codec = String::codec().list_of().or_default();
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::object! {})
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn try_else_errors() {
+        let codec = i32::codec()
+            .try_else(i32::codec().list_of().xmap(|x| x.len() as i32, |_| vec![]))
+            .try_else(Codecs::number_as_string());
+        assert_eq!(
+            codec.decode_start(&JsonOps, &json::array![1, 2]).unwrap(),
+            2
+        );
+
+        let error = codec
+            .decode_start(&JsonOps, &json::JsonValue::from(true))
+            .unwrap_err();
+        let DataError::AllFailed { sources } = error.error() else {
+            panic!("expected every alternative to fail, found {:?}", error);
+        };
+        assert_eq!(sources.len(), 3);
+        assert_eq!(
+            error.error().to_string(),
+            "every alternative failed: [Expected type number, found boolean; \
+             Expected type array, found boolean; Expected type string, found boolean]"
+        );
+        assert_eq!(error.span().path(), "");
+    }
+
+    #[test]
+    fn any_of() {
+        let codec = Codecs::any_of(vec![
+            i32::codec().dynamic(),
+            Codecs::number_as_string().dynamic(),
+            bool::codec().xmap(|b| *b as i32, |x| *x != 0).dynamic(),
+        ]);
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from(5))
+                .unwrap(),
+            5
+        );
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from("7"))
+                .unwrap(),
+            7
+        );
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from(true))
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            codec.encode_start(&JsonOps, &3).unwrap(),
+            json::JsonValue::from(3)
+        );
+
+        let error = codec.decode_start(&JsonOps, &json::array![]).unwrap_err();
+        let DataError::AllFailed { sources } = error.error() else {
+            panic!("expected every codec to fail, found {:?}", error);
+        };
+        assert_eq!(sources.len(), 3);
+
+        let codec = codec.encode_with(|x| if *x < 0 { 1 } else { 0 });
+        assert_eq!(
+            codec.encode_start(&JsonOps, &-3).unwrap(),
+            json::JsonValue::from("-3")
+        );
+        assert_eq!(
+            codec.encode_start(&JsonOps, &3).unwrap(),
+            json::JsonValue::from(3)
+        );
+
+        let codec = codec.encode_with(|_| 3);
+        assert_eq!(
+            codec.encode_start(&JsonOps, &3).unwrap_err().to_string(),
+            "any_of selected codec 3 to encode with, but there are only 3 codecs"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn map_key_transform() {
+        let codec = i32::codec()
+            .map_of()
+            .key_map(|key| key.to_lowercase(), |key| ["Key", key].concat());
+
+        let decoded = codec
+            .decode_start(&JsonOps, &json::object! { "Alpha": 1, "BETA": 2 })
+            .unwrap();
+        assert_eq!(decoded.get("alpha"), Some(&1));
+        assert_eq!(decoded.get("beta"), Some(&2));
+
+        let mut map = HashMap::new();
+        map.insert("x".to_string(), 10);
+        assert_eq!(
+            codec.encode_start(&JsonOps, &map).unwrap(),
+            json::object! { "Keyx": 10 }
+        );
+    }
+
+    #[test]
+    fn constant_codec() {
+        let codec = Codecs::constant(String::codec(), "player");
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from("player"))
+                .unwrap(),
+            "player"
+        );
+        assert_eq!(
+            codec.encode_start(&JsonOps, &"player".to_string()).unwrap(),
+            json::JsonValue::from("player")
+        );
+
+        let error = codec
+            .decode_start(&JsonOps, &json::JsonValue::from("monster"))
+            .unwrap_err();
+        assert_eq!(
+            error.error().to_string(),
+            "expected constant \"player\", found \"monster\""
+        );
+        assert!(
+            codec
+                .encode_start(&JsonOps, &"monster".to_string())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn constant_record_field() {
+        #[derive(Debug, PartialEq)]
+        struct Player {
+            name: String,
+        }
+
+        let codec = MapCodecBuilder::new()
+            .constant_field("type", String::codec(), "player")
+            .field(String::codec().field_of("name", |x: &Player| &x.name))
+            .constant_field("version", i32::codec(), 2)
+            .build(|name| Player { name });
+
+        let player = Player {
+            name: "Steve".to_string(),
+        };
+        let encoded = codec.encode_start(&JsonOps, &player).unwrap();
+        assert_eq!(
+            encoded,
+            json::object! { "name": "Steve", "type": "player", "version": 2 }
+        );
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), player);
+
+        let error = codec
+            .decode_start(
+                &JsonOps,
+                &json::object! { "name": "Steve", "type": "monster", "version": 2 },
+            )
+            .unwrap_err();
+        assert_eq!(error.span().path(), "type");
+        assert!(
+            codec
+                .decode_start(
+                    &JsonOps,
+                    &json::object! { "name": "Steve", "type": "player" }
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn clamped_codec() {
+        let codec = i32::codec().clamped(1..=10);
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from(5))
+                .unwrap(),
+            5
+        );
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from(-3))
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from(40))
+                .unwrap(),
+            10
+        );
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from("5"))
+                .is_err()
+        );
+        assert_eq!(
+            codec.encode_start(&JsonOps, &40).unwrap(),
+            json::JsonValue::from(40)
+        );
+    }
+
+    #[test]
+    fn lenient_number_codec() {
+        let codec = Codecs::lenient_number::<i32, JsonOps>();
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from(42))
+                .unwrap(),
+            42
+        );
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from("42"))
+                .unwrap(),
+            42
+        );
+        assert_eq!(
+            codec.encode_start(&JsonOps, &42).unwrap(),
+            json::JsonValue::from(42)
+        );
+
+        let error = codec
+            .decode_start(&JsonOps, &json::JsonValue::from("forty-two"))
+            .unwrap_err();
+        assert_eq!(
+            error.error().to_string(),
+            "failed to parse \"forty-two\": invalid digit found in string"
+        );
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::JsonValue::from(true))
+                .is_err()
+        );
+        assert!(
+            i32::codec()
+                .decode_start(&JsonOps, &json::JsonValue::from("42"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn recursive_sync_codec() {
+        #[derive(Clone, PartialEq, Debug)]
+        struct LinkedList {
+            value: i32,
+            next: Option<Box<LinkedList>>,
+        }
+
+        let codec =
+            Codecs::recursive_sync(|codec: super::SyncRecursiveCodec<LinkedList, JsonOps>| {
+                MapCodecBuilder::new()
+                    .field(i32::codec().field_of("value", |l: &LinkedList| &l.value))
+                    .field(
+                        codec
+                            .boxed()
+                            .optional_field_of("next", |l: &LinkedList| &l.next),
+                    )
+                    .constant_field("type", String::codec(), "node")
+                    .build(|value, next| LinkedList { value, next })
+            });
+
+        let value = LinkedList {
+            value: 1,
+            next: Some(Box::new(LinkedList {
+                value: 2,
+                next: None,
+            })),
+        };
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+                    assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn recursive_codec_before_initialization() {
+        let codec = Codecs::recursive(|codec: DynamicCodec<i32, JsonOps>| {
+            let error = codec
+                .decode_start(&JsonOps, &JsonValue::from(1))
+                .unwrap_err();
+            assert_eq!(
+                error.error().to_string(),
+                "recursive codec used before initialization"
+            );
+            i32::codec()
+        });
+        assert_eq!(
+            codec.decode_start(&JsonOps, &JsonValue::from(1)).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn any_codec() {
+        use crate::serialization::AnyCodec;
+
+        let codecs: Vec<AnyCodec<i32>> = vec![
+            i32::codec().dynamic(),
+            Codecs::number_as_string().dynamic(),
+            i32::codec().clamped(0..=10).dynamic(),
+        ];
+        let encoded: Vec<JsonValue> = codecs
+            .iter()
+            .map(|codec| codec.encode_start(&JsonOps, &5).unwrap())
+            .collect();
+        assert_eq!(
+            encoded,
+            vec![JsonValue::from(5), JsonValue::from("5"), JsonValue::from(5)]
+        );
+    }
+
+    #[test]
+    fn list_codec_encode_error() {
+        let codec = i32::codec().bounded(0..10).list_of();
+        let error = codec
+            .encode_start(&JsonOps, &vec![1, 2, 30, 40])
+            .unwrap_err();
+        assert_eq!(error.span().path(), "[2]");
+        assert_eq!(
+            error.error().to_string(),
+            "value 30 must be in range [0, 10)"
+        );
+    }
+
+    #[test]
+    fn encode_into_existing_map() {
+        #[derive(Debug, PartialEq)]
+        struct Window {
+            width: i32,
+            height: i32,
+        }
+
+        let codec = MapCodecBuilder::new()
+            .field(i32::codec().field_of("width", |w: &Window| &w.width))
+            .field(i32::codec().field_of("height", |w: &Window| &w.height))
+            .build(|width, height| Window { width, height });
+
+        let mut stored = json::object! { "width": 800, "height": 600, "title": "game" };
+        codec
+            .encode_into(
+                &JsonOps,
+                &Window {
+                    width: 1920,
+                    height: 1080,
+                },
+                &mut stored,
+            )
+            .unwrap();
+        assert_eq!(
+            stored,
+            json::object! { "width": 1920, "height": 1080, "title": "game" }
+        );
+
+        let mut scalar = JsonValue::from(1);
+        i32::codec().encode_into(&JsonOps, &5, &mut scalar).unwrap();
+        assert_eq!(scalar, JsonValue::from(5));
+    }
+
+    #[test]
+    fn decode_patch() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Graphics {
+            width: i32,
+            vsync: bool,
+        }
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Config {
+            volume: i32,
+            graphics: Graphics,
+        }
+
+        let graphics = MapCodecBuilder::new()
+            .field(i32::codec().field_of("width", |g: &Graphics| &g.width))
+            .field(bool::codec().field_of("vsync", |g: &Graphics| &g.vsync))
+            .build(|width, vsync| Graphics { width, vsync });
+        let codec = MapCodecBuilder::new()
+            .field(i32::codec().field_of("volume", |c: &Config| &c.volume))
+            .field(graphics.field_of("graphics", |c: &Config| &c.graphics))
+            .build(|volume, graphics| Config { volume, graphics });
+
+        let defaults = Config {
+            volume: 100,
+            graphics: Graphics {
+                width: 800,
+                vsync: true,
+            },
+        };
+        let mut config = defaults.clone();
+        codec
+            .decode_patch(
+                &JsonOps,
+                &json::object! { "graphics": { "vsync": false } },
+                &mut config,
+            )
+            .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                volume: 100,
+                graphics: Graphics {
+                    width: 800,
+                    vsync: false,
+                },
+            }
+        );
+
+        let mut config = defaults.clone();
+        assert!(
+            codec
+                .decode_patch(&JsonOps, &json::object! { "volume": "loud" }, &mut config)
+                .is_err()
+        );
+        assert_eq!(config, defaults);
+
+        // Map fields are merged like records, so the patch keeps the entries it doesn't mention.
+        let codec = BTreeMap::<String, i32>::codec();
+        let mut limits = BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+        codec
+            .decode_patch(&JsonOps, &json::object! { "b": 3 }, &mut limits)
+            .unwrap();
+        assert_eq!(
+            limits,
+            BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 3)])
+        );
+    }
+
+    #[test]
+    fn codec_schema() {
+        use crate::serialization::{Schema, SchemaField};
+        use core::ops::Bound;
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Settings {
+            volume: i32,
+            name: Option<String>,
+            tags: Vec<String>,
+            quality: u8,
+        }
 
-    #[test]
-    fn f64_codec() {
-        let value = 10.0;
-        let encoded = f64::codec().encode_start(&JsonOps, &value).unwrap();
-        let decoded = f64::codec().decode_start(&JsonOps, &encoded).unwrap();
+        let codec = MapCodecBuilder::<_, JsonOps>::new()
+            .field(
+                i32::codec()
+                    .bounded(0..=100)
+                    .field_of("volume", |s: &Settings| &s.volume),
+            )
+            .field(String::codec().optional_field_of("name", |s: &Settings| &s.name))
+            .field(
+                String::codec()
+                    .list_of()
+                    .field_of("tags", |s: &Settings| &s.tags),
+            )
+            .field(
+                Codecs::string_enum([("low", 0), ("high", 1)], false)
+                    .field_of("quality", |s: &Settings| &s.quality),
+            )
+            .constant_field("type", String::codec(), "settings")
+            .build(|volume, name, tags, quality| Settings {
+                volume,
+                name,
+                tags,
+                quality,
+            });
 
-        assert_eq!(value, decoded);
+        let field = |name: &str, schema, required| SchemaField {
+            name: name.into(),
+            schema,
+            required,
+        };
+        assert_eq!(
+            codec.schema(),
+            Schema::Record(vec![
+                field(
+                    "volume",
+                    Schema::Number {
+                        integer: true,
+                        minimum: Bound::Included(0.0),
+                        maximum: Bound::Included(100.0),
+                    },
+                    true
+                ),
+                field("name", Schema::String, false),
+                field("tags", Schema::List(Box::new(Schema::String)), true),
+                field(
+                    "quality",
+                    Schema::Enum(vec!["low".into(), "high".into()]),
+                    true
+                ),
+                field("type", Schema::String, true),
+            ])
+        );
+
+        assert_eq!(
+            codec.schema().to_json_schema(),
+            json::object! {
+                "type": "object",
+                "properties": {
+                    "volume": { "type": "integer", "minimum": 0.0, "maximum": 100.0 },
+                    "name": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "quality": { "enum": ["low", "high"] },
+                    "type": { "type": "string" },
+                },
+                "required": ["volume", "tags", "quality", "type"],
+                "additionalProperties": false,
+            }
+        );
+        assert_eq!(
+            Codecs::recursive(|codec: DynamicCodec<i32, JsonOps>| codec).schema(),
+            Schema::Any
+        );
     }
 
     #[test]
-    fn string_codec() {
-        let value = "Hello!".into();
-        let encoded = String::codec().encode_start(&JsonOps, &value).unwrap();
-        let decoded = String::codec().decode_start(&JsonOps, &encoded).unwrap();
+    fn codec_describe() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Config {
+            volume: i32,
+            gamma: f64,
+            mods: Vec<String>,
+            title: Option<String>,
+        }
 
-        assert_eq!(value, decoded);
+        let codec = MapCodecBuilder::<_, JsonOps>::new()
+            .field(
+                i32::codec()
+                    .bounded(0..=100)
+                    .field_of("volume", |c: &Config| &c.volume),
+            )
+            .field(f64::codec().field_of("gamma", |c: &Config| &c.gamma))
+            .field(
+                String::codec()
+                    .list_of()
+                    .field_of("mods", |c: &Config| &c.mods),
+            )
+            .field(String::codec().optional_field_of("title", |c: &Config| &c.title))
+            .build(|volume, gamma, mods, title| Config {
+                volume,
+                gamma,
+                mods,
+                title,
+            });
+        assert_eq!(
+            codec.describe(),
+            "{ volume: integer in [0, 100], gamma: number, mods: [string], title?: string }"
+        );
+        assert_eq!(
+            Codec::<Option<bool>, JsonOps>::describe(&Option::<bool>::codec()),
+            "unit | boolean"
+        );
     }
 
     #[test]
-    fn bool_codec() {
-        let value = true;
-        let encoded = bool::codec().encode_start(&JsonOps, &value).unwrap();
-        let decoded = bool::codec().decode_start(&JsonOps, &encoded).unwrap();
+    fn xmap_checked_codec() {
+        let codec = i32::codec().xmap_checked(|x| *x as i64 * 2, |x| (*x / 2) as i32);
+        assert_eq!(
+            codec.decode_start(&JsonOps, &JsonValue::from(4)).unwrap(),
+            8
+        );
+    }
 
-        assert_eq!(value, decoded);
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "xmap functions are not inverses at values[1]: 3 was mapped back to 2"
+    )]
+    fn xmap_checked_codec_mismatch() {
+        #[derive(Debug, PartialEq)]
+        struct Values {
+            values: Vec<i32>,
+        }
+
+        let codec = MapCodecBuilder::<_, JsonOps>::new()
+            .field(
+                i32::codec()
+                    .xmap_checked(|x| x + 1, |x| x / 2)
+                    .list_of()
+                    .field_of("values", |v: &Values| &v.values),
+            )
+            .build(|values| Values { values });
+        let _ = codec.decode_start(&JsonOps, &json::object! { "values": [0, 2] });
     }
 
     #[test]
-    fn numeric_codec() {
-        let value = 10;
-        let encoded = i32::codec().encode_start(&JsonOps, &value).unwrap();
-        let decoded = i32::codec().decode_start(&JsonOps, &encoded).unwrap();
+    fn dependent_field_codec() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Payload {
+            Number(f64),
+            Text(String),
+        }
 
-        assert_eq!(value, decoded);
+        #[derive(Debug, PartialEq)]
+        struct Setting {
+            kind: String,
+            data: Payload,
+        }
 
-        let value = 10;
-        let encoded = i64::codec().encode_start(&JsonOps, &value).unwrap();
-        let decoded = i64::codec().decode_start(&JsonOps, &encoded).unwrap();
+        let codec = MapCodecBuilder::<_, JsonOps>::new()
+            .field(String::codec().field_of("type", |s: &Setting| &s.kind))
+            .dependent_field(
+                "data",
+                |s: &Setting| &s.data,
+                |kind: &String| match kind.as_str() {
+                    "number" => Ok(f64::codec()
+                        .xmap(
+                            |x| Payload::Number(*x),
+                            |x| match x {
+                                Payload::Number(x) => *x,
+                                Payload::Text(_) => f64::NAN,
+                            },
+                        )
+                        .dynamic()),
+                    "text" => Ok(String::codec()
+                        .xmap(
+                            |x| Payload::Text(x.clone()),
+                            |x| match x {
+                                Payload::Text(x) => x.clone(),
+                                Payload::Number(_) => String::new(),
+                            },
+                        )
+                        .dynamic()),
+                    kind => Err(DataError::unknown_variant(kind, ["number", "text"])),
+                },
+            )
+            .build(|kind, data| Setting { kind, data });
 
-        assert_eq!(value, decoded);
-    }
+        let value = Setting {
+            kind: "text".to_string(),
+            data: Payload::Text("hello".to_string()),
+        };
+        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(encoded, json::object! { "type": "text", "data": "hello" });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
 
-    #[test]
-    fn list_codec() {
-        let value = vec![10, 20, 30];
-        let encoded = i32::codec()
-            .list_of()
-            .encode_start(&JsonOps, &value)
-            .unwrap();
-        let decoded = i32::codec()
-            .list_of()
-            .decode_start(&JsonOps, &encoded)
+        let decoded = codec
+            .decode_start(&JsonOps, &json::object! { "type": "number", "data": 2.5 })
             .unwrap();
+        assert_eq!(decoded.data, Payload::Number(2.5));
 
-        assert_eq!(value, decoded);
+        let error = codec
+            .decode_start(&JsonOps, &json::object! { "type": "number", "data": "2.5" })
+            .unwrap_err();
+        assert_eq!(error.span().path(), "data");
+
+        let error = codec
+            .decode_start(&JsonOps, &json::object! { "type": "color", "data": 2.5 })
+            .unwrap_err();
+        assert_eq!(
+            error.error().to_string(),
+            "unknown variant \"color\", expected one of [number, text]"
+        );
+
+        let errors = codec
+            .decode_start_collecting(&JsonOps, &json::object! { "type": 1, "data": 2.5 })
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span().path(), "type");
+        let mut ctx = crate::serialization::Context::new_collecting();
+        let error = codec
+            .decode(
+                &JsonOps,
+                &json::object! { "type": 1, "data": 2.5 },
+                &mut ctx,
+            )
+            .unwrap_err();
+        assert_eq!(error.to_string(), "1 errors occurred while decoding");
     }
 
     #[test]
-    fn xmap_codec() {
-        let value = 15;
-        let codec = i32::codec().xmap(|x| x * 5, |x| x / 5);
+    fn either_tagged_codec() {
+        let point = || i32::codec().pair_named(i32::codec(), "x", "y");
+        let codec = Codecs::either_tagged("kind", "start", "end", point(), point());
+
+        let value: either::Either<(i32, i32), (i32, i32)> = either::Either::Right((1, 2));
         let encoded = codec.encode_start(&JsonOps, &value).unwrap();
-        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        assert_eq!(encoded, json::object! { "x": 1, "y": 2, "kind": "end" });
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+        assert_eq!(
+            codec
+                .decode_start(&JsonOps, &json::object! { "kind": "start", "x": 3, "y": 4 })
+                .unwrap(),
+            either::Either::Left((3, 4))
+        );
 
-        assert_eq!(value, decoded);
+        let error = codec
+            .decode_start(
+                &JsonOps,
+                &json::object! { "kind": "middle", "x": 3, "y": 4 },
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "kind: unknown variant \"middle\", expected one of [start, end]"
+        );
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::object! { "x": 3, "y": 4 })
+                .is_err()
+        );
     }
 
     #[test]
-    fn pair_codec() {
-        let value = (15, "Hello".to_string());
-        let codec = i32::codec().pair(String::codec());
-        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
-        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
-
-        assert_eq!(value, decoded);
+    fn of_fns_codec() {
+        let codec = Codecs::of_fns(
+            |ops: &JsonOps, value: &bool, _ctx| {
+                Ok(ops.create_string(if *value { "yes" } else { "no" }))
+            },
+            |ops: &JsonOps, value, _ctx| match ops.get_string(value)?.as_str() {
+                "yes" => Ok(true),
+                "no" => Ok(false),
+                _ => Err(DataError::new_custom("expected \"yes\" or \"no\"")),
+            },
+        )
+        .list_of();
+        let encoded = codec.encode_start(&JsonOps, &vec![true, false]).unwrap();
+        assert_eq!(encoded, json::array!["yes", "no"]);
+        assert_eq!(
+            codec.decode_start(&JsonOps, &encoded).unwrap(),
+            vec![true, false]
+        );
+        assert!(
+            codec
+                .decode_start(&JsonOps, &json::array!["maybe"])
+                .is_err()
+        );
     }
 
     #[test]
-    fn bounded_codec() {
-        let value = 15;
-        let codec = i32::codec().bounded(1..30);
-        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
-        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+    #[cfg(feature = "std")]
+    fn context_hook() {
+        use crate::serialization::{Context, TraceEvent};
+        use alloc::sync::Arc;
+        use std::sync::Mutex;
 
-        assert_eq!(value, decoded);
+        let codec = MapCodecBuilder::<_, JsonOps>::new()
+            .field(String::codec().field_of("name", |x: &(String, Vec<i32>)| &x.0))
+            .field(
+                i32::codec()
+                    .list_of()
+                    .field_of("scores", |x: &(String, Vec<i32>)| &x.1),
+            )
+            .build(|name, scores| (name, scores));
 
-        assert!(codec.encode_start(&JsonOps, &75).is_err());
-        assert!(codec.encode_start(&JsonOps, &1).is_ok());
-        assert!(codec.encode_start(&JsonOps, &30).is_err());
-    }
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let hook_events = events.clone();
+        let hook = move |ctx: &Context, event: TraceEvent<'_>| {
+            let kind = match event {
+                TraceEvent::Enter(_) => "enter ",
+                TraceEvent::Exit(_) => "exit ",
+                TraceEvent::Failed(_) => "failed ",
+            };
+            hook_events
+                .lock()
+                .unwrap()
+                .push([kind, &ctx.path()].concat());
+        };
 
-    #[test]
-    fn dynamic_codec() {
-        let value = 10.0;
-        let encoded = f64::codec()
-            .dynamic()
-            .encode_start(&JsonOps, &value)
-            .unwrap();
-        let decoded = f64::codec()
-            .dynamic()
-            .decode_start(&JsonOps, &encoded)
-            .unwrap();
-        assert_eq!(decoded, value);
+        let mut ctx = Context::new().with_hook(hook.clone());
+        codec
+            .decode(
+                &JsonOps,
+                &json::object! { "name": "a", "scores": [1, "two"] },
+                &mut ctx,
+            )
+            .unwrap_err();
+        assert_eq!(
+            core::mem::take(&mut *events.lock().unwrap()),
+            [
+                "enter name",
+                "exit name",
+                "enter scores",
+                "enter scores[0]",
+                "exit scores[0]",
+                "enter scores[1]",
+            ]
+        );
+
+        // Fields given up on while collecting are reported as failed, not exited.
+        let mut ctx = Context::new_collecting().with_hook(hook);
+        codec
+            .decode(
+                &JsonOps,
+                &json::object! { "name": 1, "scores": [] },
+                &mut ctx,
+            )
+            .unwrap_err();
+        assert_eq!(
+            core::mem::take(&mut *events.lock().unwrap()),
+            ["enter name", "failed name", "enter scores", "exit scores"]
+        );
+        // The recorded error doesn't keep the hook.
+        let errors = ctx.take_errors();
+        assert_eq!(errors.len(), 1);
+        errors[0].span().clone().push_field("x");
+        assert!(events.lock().unwrap().is_empty());
     }
 
     #[test]
-    fn arc_codec() {
-        let value = 10.0;
-        let encoded = f64::codec().arc().encode_start(&JsonOps, &value).unwrap();
-        let decoded = f64::codec()
-            .dynamic()
-            .decode_start(&JsonOps, &encoded)
-            .unwrap();
-        assert_eq!(decoded, value);
+    fn codec_errors_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<crate::result::CodecError>();
+        assert_send_sync::<crate::serialization::Context>();
     }
 
     #[test]
-    pub fn optional_codec() {
-        #[derive(Clone, Debug, PartialEq)]
-        struct Wrapper {
-            value: Option<f64>,
+    fn int_enum_codec() {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        enum Difficulty {
+            Peaceful,
+            Easy,
+            Hard,
         }
 
-        let codec = MapCodecBuilder::new()
-            .field(f64::codec().optional_field_of("value", |w: &Wrapper| &w.value))
-            .build(|value| Wrapper { value });
-
-        let value = Wrapper { value: None };
+        let codec = Codecs::int_enum([
+            (0i64, Difficulty::Peaceful),
+            (1, Difficulty::Easy),
+            (3, Difficulty::Hard),
+        ])
+        .list_of();
+        let value = vec![Difficulty::Hard, Difficulty::Peaceful];
         let encoded = codec.encode_start(&JsonOps, &value).unwrap();
-        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
-        assert_eq!(value, decoded);
+        assert_eq!(encoded, json::array![3, 0]);
+        assert_eq!(codec.decode_start(&JsonOps, &encoded).unwrap(), value);
+
+        let error = codec
+            .decode_start(&JsonOps, &json::array![1, 2])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "[1]: unknown variant \"2\", expected one of [0, 1, 3]"
+        );
     }
 
     #[test]
-    pub fn default_codec() {
-        #[derive(Clone, Debug, PartialEq)]
-        struct Wrapper {
-            value: f64,
+    #[cfg(feature = "std")]
+    fn capture_rest_field() {
+        use crate::serialization::Dynamic;
+        use std::collections::HashMap;
+
+        struct Profile {
+            name: String,
+            id: i32,
+            rest: HashMap<String, Dynamic<JsonOps>>,
         }
 
+        let ids = MapCodecBuilder::new()
+            .field(i32::codec().field_of("id", |id: &i32| id).alias("uid"))
+            .build(|id| id);
         let codec = MapCodecBuilder::new()
-            .field(f64::codec().default_field_of("value", |w: &Wrapper| &w.value, || 12.1))
-            .build(|value| Wrapper { value });
+            .field(String::codec().field_of("name", |p: &Profile| &p.name))
+            .field(ids.flatten_field_of(|p: &Profile| &p.id))
+            .capture_rest(|p: &Profile| &p.rest)
+            .build(|name, id, rest| Profile { name, id, rest });
 
-        let value = Wrapper { value: 0.0 };
-        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
-        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
-        assert_eq!(value, decoded);
+        let value = json::object! { "name": "a", "id": 3, "theme": "dark", "keys": [1, 2] };
+        let mut decoded = codec.decode_start(&JsonOps, &value).unwrap();
+        assert_eq!(decoded.name, "a");
+        assert_eq!(decoded.id, 3);
+        let mut keys = decoded.rest.keys().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, ["keys", "theme"]);
+        assert_eq!(codec.encode_start(&JsonOps, &decoded).unwrap(), value);
 
-        let empty_obj = JsonValue::new_object();
-        let decoded = codec.decode_start(&JsonOps, &empty_obj).unwrap();
-        assert_eq!(Wrapper { value: 12.1 }, decoded);
+        decoded.rest.insert(
+            "name".to_string(),
+            Dynamic::new(JsonValue::from("b"), JsonOps),
+        );
+        assert_eq!(
+            codec
+                .encode_start(&JsonOps, &decoded)
+                .unwrap_err()
+                .to_string(),
+            "Duplicate key \"name\" in object"
+        );
+
+        let aliased = codec
+            .decode_start(
+                &JsonOps,
+                &json::object! { "name": "a", "uid": 4, "theme": "dark" },
+            )
+            .unwrap();
+        assert_eq!(aliased.id, 4);
+        assert_eq!(aliased.rest.keys().collect::<Vec<_>>(), ["theme"]);
     }
 
     #[test]
-    pub fn recursive_codec() {
-        #[derive(Clone, PartialEq, Debug)]
-        struct LinkedList {
-            value: i32,
-            next: Option<Box<LinkedList>>,
+    fn recovered_errors_are_not_collected() {
+        let inner = MapCodecBuilder::<_, JsonOps>::new()
+            .field(i32::codec().field_of("x", |x: &(i32,)| &x.0))
+            .build(|x| (x,));
+        let codec = MapCodecBuilder::<_, JsonOps>::new()
+            .field(inner.or_default().field_of("inner", |x: &((i32,),)| &x.0))
+            .build(|inner| (inner,));
+        assert_eq!(
+            codec
+                .decode_start_collecting(&JsonOps, &json::object! { "inner": { "x": "one" } })
+                .unwrap(),
+            ((0,),)
+        );
+
+        let record = MapCodecBuilder::<_, JsonOps>::new()
+            .field(i32::codec().field_of("x", |x: &i32| x))
+            .build(|x| x);
+        let codec = record.try_else(i32::codec());
+        let errors = codec
+            .decode_start_collecting(&JsonOps, &json::object! { "x": "one" })
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        let DataError::AllFailed { sources } = errors[0].error() else {
+            panic!("expected AllFailed, got {}", errors[0].error());
+        };
+        assert_eq!(sources.len(), 2);
+        assert!(
+            sources
+                .iter()
+                .all(|source| !matches!(source, DataError::Collected { .. }))
+        );
+    }
+
+    #[test]
+    fn unsigned_integer_range() {
+        assert_eq!(
+            u8::codec()
+                .decode_start(&JsonOps, &JsonValue::from(200))
+                .unwrap(),
+            200
+        );
+        assert_eq!(
+            u8::codec().encode_start(&JsonOps, &255).unwrap(),
+            JsonValue::from(255)
+        );
+        for value in [-1, 256] {
+            let error = u8::codec()
+                .decode_start(&JsonOps, &JsonValue::from(value))
+                .unwrap_err();
+            assert!(matches!(error.error(), DataError::OutOfRange { .. }));
         }
+        assert_eq!(
+            u64::codec()
+                .decode_start(&JsonOps, &JsonValue::from(i64::MAX))
+                .unwrap(),
+            i64::MAX as u64
+        );
+        let encoded = u64::codec().encode_start(&JsonOps, &u64::MAX).unwrap();
+        assert_eq!(encoded, JsonValue::from(u64::MAX));
+        assert_eq!(
+            u64::codec().decode_start(&JsonOps, &encoded).unwrap(),
+            u64::MAX
+        );
+    }
 
-        impl LinkedList {
-            pub fn new(value: i32) -> LinkedList {
-                LinkedList { value, next: None }
-            }
-            pub fn seq(self, next: LinkedList) -> Self {
-                LinkedList {
-                    value: self.value,
-                    next: Some(Box::new(next)),
-                }
-            }
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derive_raw_and_optional_fields() {
+        #[derive(crate::serialization::Codec, Debug, PartialEq)]
+        struct Item {
+            r#type: String,
+            #[datafix(optional)]
+            count: core::option::Option<i32>,
+            scores: Vec<Option<i32>>,
         }
 
-        impl<O: CodecOps> DefaultCodec<O> for LinkedList
-        where
-            ArcCodec<LinkedList, JsonOps>: Codec<LinkedList, O>,
-        {
-            fn codec() -> impl Codec<Self, O> {
-                Codecs::recursive(|codec: DynamicCodec<LinkedList, JsonOps>| {
-                    MapCodecBuilder::new()
-                        .field(i32::codec().field_of("value", |l: &LinkedList| &l.value))
-                        .field(
-                            codec
-                                .boxed()
-                                .optional_field_of("next", |l: &LinkedList| &l.next),
-                        )
-                        .build(|value, next| LinkedList { value, next })
-                })
+        let value = Item {
+            r#type: "stone".into(),
+            count: None,
+            scores: vec![Some(1), None],
+        };
+        let encoded = Item::codec().encode_start(&JsonOps, &value).unwrap();
+        assert_eq!(
+            encoded,
+            json::object! { "type": "stone", "scores": [1, null] }
+        );
+        assert_eq!(
+            Item::codec().decode_start(&JsonOps, &encoded).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn open_record_json_schema() {
+        let codec = MapCodecBuilder::<_, JsonOps>::new()
+            .field(String::codec().field_of("name", |x: &(String, BTreeMap<String, i32>)| &x.0))
+            .field(
+                BTreeMap::<String, i32>::codec()
+                    .flatten_field_of(|x: &(String, BTreeMap<String, i32>)| &x.1),
+            )
+            .build(|name, rest| (name, rest));
+        assert_eq!(
+            codec.schema().to_json_schema(),
+            json::object! {
+                "type": "object",
+                "properties": { "name": { "type": "string" } },
+                "required": ["name"],
+                "additionalProperties": { "type": "integer" },
             }
-        }
+        );
+        assert_eq!(codec.describe(), "{ name: string, [string]: integer }");
 
-        let value = LinkedList::new(1).seq(LinkedList::new(2).seq(LinkedList::new(3)));
+        assert_eq!(
+            crate::serialization::Schema::Unit.to_json_schema(),
+            json::object! { "type": "object", "maxProperties": 0 }
+        );
+    }
 
-        let codec = LinkedList::codec();
+    #[test]
+    fn list_encode_error_path() {
+        let codec = Codecs::tuple3(i32::codec(), Codecs::duration_millis(), bool::codec());
+        let error = codec
+            .encode_start(&JsonOps, &(1, Duration::MAX, true))
+            .unwrap_err();
+        assert_eq!(error.span().path(), "[1]");
 
-        let encoded = codec.encode_start(&JsonOps, &value).unwrap();
-        let decoded = codec.decode_start(&JsonOps, &encoded).unwrap();
+        let codec = Codecs::duration_millis().array_of();
+        let error = codec
+            .encode_start(&JsonOps, &[Duration::ZERO, Duration::MAX])
+            .unwrap_err();
+        assert_eq!(error.span().path(), "[1]");
+    }
 
-        assert_eq!(value, decoded);
+    #[test]
+    #[should_panic(expected = "the start of a clamped range must not be greater than its end")]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn clamped_codec_rejects_reversed_range() {
+        let _ = <i32 as DefaultCodec<JsonOps>>::codec().clamped(10..=1);
     }
 
     #[test]
-    pub fn dispatch_codec() {
-        #[derive(PartialEq, Debug)]
-        enum UnknownType {
-            Number(f64),
-            String(String),
-        }
+    #[cfg(feature = "std")]
+    fn keyed_map_key_retry_restores_path() {
+        use crate::{result::DataResult, serialization::Context};
 
-        impl UnknownType {
-            pub fn number_codec<O: CodecOps + 'static>() -> DynamicCodec<Self, O> {
-                f64::codec()
-                    .xmap(
-                        |x| UnknownType::Number(*x),
-                        |x| {
-                            let UnknownType::Number(x) = x else {
-                                panic!();
-                            };
-                            *x
-                        },
-                    )
-                    .dynamic()
-            }
+        // Fails on strings after pushing a field, and decodes numbers to the path they were decoded at.
+        struct PathKey;
 
-            pub fn string_codec<O: CodecOps + 'static>() -> DynamicCodec<Self, O> {
-                String::codec()
-                    .xmap(
-                        |x| UnknownType::String(x.clone()),
-                        |x| {
-                            let UnknownType::String(x) = x else {
-                                unreachable!();
-                            };
-                            x.clone()
-                        },
-                    )
-                    .dynamic()
+        impl Codec<String, JsonOps> for PathKey {
+            fn encode(
+                &self,
+                ops: &JsonOps,
+                value: &String,
+                _ctx: &mut Context,
+            ) -> DataResult<JsonValue> {
+                Ok(ops.create_string(value))
             }
 
-            pub fn codec<O: CodecOps + 'static>() -> impl Codec<Self, O> {
-                Codecs::dispatch(
-                    |value| match value {
-                        UnknownType::Number(_) => Ok(UnknownType::number_codec()),
-                        UnknownType::String(_) => Ok(UnknownType::string_codec()),
-                    },
-                    |ops: &O, value: &O::T| {
-                        if ops.get_string(value).is_ok() {
-                            Ok(UnknownType::string_codec())
-                        } else if ops.get_double(value).is_ok() {
-                            Ok(UnknownType::number_codec())
-                        } else {
-                            Err(DataError::unexpected_type("string | number"))
-                        }
-                    },
-                )
+            fn decode(
+                &self,
+                ops: &JsonOps,
+                value: &JsonValue,
+                ctx: &mut Context,
+            ) -> DataResult<String> {
+                if value.is_string() {
+                    ctx.push_field("inner");
+                    return Err(DataError::new_custom("expected a number"));
+                }
+                ops.get_long(value)?;
+                Ok(ctx.path())
             }
         }
 
-        let value = UnknownType::Number(10.0);
-        let encoded = UnknownType::codec().encode_start(&JsonOps, &value).unwrap();
-        let decoded = UnknownType::codec()
-            .decode_start(&JsonOps, &encoded)
-            .unwrap();
-        assert_eq!(value, decoded);
-
-        let value = UnknownType::String("foobar".to_string());
-        let encoded = UnknownType::codec().encode_start(&JsonOps, &value).unwrap();
-        let decoded = UnknownType::codec()
-            .decode_start(&JsonOps, &encoded)
+        let codec = Codecs::map_with_key(PathKey, bool::codec());
+        let decoded: HashMap<String, bool> = codec
+            .decode_start(&JsonOps, &json::object! { "5": true })
             .unwrap();
-        assert_eq!(value, decoded);
+        assert_eq!(decoded.keys().collect::<Vec<_>>(), ["5"]);
     }
 
     #[test]
-    fn untyped_map_codec() {
-        let mut map = BTreeMap::new();
-        map.insert("x".to_string(), 10.0);
-        map.insert("y".to_string(), 20.0);
-        map.insert("z".to_string(), 30.0);
-
-        let encoded = BTreeMap::codec().encode_start(&JsonOps, &map).unwrap();
-        let decoded = BTreeMap::codec().decode_start(&JsonOps, &encoded).unwrap();
+    fn bounded_schema_bounds() {
+        use crate::serialization::Schema;
+        use core::ops::Bound;
 
-        assert_eq!(map, decoded);
+        assert_eq!(
+            Codec::<u64, JsonOps>::schema(&u64::codec().bounded(..10)),
+            Schema::Number {
+                integer: true,
+                minimum: Bound::Unbounded,
+                maximum: Bound::Excluded(10.0),
+            }
+        );
+        let secs = Codecs::duration_secs::<JsonOps>()
+            .bounded(Duration::from_secs(1)..=Duration::from_secs(60));
+        assert_eq!(secs.schema(), Schema::number());
     }
 }
@@ -1,17 +1,46 @@
-use core::{cell::OnceCell, marker::PhantomData};
+use core::{fmt::Debug, marker::PhantomData};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
 
 use crate::{
-    serialization::builtins::records::*,
-    serialization::{Codec, CodecOps},
+    result::DataResult,
+    serialization::builtins::{codecs::DynamicCodec, records::*},
+    serialization::{Codec, CodecAdapters, CodecOps, Codecs},
 };
 
 use super::records::UnitCodec;
+#[cfg(feature = "std")]
+use crate::serialization::Dynamic;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
+/// Builds a codec for a record, encoded as a map with one key per field.
+/// Fields are added one at a time with `field`, and a record can have up to 32 fields.
 pub struct MapCodecBuilder<C, O: CodecOps> {
     pub(crate) codec: C,
+    pub(crate) constants: Vec<(String, ConstantField<O>)>,
     pub(crate) _phantom: PhantomData<fn() -> O>,
 }
 
+impl<C, O: CodecOps + 'static> MapCodecBuilder<C, O> {
+    /// Adds a key to the record that always holds `value`, without a matching field in the struct.
+    /// This is useful for markers such as `"type": "player"` or a format version.
+    ///
+    /// The value is written when encoding, and decoding fails if the key is missing or holds any other value.
+    /// Constant fields don't count towards the limit of 32 fields.
+    pub fn constant_field<T: Clone + PartialEq + Debug + Send + Sync + 'static>(
+        mut self,
+        name: impl Into<String>,
+        codec: impl Codec<T, O> + Send + Sync + 'static,
+        value: impl Into<T>,
+    ) -> Self {
+        let value = value.into();
+        let codec = Codecs::constant(codec, value.clone()).xmap(|_| (), move |_| value.clone());
+        self.constants.push((name.into(), Box::new(codec)));
+        self
+    }
+}
+
 #[doc(hidden)]
 impl<O: CodecOps> Default for MapCodecBuilder<UnitCodec, O> {
     fn default() -> Self {
@@ -23,6 +52,7 @@ impl<O: CodecOps> MapCodecBuilder<UnitCodec, O> {
     pub fn new() -> MapCodecBuilder<UnitCodec, O> {
         MapCodecBuilder {
             codec: UnitCodec {},
+            constants: Vec::new(),
             _phantom: PhantomData,
         }
     }
@@ -40,12 +70,51 @@ impl<O: CodecOps> MapCodecBuilder<UnitCodec, O> {
         MapCodecBuilder {
             codec: MapCodec1 {
                 codec1: field,
-                into_struct: OnceCell::new(),
+                into_struct: None,
                 _phantom: PhantomData,
             },
+            constants: self.constants,
             _phantom: PhantomData,
         }
     }
+
+    /// Adds a field that captures every entry of the map that no other field claims, and writes the entries back
+    /// when encoding. This lets a record pass through keys it doesn't know about, such as keys added by a newer
+    /// version of the format, without losing them. A record with this field accepts any unknown key.
+    ///
+    /// Flattened records only take the keys of their own fields, so their keys aren't captured. Encoding fails
+    /// if a captured entry has the same key as a field of the record.
+    #[cfg(feature = "std")]
+    pub fn capture_rest<Struct>(
+        self,
+        getter: fn(&Struct) -> &HashMap<String, Dynamic<O>>,
+    ) -> MapCodecBuilder<
+        MapCodec1<
+            HashMap<String, Dynamic<O>>,
+            RestCodec,
+            HashMap<String, Dynamic<O>>,
+            RestField<Struct, O>,
+            Struct,
+            O,
+        >,
+        O,
+    > {
+        self.field(RestField {
+            getter,
+            codec: RestCodec,
+        })
+    }
+
+    /// Builds a codec for a record with no fields, which is encoded as an empty map.
+    pub fn build<Struct>(self, into_struct: fn() -> Struct) -> impl Codec<Struct, O> {
+        ConstantFieldsCodec {
+            codec: MapCodec0 {
+                into_struct,
+                _phantom: PhantomData,
+            },
+            constants: self.constants,
+        }
+    }
 }
 
 /// I'm sorry. Not even God himself understands this macro anymore.
@@ -54,11 +123,13 @@ macro_rules! impl_record_codec_builder {
         type: $type:ident,
         fields: { $(
             $field:ident: $name:ident[$codec:ident; $field_type:ident; $field_return_type:ident]
+            $(after $previous:ident: $previous_return_type:ident)?
         ),* },
         next:
         $next_field_name:ident:
         $next_type:ident as
         $next_name:ident[$next_codec:ident; $next_field_type:ident; $next_field_return_type:ident]
+        after $last_field:ident: $last_return_type:ident
     ) => {
         #[doc(hidden)]
         impl<
@@ -66,7 +137,7 @@ macro_rules! impl_record_codec_builder {
                 $name,
                 $codec: Codec<$name, O>,
                 $field_return_type,
-                $field_type: MapFieldGetter<$name, $codec, Struct, $field_return_type, O>
+                $field_type: MapFieldGetter<$name, $codec, Struct, $field_return_type, O $(, $previous_return_type)?>
             ),*
             ,  O: CodecOps, Struct
         > MapCodecBuilder<
@@ -88,7 +159,7 @@ macro_rules! impl_record_codec_builder {
                     $next_name,
                     $next_codec,
                     Struct,
-                    $next_field_return_type, O>>
+                    $next_field_return_type, O, $last_return_type>>
             (
                 self,
                 field: NxtField
@@ -102,16 +173,67 @@ macro_rules! impl_record_codec_builder {
                     codec: $next_type {
                         $($field: self.codec.$field),*,
                         $next_field_name: field,
-                        into_struct: OnceCell::new(),
+                        into_struct: None,
                         _phantom: PhantomData,
                     },
+                    constants: self.constants,
                     _phantom: PhantomData
                 }
             }
 
+            #[cfg(feature = "std")]
+            pub fn capture_rest(
+                self,
+                getter: fn(&Struct) -> &HashMap<String, Dynamic<O>>,
+            ) -> MapCodecBuilder<
+                $next_type<
+                    $($name, $codec, $field_return_type, $field_type),*,
+                    HashMap<String, Dynamic<O>>, RestCodec, HashMap<String, Dynamic<O>>, RestField<Struct, O>, Struct, O
+                >, O
+            > {
+                self.field(RestField {
+                    getter,
+                    codec: RestCodec,
+                })
+            }
+
+            /// Adds a field whose codec is chosen from the value of the field added just before it, such as
+            /// the payload of a record tagged by a `"type"` field. When decoding, `selector` receives what
+            /// the previous field decoded to, and when encoding, the value of the previous field.
+            ///
+            /// The selector's error is reported at this field. If the previous field fails while errors
+            /// are being collected, this field is skipped and only the previous field's error is reported.
+            pub fn dependent_field<
+                $next_name,
+                S: Fn(&$last_return_type) -> DataResult<DynamicCodec<$next_name, O>>,
+            >(
+                self,
+                name: impl Into<String>,
+                getter: fn(&Struct) -> &$next_name,
+                selector: S,
+            ) -> MapCodecBuilder<
+                $next_type<
+                    $($name, $codec, $field_return_type, $field_type),*,
+                    $next_name, DependentCodec<$next_name, O>, $next_name,
+                    DependentField<$last_return_type, $next_name, Struct, O, S>, Struct, O
+                >, O
+            > {
+                self.field(DependentField {
+                    field_name: name.into(),
+                    getter,
+                    selector,
+                    codec: DependentCodec { _phantom: PhantomData },
+                    _phantom: PhantomData,
+                })
+            }
+
             pub fn build(self, into_struct: fn($($field_return_type),*) -> Struct) -> impl Codec<Struct, O> {
-                self.codec.into_struct.set(into_struct).unwrap();
-                self.codec
+                let mut codec = self.codec;
+                codec.into_struct = Some(into_struct);
+                ConstantFieldsCodec {
+                    codec,
+                    constants: self.constants,
+                }
             }
         }
     };
@@ -120,14 +242,21 @@ macro_rules! impl_record_codec_builder {
 macro_rules! impl_record_codec_builder_last {
     (
         type: $type:ident,
-        fields: { $($field:ident: $name:ident[$codec:ident; $field_type:ident; $field_return_type:ident]),* }
+        fields: { $(
+            $field:ident: $name:ident[$codec:ident; $field_type:ident; $field_return_type:ident]
+            $(after $previous:ident: $previous_return_type:ident)?
+        ),* }
     ) => {
         #[doc(hidden)]
-        impl<$($name, $codec: Codec<$name, O>, $field_return_type, $field_type: MapFieldGetter<$name, $codec, Struct, $field_return_type, O>),*, Struct,  O: CodecOps>
+        impl<$($name, $codec: Codec<$name, O>, $field_return_type, $field_type: MapFieldGetter<$name, $codec, Struct, $field_return_type, O $(, $previous_return_type)?>),*, Struct,  O: CodecOps>
             MapCodecBuilder<$type<$($name, $codec, $field_return_type, $field_type),*, Struct, O>, O> {
             pub fn build(self, into_struct: fn($($field_return_type),*) -> Struct) -> impl Codec<Struct, O> {
-                self.codec.into_struct.set(into_struct).unwrap();
-                self.codec
+                let mut codec = self.codec;
+                codec.into_struct = Some(into_struct);
+                ConstantFieldsCodec {
+                    codec,
+                    constants: self.constants,
+                }
             }
         }
     };
@@ -136,57 +265,189 @@ macro_rules! impl_record_codec_builder_last {
 impl_record_codec_builder! {
     type: MapCodec1,
     fields: { codec1: P1[P1C; P1F; P1R] },
-    next: codec2: MapCodec2 as P2[P2C; P2F; P2R]
+    next: codec2: MapCodec2 as P2[P2C; P2F; P2R] after codec1: P1R
 }
 
 impl_record_codec_builder! {
     type: MapCodec2,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] },
-    next: codec3: MapCodec3 as P3[P3C; P3F; P3R]
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R },
+    next: codec3: MapCodec3 as P3[P3C; P3F; P3R] after codec2: P2R
 }
 impl_record_codec_builder! {
     type: MapCodec3,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R], codec3: P3[P3C; P3F; P3R] },
-    next: codec4: MapCodec4 as P4[P4C; P4F; P4R]
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R },
+    next: codec4: MapCodec4 as P4[P4C; P4F; P4R] after codec3: P3R
 }
 
 impl_record_codec_builder! {
     type: MapCodec4,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R], codec3: P3[P3C; P3F; P3R], codec4: P4[P4C; P4F; P4R] },
-    next: codec5: MapCodec5 as P5[P5C; P5F; P5R]
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R },
+    next: codec5: MapCodec5 as P5[P5C; P5F; P5R] after codec4: P4R
 }
 
 impl_record_codec_builder! {
     type: MapCodec5,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R], codec3: P3[P3C; P3F; P3R], codec4: P4[P4C; P4F; P4R], codec5: P5[P5C; P5F; P5R] },
-    next: codec6: MapCodec6 as P6[P6C; P6F; P6R]
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R },
+    next: codec6: MapCodec6 as P6[P6C; P6F; P6R] after codec5: P5R
 }
 
 impl_record_codec_builder! {
     type: MapCodec6,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R], codec3: P3[P3C; P3F; P3R], codec4: P4[P4C; P4F; P4R], codec5: P5[P5C; P5F; P5R], codec6: P6[P6C; P6F; P6R] },
-    next: codec7: MapCodec7 as P7[P7C; P7F; P7R]
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R },
+    next: codec7: MapCodec7 as P7[P7C; P7F; P7R] after codec6: P6R
 }
 
 impl_record_codec_builder! {
     type: MapCodec7,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R], codec3: P3[P3C; P3F; P3R], codec4: P4[P4C; P4F; P4R], codec5: P5[P5C; P5F; P5R], codec6: P6[P6C; P6F; P6R], codec7: P7[P7C; P7F; P7R] },
-    next: codec8: MapCodec8 as P8[P8C; P8F; P8R]
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R },
+    next: codec8: MapCodec8 as P8[P8C; P8F; P8R] after codec7: P7R
 }
 
 impl_record_codec_builder! {
     type: MapCodec8,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R], codec3: P3[P3C; P3F; P3R], codec4: P4[P4C; P4F; P4R], codec5: P5[P5C; P5F; P5R], codec6: P6[P6C; P6F; P6R], codec7: P7[P7C; P7F; P7R], codec8: P8[P8C; P8F; P8R] },
-    next: codec9: MapCodec9 as P9[P9C; P9F; P9R]
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R },
+    next: codec9: MapCodec9 as P9[P9C; P9F; P9R] after codec8: P8R
 }
 
 impl_record_codec_builder! {
     type: MapCodec9,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R], codec3: P3[P3C; P3F; P3R], codec4: P4[P4C; P4F; P4R], codec5: P5[P5C; P5F; P5R], codec6: P6[P6C; P6F; P6R], codec7: P7[P7C; P7F; P7R], codec8: P8[P8C; P8F; P8R], codec9: P9[P9C; P9F; P9R] },
-    next: codec10: MapCodec10 as P10[P10C; P10F; P10R]
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R },
+    next: codec10: MapCodec10 as P10[P10C; P10F; P10R] after codec9: P9R
 }
 
-impl_record_codec_builder_last! {
+impl_record_codec_builder! {
     type: MapCodec10,
-    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R], codec3: P3[P3C; P3F; P3R], codec4: P4[P4C; P4F; P4R], codec5: P5[P5C; P5F; P5R], codec6: P6[P6C; P6F; P6R], codec7: P7[P7C; P7F; P7R], codec8: P8[P8C; P8F; P8R], codec9: P9[P9C; P9F; P9R], codec10: P10[P10C; P10F; P10R] }
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R },
+    next: codec11: MapCodec11 as P11[P11C; P11F; P11R] after codec10: P10R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec11,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R },
+    next: codec12: MapCodec12 as P12[P12C; P12F; P12R] after codec11: P11R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec12,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R },
+    next: codec13: MapCodec13 as P13[P13C; P13F; P13R] after codec12: P12R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec13,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R },
+    next: codec14: MapCodec14 as P14[P14C; P14F; P14R] after codec13: P13R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec14,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R },
+    next: codec15: MapCodec15 as P15[P15C; P15F; P15R] after codec14: P14R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec15,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R },
+    next: codec16: MapCodec16 as P16[P16C; P16F; P16R] after codec15: P15R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec16,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R },
+    next: codec17: MapCodec17 as P17[P17C; P17F; P17R] after codec16: P16R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec17,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R },
+    next: codec18: MapCodec18 as P18[P18C; P18F; P18R] after codec17: P17R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec18,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R },
+    next: codec19: MapCodec19 as P19[P19C; P19F; P19R] after codec18: P18R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec19,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R },
+    next: codec20: MapCodec20 as P20[P20C; P20F; P20R] after codec19: P19R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec20,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R },
+    next: codec21: MapCodec21 as P21[P21C; P21F; P21R] after codec20: P20R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec21,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R },
+    next: codec22: MapCodec22 as P22[P22C; P22F; P22R] after codec21: P21R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec22,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R },
+    next: codec23: MapCodec23 as P23[P23C; P23F; P23R] after codec22: P22R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec23,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R },
+    next: codec24: MapCodec24 as P24[P24C; P24F; P24R] after codec23: P23R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec24,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R },
+    next: codec25: MapCodec25 as P25[P25C; P25F; P25R] after codec24: P24R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec25,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R, codec25: P25[P25C; P25F; P25R] after codec24: P24R },
+    next: codec26: MapCodec26 as P26[P26C; P26F; P26R] after codec25: P25R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec26,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R, codec25: P25[P25C; P25F; P25R] after codec24: P24R, codec26: P26[P26C; P26F; P26R] after codec25: P25R },
+    next: codec27: MapCodec27 as P27[P27C; P27F; P27R] after codec26: P26R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec27,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R, codec25: P25[P25C; P25F; P25R] after codec24: P24R, codec26: P26[P26C; P26F; P26R] after codec25: P25R, codec27: P27[P27C; P27F; P27R] after codec26: P26R },
+    next: codec28: MapCodec28 as P28[P28C; P28F; P28R] after codec27: P27R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec28,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R, codec25: P25[P25C; P25F; P25R] after codec24: P24R, codec26: P26[P26C; P26F; P26R] after codec25: P25R, codec27: P27[P27C; P27F; P27R] after codec26: P26R, codec28: P28[P28C; P28F; P28R] after codec27: P27R },
+    next: codec29: MapCodec29 as P29[P29C; P29F; P29R] after codec28: P28R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec29,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R, codec25: P25[P25C; P25F; P25R] after codec24: P24R, codec26: P26[P26C; P26F; P26R] after codec25: P25R, codec27: P27[P27C; P27F; P27R] after codec26: P26R, codec28: P28[P28C; P28F; P28R] after codec27: P27R, codec29: P29[P29C; P29F; P29R] after codec28: P28R },
+    next: codec30: MapCodec30 as P30[P30C; P30F; P30R] after codec29: P29R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec30,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R, codec25: P25[P25C; P25F; P25R] after codec24: P24R, codec26: P26[P26C; P26F; P26R] after codec25: P25R, codec27: P27[P27C; P27F; P27R] after codec26: P26R, codec28: P28[P28C; P28F; P28R] after codec27: P27R, codec29: P29[P29C; P29F; P29R] after codec28: P28R, codec30: P30[P30C; P30F; P30R] after codec29: P29R },
+    next: codec31: MapCodec31 as P31[P31C; P31F; P31R] after codec30: P30R
+}
+
+impl_record_codec_builder! {
+    type: MapCodec31,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R, codec25: P25[P25C; P25F; P25R] after codec24: P24R, codec26: P26[P26C; P26F; P26R] after codec25: P25R, codec27: P27[P27C; P27F; P27R] after codec26: P26R, codec28: P28[P28C; P28F; P28R] after codec27: P27R, codec29: P29[P29C; P29F; P29R] after codec28: P28R, codec30: P30[P30C; P30F; P30R] after codec29: P29R, codec31: P31[P31C; P31F; P31R] after codec30: P30R },
+    next: codec32: MapCodec32 as P32[P32C; P32F; P32R] after codec31: P31R
+}
+
+impl_record_codec_builder_last! {
+    type: MapCodec32,
+    fields: { codec1: P1[P1C; P1F; P1R], codec2: P2[P2C; P2F; P2R] after codec1: P1R, codec3: P3[P3C; P3F; P3R] after codec2: P2R, codec4: P4[P4C; P4F; P4R] after codec3: P3R, codec5: P5[P5C; P5F; P5R] after codec4: P4R, codec6: P6[P6C; P6F; P6R] after codec5: P5R, codec7: P7[P7C; P7F; P7R] after codec6: P6R, codec8: P8[P8C; P8F; P8R] after codec7: P7R, codec9: P9[P9C; P9F; P9R] after codec8: P8R, codec10: P10[P10C; P10F; P10R] after codec9: P9R, codec11: P11[P11C; P11F; P11R] after codec10: P10R, codec12: P12[P12C; P12F; P12R] after codec11: P11R, codec13: P13[P13C; P13F; P13R] after codec12: P12R, codec14: P14[P14C; P14F; P14R] after codec13: P13R, codec15: P15[P15C; P15F; P15R] after codec14: P14R, codec16: P16[P16C; P16F; P16R] after codec15: P15R, codec17: P17[P17C; P17F; P17R] after codec16: P16R, codec18: P18[P18C; P18F; P18R] after codec17: P17R, codec19: P19[P19C; P19F; P19R] after codec18: P18R, codec20: P20[P20C; P20F; P20R] after codec19: P19R, codec21: P21[P21C; P21F; P21R] after codec20: P20R, codec22: P22[P22C; P22F; P22R] after codec21: P21R, codec23: P23[P23C; P23F; P23R] after codec22: P22R, codec24: P24[P24C; P24F; P24R] after codec23: P23R, codec25: P25[P25C; P25F; P25R] after codec24: P24R, codec26: P26[P26C; P26F; P26R] after codec25: P25R, codec27: P27[P27C; P27F; P27R] after codec26: P26R, codec28: P28[P28C; P28F; P28R] after codec27: P27R, codec29: P29[P29C; P29F; P29R] after codec28: P28R, codec30: P30[P30C; P30F; P30R] after codec29: P29R, codec31: P31[P31C; P31F; P31R] after codec30: P30R, codec32: P32[P32C; P32F; P32R] after codec31: P31R }
 }
@@ -1,12 +1,21 @@
-use core::{cell::OnceCell, marker::PhantomData};
+use core::marker::PhantomData;
 
 use crate::{
     result::{DataError, DataResult},
-    serialization::{Codec, CodecOps, Context, MapView},
+    serialization::{
+        Codec, CodecOps, Context, DynamicCodec, MapView, MapViewMut, Schema, SchemaField,
+    },
 };
-use alloc::string::String;
+use alloc::{boxed::Box, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
-pub trait MapFieldGetter<T, C: Codec<T, O>, Struct, Rt, O: CodecOps> {
+#[cfg(feature = "std")]
+use crate::serialization::Dynamic;
+
+/// A field of a record built with [`MapCodecBuilder`](super::record_builder::MapCodecBuilder).
+/// `P` is the type the field before it in the record decodes to, for fields whose codec depends on it.
+pub trait MapFieldGetter<T, C: Codec<T, O>, Struct, Rt, O: CodecOps, P = ()> {
     fn encode_into(
         &self,
         ops: &O,
@@ -14,20 +23,105 @@ pub trait MapFieldGetter<T, C: Codec<T, O>, Struct, Rt, O: CodecOps> {
         ctx: &mut Context,
     ) -> Option<DataResult<(String, O::T)>>;
     fn get_field(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Rt>;
+    /// Like [`MapFieldGetter::encode_into`], given the value of the field before this one, or `None` for
+    /// the first field of a record or if the value can't be read.
+    fn encode_into_after(
+        &self,
+        ops: &O,
+        value: &Struct,
+        ctx: &mut Context,
+        previous: Option<&P>,
+    ) -> Option<DataResult<(String, O::T)>> {
+        let _ = previous;
+        self.encode_into(ops, value, ctx)
+    }
+    /// Like [`MapFieldGetter::get_field`], given what the field before this one decoded to, or `None` for
+    /// the first field of a record or if that field failed while collecting errors.
+    fn get_field_after(
+        &self,
+        ops: &O,
+        value: &O::T,
+        ctx: &mut Context,
+        previous: Option<&P>,
+    ) -> DataResult<Rt> {
+        let _ = previous;
+        self.get_field(ops, value, ctx)
+    }
+    /// Returns the value of this field in `value`, as it would be decoded.
+    fn value<'a>(&self, value: &'a Struct) -> DataResult<&'a Rt>;
     fn field_name(&self) -> &str;
+    /// Returns the additional keys this field is decoded from when the primary key is missing.
+    fn aliases(&self) -> &[String] {
+        &[]
+    }
+    /// Returns whether this field's map entries are inlined into the record instead of being nested under a key.
+    fn flattened(&self) -> bool {
+        false
+    }
+    /// Returns whether this field is flattened and takes the entries that no other field of the record claims,
+    /// see `MapCodecBuilder::capture_rest`.
+    fn captures_rest(&self) -> bool {
+        false
+    }
+    /// Returns the keys this field reads from the record, including aliases and the keys of a flattened record,
+    /// or `None` if it reads any key.
+    fn claimed_keys(&self) -> Option<Vec<String>> {
+        Some(
+            core::iter::once(self.field_name())
+                .chain(self.aliases().iter().map(String::as_str))
+                .map(String::from)
+                .collect(),
+        )
+    }
+    /// Returns whether this field can only be decoded from the value of the field before it, so it is skipped
+    /// when that field failed while collecting errors.
+    fn depends_on_previous(&self) -> bool {
+        false
+    }
+    /// Returns whether decoding fails if this field is missing, for [`Codec::schema`].
+    fn required(&self) -> bool {
+        true
+    }
     fn codec(&self) -> &C;
 }
 
+/// Gets the value of a field from a map, trying the primary key and then each alias in order.
+fn get_aliased<'a, T>(
+    map: &'a impl MapView<T>,
+    field_name: &str,
+    aliases: &[String],
+) -> DataResult<&'a T> {
+    core::iter::once(field_name)
+        .chain(aliases.iter().map(String::as_str))
+        .find_map(|key| map.get(key).ok())
+        .ok_or_else(|| DataError::key_not_found(field_name))
+}
+
 pub struct OptionalField<T, C: Codec<T, O>, Struct, O: CodecOps> {
     pub(crate) field_name: String,
+    pub(crate) aliases: Vec<String>,
     pub(crate) getter: fn(&Struct) -> &Option<T>,
     pub(crate) codec: C,
     pub(crate) _phantom: PhantomData<fn() -> O>,
 }
 
-impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, Option<T>, O>
+impl<T, C: Codec<T, O>, Struct, O: CodecOps> OptionalField<T, C, Struct, O> {
+    /// Adds a key that this field is also decoded from when the primary key is missing, such as a key
+    /// the field was previously named. Aliases are tried in the order they were added.
+    /// The field is always encoded under the primary key.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+}
+
+impl<T, C: Codec<T, O>, Struct, O: CodecOps, P> MapFieldGetter<T, C, Struct, Option<T>, O, P>
     for OptionalField<T, C, Struct, O>
 {
+    fn value<'a>(&self, value: &'a Struct) -> DataResult<&'a Option<T>> {
+        Ok((self.getter)(value))
+    }
+
     fn encode_into(
         &self,
         ops: &O,
@@ -50,7 +144,7 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, Option
 
     fn get_field(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Option<T>> {
         let obj = ops.get_map(value)?;
-        match obj.get(&self.field_name) {
+        match get_aliased(&obj, &self.field_name, &self.aliases) {
             Ok(field) => Ok(Some(self.codec.decode(ops, field, ctx)?)),
             Err(_) => Ok(None),
         }
@@ -60,6 +154,14 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, Option
         &self.field_name
     }
 
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn required(&self) -> bool {
+        false
+    }
+
     fn codec(&self) -> &C {
         &self.codec
     }
@@ -67,15 +169,30 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, Option
 
 pub struct DefaultField<T, C: Codec<T, O>, Struct, O: CodecOps, F: Fn() -> T> {
     pub(crate) field_name: String,
+    pub(crate) aliases: Vec<String>,
     pub(crate) getter: fn(&Struct) -> &T,
     pub(crate) codec: C,
     pub(crate) default: F,
     pub(crate) _phantom: PhantomData<fn() -> (O::T, O)>,
 }
 
-impl<T, C: Codec<T, O>, Struct, O: CodecOps, F: Fn() -> T> MapFieldGetter<T, C, Struct, T, O>
+impl<T, C: Codec<T, O>, Struct, O: CodecOps, F: Fn() -> T> DefaultField<T, C, Struct, O, F> {
+    /// Adds a key that this field is also decoded from when the primary key is missing, such as a key
+    /// the field was previously named. Aliases are tried in the order they were added.
+    /// The field is always encoded under the primary key.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+}
+
+impl<T, C: Codec<T, O>, Struct, O: CodecOps, F: Fn() -> T, P> MapFieldGetter<T, C, Struct, T, O, P>
     for DefaultField<T, C, Struct, O, F>
 {
+    fn value<'a>(&self, value: &'a Struct) -> DataResult<&'a T> {
+        Ok((self.getter)(value))
+    }
+
     fn encode_into(
         &self,
         ops: &O,
@@ -93,7 +210,7 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps, F: Fn() -> T> MapFieldGetter<T, C,
 
     fn get_field(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
         let obj = ops.get_map(value)?;
-        match obj.get(&self.field_name) {
+        match get_aliased(&obj, &self.field_name, &self.aliases) {
             Ok(field) => Ok(self.codec.decode(ops, field, ctx)?),
             Err(_) => {
                 let default_value = (self.default)();
@@ -106,6 +223,14 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps, F: Fn() -> T> MapFieldGetter<T, C,
         &self.field_name
     }
 
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn required(&self) -> bool {
+        false
+    }
+
     fn codec(&self) -> &C {
         &self.codec
     }
@@ -113,17 +238,32 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps, F: Fn() -> T> MapFieldGetter<T, C,
 
 pub struct RecordField<T, C: Codec<T, O>, Struct, O: CodecOps> {
     pub(crate) field_name: String,
+    pub(crate) aliases: Vec<String>,
     pub(crate) getter: fn(&Struct) -> &T,
     pub(crate) codec: C,
     pub(crate) _phantom: PhantomData<fn() -> (T, O::T, O)>,
 }
 
-impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, T, O>
+impl<T, C: Codec<T, O>, Struct, O: CodecOps> RecordField<T, C, Struct, O> {
+    /// Adds a key that this field is also decoded from when the primary key is missing, such as a key
+    /// the field was previously named. Aliases are tried in the order they were added.
+    /// The field is always encoded under the primary key.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+}
+
+impl<T, C: Codec<T, O>, Struct, O: CodecOps, P> MapFieldGetter<T, C, Struct, T, O, P>
     for RecordField<T, C, Struct, O>
 {
+    fn value<'a>(&self, value: &'a Struct) -> DataResult<&'a T> {
+        Ok((self.getter)(value))
+    }
+
     fn get_field(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
         let obj = ops.get_map(value)?;
-        let field = obj.get(&self.field_name)?;
+        let field = get_aliased(&obj, &self.field_name, &self.aliases)?;
         self.codec.decode(ops, field, ctx)
     }
 
@@ -131,6 +271,10 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, T, O>
         &self.field_name
     }
 
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
     fn encode_into(
         &self,
         ops: &O,
@@ -150,19 +294,142 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, T, O>
     }
 }
 
+/// A field of a record whose codec is chosen from the value of the field before it, its key.
+/// See `MapCodecBuilder::dependent_field`.
+pub struct DependentField<K, T, Struct, O: CodecOps, S>
+where
+    S: Fn(&K) -> DataResult<DynamicCodec<T, O>>,
+{
+    pub(crate) field_name: String,
+    pub(crate) getter: fn(&Struct) -> &T,
+    pub(crate) selector: S,
+    pub(crate) codec: DependentCodec<T, O>,
+    pub(crate) _phantom: PhantomData<fn(&K)>,
+}
+
+impl<K, T, Struct, O: CodecOps, S> MapFieldGetter<T, DependentCodec<T, O>, Struct, T, O, K>
+    for DependentField<K, T, Struct, O, S>
+where
+    S: Fn(&K) -> DataResult<DynamicCodec<T, O>>,
+{
+    fn encode_into(
+        &self,
+        _ops: &O,
+        _value: &Struct,
+        _ctx: &mut Context,
+    ) -> Option<DataResult<(String, O::T)>> {
+        Some(Err(DataError::new_custom(
+            "dependent field can only be encoded after the field it depends on",
+        )))
+    }
+
+    fn encode_into_after(
+        &self,
+        ops: &O,
+        value: &Struct,
+        ctx: &mut Context,
+        previous: Option<&K>,
+    ) -> Option<DataResult<(String, O::T)>> {
+        let Some(key) = previous else {
+            return self.encode_into(ops, value, ctx);
+        };
+        let encoded = (self.selector)(key)
+            .and_then(|codec| codec.encode(ops, (self.getter)(value), ctx))
+            .map(|encoded| (self.field_name.clone(), encoded));
+        Some(encoded)
+    }
+
+    fn get_field(&self, _ops: &O, _value: &O::T, _ctx: &mut Context) -> DataResult<T> {
+        Err(DataError::new_custom(
+            "dependent field can only be decoded after the field it depends on",
+        ))
+    }
+
+    fn depends_on_previous(&self) -> bool {
+        true
+    }
+
+    fn get_field_after(
+        &self,
+        ops: &O,
+        value: &O::T,
+        ctx: &mut Context,
+        previous: Option<&K>,
+    ) -> DataResult<T> {
+        let Some(key) = previous else {
+            return self.get_field(ops, value, ctx);
+        };
+        let codec = (self.selector)(key)?;
+        let obj = ops.get_map(value)?;
+        let field = get_aliased(&obj, &self.field_name, &[])?;
+        codec.decode(ops, field, ctx)
+    }
+
+    fn value<'a>(&self, value: &'a Struct) -> DataResult<&'a T> {
+        Ok((self.getter)(value))
+    }
+
+    fn field_name(&self) -> &str {
+        &self.field_name
+    }
+
+    fn codec(&self) -> &DependentCodec<T, O> {
+        &self.codec
+    }
+}
+
+/// Stands in for the codec of a [`DependentField`], which is only known once its key has been decoded.
+/// It can't encode or decode a value on its own, and describes itself as [`Schema::Any`].
+pub struct DependentCodec<T, O: CodecOps> {
+    pub(crate) _phantom: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, O: CodecOps> Codec<T, O> for DependentCodec<T, O> {
+    fn encode(&self, _ops: &O, _value: &T, _ctx: &mut Context) -> DataResult<O::T> {
+        Err(DataError::new_custom(
+            "dependent field can only be encoded as part of its record",
+        ))
+    }
+
+    fn decode(&self, _ops: &O, _value: &O::T, _ctx: &mut Context) -> DataResult<T> {
+        Err(DataError::new_custom(
+            "dependent field can only be decoded as part of its record",
+        ))
+    }
+
+    fn debug(&self) -> String {
+        "Dependent".into()
+    }
+}
+
 pub struct FallibleField<T, C: Codec<T, O>, Struct, O: CodecOps> {
     pub(crate) field_name: String,
+    pub(crate) aliases: Vec<String>,
     pub(crate) getter: fn(&Struct) -> DataResult<&T>,
     pub(crate) codec: C,
     pub(crate) _phantom: PhantomData<fn() -> (T, O::T, O)>,
 }
 
-impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, T, O>
+impl<T, C: Codec<T, O>, Struct, O: CodecOps> FallibleField<T, C, Struct, O> {
+    /// Adds a key that this field is also decoded from when the primary key is missing, such as a key
+    /// the field was previously named. Aliases are tried in the order they were added.
+    /// The field is always encoded under the primary key.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+}
+
+impl<T, C: Codec<T, O>, Struct, O: CodecOps, P> MapFieldGetter<T, C, Struct, T, O, P>
     for FallibleField<T, C, Struct, O>
 {
+    fn value<'a>(&self, value: &'a Struct) -> DataResult<&'a T> {
+        (self.getter)(value)
+    }
+
     fn get_field(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
         let obj = ops.get_map(value)?;
-        let field = obj.get(&self.field_name)?;
+        let field = get_aliased(&obj, &self.field_name, &self.aliases)?;
         self.codec.decode(ops, field, ctx)
     }
 
@@ -170,6 +437,10 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, T, O>
         &self.field_name
     }
 
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
     fn encode_into(
         &self,
         ops: &O,
@@ -193,6 +464,157 @@ impl<T, C: Codec<T, O>, Struct, O: CodecOps> MapFieldGetter<T, C, Struct, T, O>
     }
 }
 
+pub struct FlattenField<T, C: Codec<T, O>, Struct, O: CodecOps> {
+    pub(crate) getter: fn(&Struct) -> &T,
+    pub(crate) codec: C,
+    pub(crate) _phantom: PhantomData<fn() -> O>,
+}
+
+impl<T, C: Codec<T, O>, Struct, O: CodecOps, P> MapFieldGetter<T, C, Struct, T, O, P>
+    for FlattenField<T, C, Struct, O>
+{
+    fn value<'a>(&self, value: &'a Struct) -> DataResult<&'a T> {
+        Ok((self.getter)(value))
+    }
+
+    fn encode_into(
+        &self,
+        ops: &O,
+        value: &Struct,
+        ctx: &mut Context,
+    ) -> Option<DataResult<(String, O::T)>> {
+        Some(
+            self.codec
+                .encode(ops, (self.getter)(value), ctx)
+                .map(|encoded| (String::new(), encoded)),
+        )
+    }
+
+    fn get_field(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<T> {
+        self.codec.decode(ops, value, ctx)
+    }
+
+    fn field_name(&self) -> &str {
+        ""
+    }
+
+    fn flattened(&self) -> bool {
+        true
+    }
+
+    fn claimed_keys(&self) -> Option<Vec<String>> {
+        self.codec.record_keys()
+    }
+
+    fn codec(&self) -> &C {
+        &self.codec
+    }
+}
+
+/// The field added by `MapCodecBuilder::capture_rest`.
+#[cfg(feature = "std")]
+pub struct RestField<Struct, O: CodecOps> {
+    pub(crate) getter: fn(&Struct) -> &HashMap<String, Dynamic<O>>,
+    pub(crate) codec: RestCodec,
+}
+
+#[cfg(feature = "std")]
+impl<Struct, O: CodecOps, P>
+    MapFieldGetter<
+        HashMap<String, Dynamic<O>>,
+        RestCodec,
+        Struct,
+        HashMap<String, Dynamic<O>>,
+        O,
+        P,
+    > for RestField<Struct, O>
+{
+    fn value<'a>(&self, value: &'a Struct) -> DataResult<&'a HashMap<String, Dynamic<O>>> {
+        Ok((self.getter)(value))
+    }
+
+    fn encode_into(
+        &self,
+        ops: &O,
+        value: &Struct,
+        ctx: &mut Context,
+    ) -> Option<DataResult<(String, O::T)>> {
+        Some(
+            self.codec
+                .encode(ops, (self.getter)(value), ctx)
+                .map(|encoded| (String::new(), encoded)),
+        )
+    }
+
+    fn get_field(
+        &self,
+        ops: &O,
+        value: &O::T,
+        ctx: &mut Context,
+    ) -> DataResult<HashMap<String, Dynamic<O>>> {
+        self.codec.decode(ops, value, ctx)
+    }
+
+    fn field_name(&self) -> &str {
+        ""
+    }
+
+    fn flattened(&self) -> bool {
+        true
+    }
+
+    fn captures_rest(&self) -> bool {
+        true
+    }
+
+    fn claimed_keys(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn codec(&self) -> &RestCodec {
+        &self.codec
+    }
+}
+
+/// Keeps the entries of a map as [`Dynamic`] values. Entries are encoded sorted by key,
+/// so encoding the same record twice gives the same output.
+#[cfg(feature = "std")]
+pub struct RestCodec;
+
+#[cfg(feature = "std")]
+impl<O: CodecOps> Codec<HashMap<String, Dynamic<O>>, O> for RestCodec {
+    fn encode(
+        &self,
+        ops: &O,
+        value: &HashMap<String, Dynamic<O>>,
+        _ctx: &mut Context,
+    ) -> DataResult<O::T> {
+        let mut entries = value
+            .iter()
+            .map(|(key, value)| (key.clone(), value.value().clone()))
+            .collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(ops.create_map(entries))
+    }
+
+    fn decode(
+        &self,
+        ops: &O,
+        value: &O::T,
+        _ctx: &mut Context,
+    ) -> DataResult<HashMap<String, Dynamic<O>>> {
+        Ok(ops
+            .get_map(value)?
+            .entries()
+            .map(|(key, value)| (String::from(key), Dynamic::new(value.clone(), ops.clone())))
+            .collect())
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::Map(Box::new(Schema::Any))
+    }
+}
+
 pub struct UnitCodec {}
 
 impl<O: CodecOps> Codec<(), O> for UnitCodec {
@@ -205,22 +627,67 @@ impl<O: CodecOps> Codec<(), O> for UnitCodec {
     }
 }
 
+/// A record codec with no fields, encoded as an empty map.
+pub struct MapCodec0<Struct, O: CodecOps> {
+    pub(crate) into_struct: fn() -> Struct,
+    pub(crate) _phantom: PhantomData<O>,
+}
+
+impl<Struct, O: CodecOps> Codec<Struct, O> for MapCodec0<Struct, O> {
+    fn encode(&self, ops: &O, _value: &Struct, _ctx: &mut Context) -> DataResult<O::T> {
+        Ok(ops.create_map([]))
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Struct> {
+        let map = ops.get_map(value)?;
+        let mut failed = 0;
+        for key in map.keys() {
+            let error =
+                DataError::new_custom(&alloc::format!("Unsupported key \"{}\" in object", key));
+            if !ctx.is_collecting() {
+                return Err(error);
+            }
+            ctx.record_error(error);
+            failed += 1;
+        }
+        if failed > 0 {
+            return Err(DataError::Collected { count: failed });
+        }
+        Ok((self.into_struct)())
+    }
+
+    fn debug(&self) -> String {
+        String::from("Map[]")
+    }
+
+    fn schema(&self) -> Schema {
+        Schema::Record(Vec::new())
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        Some(Vec::new())
+    }
+}
+
 /// I'm sorry. Not even God himself understands this macro anymore.
 macro_rules! record_codec {
     (
         name: $struct_name:ident,
-        fields: { $($field:ident: $name:ident[$codec:ident; $field_type:ident; $field_return_type:ident]),* }
+        fields: { $(
+            $field:ident: $name:ident[$codec:ident; $field_type:ident; $field_return_type:ident]
+            $(after $previous:ident: $previous_return_type:ident)?
+        ),* }
     ) => {
         pub struct $struct_name<$(
                 $name,
                 $codec: Codec<$name, O>,
                 $field_return_type,
-                $field_type: MapFieldGetter<$name, $codec, Struct, $field_return_type, O>
+                $field_type: MapFieldGetter<$name, $codec, Struct, $field_return_type, O $(, $previous_return_type)?>
             ),*,
             Struct, O: CodecOps
         > {
             $(pub(crate) $field: $field_type),*,
-            pub(crate) into_struct: OnceCell<fn($($field_return_type),*) -> Struct>,
+            pub(crate) into_struct: Option<fn($($field_return_type),*) -> Struct>,
             pub(crate) _phantom: PhantomData<($($name, $codec, $field_return_type),*, O)>
         }
 
@@ -229,31 +696,151 @@ macro_rules! record_codec {
             $name,
             $codec: Codec<$name, O>,
             $field_return_type,
-            $field_type: MapFieldGetter<$name, $codec, Struct, $field_return_type, O>
+            $field_type: MapFieldGetter<$name, $codec, Struct, $field_return_type, O $(, $previous_return_type)?>
         ),*, O: CodecOps> Codec<Struct, O> for $struct_name<$($name, $codec, $field_return_type, $field_type),*, Struct, O> {
             fn encode(&self, ops: &O, value: &Struct, ctx: &mut Context) -> DataResult<O::T> {
-                ops.create_map_special([
-                    $(self.$field.encode_into(ops, value, ctx),)*
-                ])
+                let mut entries = alloc::vec::Vec::new();
+                $(
+                    if !self.$field.flattened() {
+                        ctx.push_field(self.$field.field_name());
+                    }
+                    let previous = None $(.or(self.$previous.value(value).ok()))?;
+                    if let Some(entry) = self.$field.encode_into_after(ops, value, ctx, previous) {
+                        let (key, encoded) = entry?;
+                        if self.$field.flattened() {
+                            let map = ops.get_map(&encoded)?;
+                            for (key, encoded) in map.entries() {
+                                entries.push((String::from(key), encoded.clone()));
+                            }
+                        } else {
+                            entries.push((key, encoded));
+                        }
+                    }
+                    if !self.$field.flattened() {
+                        ctx.pop();
+                    }
+                )*
+                // A flattened field must not write a key that another field of this record already writes.
+                if $(self.$field.flattened())||* {
+                    let mut keys = entries.iter().map(|(key, _)| key).collect::<Vec<_>>();
+                    keys.sort();
+                    if let Some(key) = keys.windows(2).find(|pair| pair[0] == pair[1]) {
+                        return Err(DataError::new_custom(&alloc::format!(
+                            "Duplicate key \"{}\" in object",
+                            key[0]
+                        )));
+                    }
+                }
+                Ok(ops.create_map(entries))
             }
 
             fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Struct> {
+                let is_own_key = |key: &str| {
+                    $((!self.$field.flattened()
+                        && (self.$field.field_name() == key
+                            || self.$field.aliases().iter().any(|alias| alias == key))))||*
+                };
+                let has_flattened = $(self.$field.flattened())||*;
+                let has_rest = $(self.$field.captures_rest())||*;
+                // With a field capturing the rest, each flattened field only gets the keys it claims,
+                // and the rest gets the keys that no field claims. `None` claims every key.
+                let claimed = if has_rest {
+                    alloc::vec![$(
+                        if self.$field.flattened() && !self.$field.captures_rest() {
+                            self.$field.claimed_keys()
+                        } else {
+                            Some(Vec::new())
+                        }
+                    ),*]
+                } else {
+                    Vec::new()
+                };
+                let mut claimed_by_field = claimed.iter();
+                let claims = |keys: &Option<Vec<String>>, key: &str| {
+                    keys.as_ref()
+                        .is_none_or(|keys| keys.iter().any(|claimed| claimed == key))
+                };
+                // Flattened fields are decoded from the entries that no other field of this record claims.
+                let rest = if has_flattened {
+                    let map = ops.get_map(value)?;
+                    let entries = map
+                        .entries()
+                        .filter(|(key, _)| !is_own_key(key))
+                        .map(|(key, value)| (String::from(key), value.clone()))
+                        .collect::<Vec<_>>();
+                    Some(ops.create_map(entries))
+                } else {
+                    None
+                };
+
+                let mut failed = 0;
                 $(
-                    ctx.push_field(self.$field.field_name());
-                    let $field: $field_return_type = self.$field.get_field(ops, value, ctx)?;
-                    ctx.pop();
+                    let depth = ctx.depth();
+                    let own_claimed = claimed_by_field.next();
+                    let own_rest;
+                    let field_value = match &rest {
+                        Some(rest) if has_rest && self.$field.flattened() => {
+                            let own_keys = own_claimed.filter(|_| !self.$field.captures_rest());
+                            let entries = ops
+                                .get_map(rest)?
+                                .entries()
+                                .filter(|(key, _)| match own_keys {
+                                    Some(keys) => claims(keys, key),
+                                    None => !claimed.iter().any(|keys| claims(keys, key)),
+                                })
+                                .map(|(key, value)| (String::from(key), value.clone()))
+                                .collect::<Vec<_>>();
+                            own_rest = ops.create_map(entries);
+                            &own_rest
+                        }
+                        Some(rest) if self.$field.flattened() => rest,
+                        _ => {
+                            ctx.push_field(self.$field.field_name());
+                            value
+                        }
+                    };
+                    let previous = None $(.or($previous.as_ref()))?;
+                    // A field that depends on one that failed is skipped, as that failure is already counted.
+                    let skipped = self.$field.depends_on_previous() && previous.is_none();
+                    let $field: Option<$field_return_type> = if skipped {
+                        ctx.truncate(depth);
+                        None
+                    } else {
+                        match self.$field.get_field_after(ops, field_value, ctx, previous) {
+                            Ok(field) => {
+                                if !self.$field.flattened() {
+                                    ctx.pop();
+                                }
+                                Some(field)
+                            }
+                            Err(e) if ctx.is_collecting() => {
+                                ctx.record_error(e);
+                                ctx.truncate(depth);
+                                failed += 1;
+                                None
+                            }
+                            Err(e) => return Err(e),
+                        }
+                    };
                 )*
                 let map = ops.get_map(value)?;
-                let slice = [$(&self.$field.field_name()),*];
                 for key in map.keys() {
-                    if !slice.contains(&&&*key) {
-                        return Err(DataError::new_custom(&alloc::format!("Unsupported key \"{}\" in object", key)))
+                    if !has_flattened && !is_own_key(&key) {
+                        let error = DataError::new_custom(&alloc::format!("Unsupported key \"{}\" in object", key));
+                        if !ctx.is_collecting() {
+                            return Err(error);
+                        }
+                        ctx.record_error(error);
+                        failed += 1;
                     }
                 }
 
-                Ok((self.into_struct.get().unwrap())(
-                    $($field),*
-                ))
+                match ($($field,)*) {
+                    ($(Some($field),)*) if failed == 0 => Ok((self.into_struct.unwrap())(
+                        $($field),*
+                    )),
+                    _ => Err(DataError::Collected { count: failed }),
+                }
             }
 
             fn debug(&self) -> String {
@@ -266,6 +853,46 @@ macro_rules! record_codec {
                 str.push_str("]");
                 str
             }
+
+            fn record_keys(&self) -> Option<Vec<String>> {
+                let mut keys = Vec::new();
+                $(keys.extend(self.$field.claimed_keys()?);)*
+                Some(keys)
+            }
+
+            fn schema(&self) -> Schema {
+                let mut fields = Vec::new();
+                // The schema of the values of keys that aren't fields, if a flattened field accepts them.
+                let mut rest = None;
+                $(
+                    let schema = self.$field.codec().schema();
+                    if self.$field.flattened() {
+                        match schema {
+                            Schema::Record(flattened) => fields.extend(flattened),
+                            Schema::OpenRecord { fields: flattened, rest: values } => {
+                                fields.extend(flattened);
+                                rest.get_or_insert(values);
+                            }
+                            Schema::Map(values) => {
+                                rest.get_or_insert(values);
+                            }
+                            schema => {
+                                rest.get_or_insert(Box::new(schema));
+                            }
+                        }
+                    } else {
+                        fields.push(SchemaField {
+                            name: self.$field.field_name().into(),
+                            schema,
+                            required: self.$field.required(),
+                        });
+                    }
+                )*
+                match rest {
+                    Some(rest) => Schema::OpenRecord { fields, rest },
+                    None => Schema::Record(fields),
+                }
+            }
         }
     };
 }
@@ -281,7 +908,7 @@ record_codec! {
     name: MapCodec2,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R
     }
 }
 
@@ -289,8 +916,8 @@ record_codec! {
     name: MapCodec3,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R
     }
 }
 
@@ -298,9 +925,9 @@ record_codec! {
     name: MapCodec4,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R
     }
 }
 
@@ -308,10 +935,10 @@ record_codec! {
     name: MapCodec5,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R
     }
 }
 
@@ -319,11 +946,11 @@ record_codec! {
     name: MapCodec6,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R
     }
 }
 
@@ -331,12 +958,12 @@ record_codec! {
     name: MapCodec7,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R
     }
 }
 
@@ -344,13 +971,13 @@ record_codec! {
     name: MapCodec8,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R
     }
 }
 
@@ -358,14 +985,14 @@ record_codec! {
     name: MapCodec9,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R],
-        codec9: P9[P9C; P9F; P9R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R
     }
 }
 
@@ -373,15 +1000,15 @@ record_codec! {
     name: MapCodec10,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R],
-        codec9: P9[P9C; P9F; P9R],
-        codec10: P10[P10C; P10F; P10R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R
     }
 }
 
@@ -389,16 +1016,16 @@ record_codec! {
     name: MapCodec11,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R],
-        codec9: P9[P9C; P9F; P9R],
-        codec10: P10[P10C; P10F; P10R],
-        codec11: P11[P11C; P11F; P11R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R
     }
 }
 
@@ -406,17 +1033,17 @@ record_codec! {
     name: MapCodec12,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R],
-        codec9: P9[P9C; P9F; P9R],
-        codec10: P10[P10C; P10F; P10R],
-        codec11: P11[P11C; P11F; P11R],
-        codec12: P12[P12C; P12F; P12R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R
     }
 }
 
@@ -424,18 +1051,18 @@ record_codec! {
     name: MapCodec13,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R],
-        codec9: P9[P9C; P9F; P9R],
-        codec10: P10[P10C; P10F; P10R],
-        codec11: P11[P11C; P11F; P11R],
-        codec12: P12[P12C; P12F; P12R],
-        codec13: P13[P13C; P13F; P13R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R
     }
 }
 
@@ -443,19 +1070,19 @@ record_codec! {
     name: MapCodec14,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R],
-        codec9: P9[P9C; P9F; P9R],
-        codec10: P10[P10C; P10F; P10R],
-        codec11: P11[P11C; P11F; P11R],
-        codec12: P12[P12C; P12F; P12R],
-        codec13: P13[P13C; P13F; P13R],
-        codec14: P14[P14C; P14F; P14R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R
     }
 }
 
@@ -463,20 +1090,20 @@ record_codec! {
     name: MapCodec15,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R],
-        codec9: P9[P9C; P9F; P9R],
-        codec10: P10[P10C; P10F; P10R],
-        codec11: P11[P11C; P11F; P11R],
-        codec12: P12[P12C; P12F; P12R],
-        codec13: P13[P13C; P13F; P13R],
-        codec14: P14[P14C; P14F; P14R],
-        codec15: P15[P15C; P15F; P15R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R
     }
 }
 
@@ -484,20 +1111,577 @@ record_codec! {
     name: MapCodec16,
     fields: {
         codec1: P1[P1C; P1F; P1R],
-        codec2: P2[P2C; P2F; P2R],
-        codec3: P3[P3C; P3F; P3R],
-        codec4: P4[P4C; P4F; P4R],
-        codec5: P5[P5C; P5F; P5R],
-        codec6: P6[P6C; P6F; P6R],
-        codec7: P7[P7C; P7F; P7R],
-        codec8: P8[P8C; P8F; P8R],
-        codec9: P9[P9C; P9F; P9R],
-        codec10: P10[P10C; P10F; P10R],
-        codec11: P11[P11C; P11F; P11R],
-        codec12: P12[P12C; P12F; P12R],
-        codec13: P13[P13C; P13F; P13R],
-        codec14: P14[P14C; P14F; P14R],
-        codec15: P15[P15C; P15F; P15R],
-        codec16: P16[P16C; P16F; P16R]
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R
+    }
+}
+
+record_codec! {
+    name: MapCodec17,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R
+    }
+}
+
+record_codec! {
+    name: MapCodec18,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R
+    }
+}
+
+record_codec! {
+    name: MapCodec19,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R
+    }
+}
+
+record_codec! {
+    name: MapCodec20,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R
+    }
+}
+
+record_codec! {
+    name: MapCodec21,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R
+    }
+}
+
+record_codec! {
+    name: MapCodec22,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R
+    }
+}
+
+record_codec! {
+    name: MapCodec23,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R
+    }
+}
+
+record_codec! {
+    name: MapCodec24,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R
+    }
+}
+
+record_codec! {
+    name: MapCodec25,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R,
+        codec25: P25[P25C; P25F; P25R] after codec24: P24R
+    }
+}
+
+record_codec! {
+    name: MapCodec26,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R,
+        codec25: P25[P25C; P25F; P25R] after codec24: P24R,
+        codec26: P26[P26C; P26F; P26R] after codec25: P25R
+    }
+}
+
+record_codec! {
+    name: MapCodec27,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R,
+        codec25: P25[P25C; P25F; P25R] after codec24: P24R,
+        codec26: P26[P26C; P26F; P26R] after codec25: P25R,
+        codec27: P27[P27C; P27F; P27R] after codec26: P26R
+    }
+}
+
+record_codec! {
+    name: MapCodec28,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R,
+        codec25: P25[P25C; P25F; P25R] after codec24: P24R,
+        codec26: P26[P26C; P26F; P26R] after codec25: P25R,
+        codec27: P27[P27C; P27F; P27R] after codec26: P26R,
+        codec28: P28[P28C; P28F; P28R] after codec27: P27R
+    }
+}
+
+record_codec! {
+    name: MapCodec29,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R,
+        codec25: P25[P25C; P25F; P25R] after codec24: P24R,
+        codec26: P26[P26C; P26F; P26R] after codec25: P25R,
+        codec27: P27[P27C; P27F; P27R] after codec26: P26R,
+        codec28: P28[P28C; P28F; P28R] after codec27: P27R,
+        codec29: P29[P29C; P29F; P29R] after codec28: P28R
+    }
+}
+
+record_codec! {
+    name: MapCodec30,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R,
+        codec25: P25[P25C; P25F; P25R] after codec24: P24R,
+        codec26: P26[P26C; P26F; P26R] after codec25: P25R,
+        codec27: P27[P27C; P27F; P27R] after codec26: P26R,
+        codec28: P28[P28C; P28F; P28R] after codec27: P27R,
+        codec29: P29[P29C; P29F; P29R] after codec28: P28R,
+        codec30: P30[P30C; P30F; P30R] after codec29: P29R
+    }
+}
+
+record_codec! {
+    name: MapCodec31,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R,
+        codec25: P25[P25C; P25F; P25R] after codec24: P24R,
+        codec26: P26[P26C; P26F; P26R] after codec25: P25R,
+        codec27: P27[P27C; P27F; P27R] after codec26: P26R,
+        codec28: P28[P28C; P28F; P28R] after codec27: P27R,
+        codec29: P29[P29C; P29F; P29R] after codec28: P28R,
+        codec30: P30[P30C; P30F; P30R] after codec29: P29R,
+        codec31: P31[P31C; P31F; P31R] after codec30: P30R
+    }
+}
+
+record_codec! {
+    name: MapCodec32,
+    fields: {
+        codec1: P1[P1C; P1F; P1R],
+        codec2: P2[P2C; P2F; P2R] after codec1: P1R,
+        codec3: P3[P3C; P3F; P3R] after codec2: P2R,
+        codec4: P4[P4C; P4F; P4R] after codec3: P3R,
+        codec5: P5[P5C; P5F; P5R] after codec4: P4R,
+        codec6: P6[P6C; P6F; P6R] after codec5: P5R,
+        codec7: P7[P7C; P7F; P7R] after codec6: P6R,
+        codec8: P8[P8C; P8F; P8R] after codec7: P7R,
+        codec9: P9[P9C; P9F; P9R] after codec8: P8R,
+        codec10: P10[P10C; P10F; P10R] after codec9: P9R,
+        codec11: P11[P11C; P11F; P11R] after codec10: P10R,
+        codec12: P12[P12C; P12F; P12R] after codec11: P11R,
+        codec13: P13[P13C; P13F; P13R] after codec12: P12R,
+        codec14: P14[P14C; P14F; P14R] after codec13: P13R,
+        codec15: P15[P15C; P15F; P15R] after codec14: P14R,
+        codec16: P16[P16C; P16F; P16R] after codec15: P15R,
+        codec17: P17[P17C; P17F; P17R] after codec16: P16R,
+        codec18: P18[P18C; P18F; P18R] after codec17: P17R,
+        codec19: P19[P19C; P19F; P19R] after codec18: P18R,
+        codec20: P20[P20C; P20F; P20R] after codec19: P19R,
+        codec21: P21[P21C; P21F; P21R] after codec20: P20R,
+        codec22: P22[P22C; P22F; P22R] after codec21: P21R,
+        codec23: P23[P23C; P23F; P23R] after codec22: P22R,
+        codec24: P24[P24C; P24F; P24R] after codec23: P23R,
+        codec25: P25[P25C; P25F; P25R] after codec24: P24R,
+        codec26: P26[P26C; P26F; P26R] after codec25: P25R,
+        codec27: P27[P27C; P27F; P27R] after codec26: P26R,
+        codec28: P28[P28C; P28F; P28R] after codec27: P27R,
+        codec29: P29[P29C; P29F; P29R] after codec28: P28R,
+        codec30: P30[P30C; P30F; P30R] after codec29: P29R,
+        codec31: P31[P31C; P31F; P31R] after codec30: P30R,
+        codec32: P32[P32C; P32F; P32R] after codec31: P31R
+    }
+}
+
+/// Wraps a record codec with the constant fields added by [`MapCodecBuilder::constant_field`](crate::serialization::MapCodecBuilder::constant_field).
+pub(crate) struct ConstantFieldsCodec<C, O: CodecOps> {
+    pub(crate) codec: C,
+    pub(crate) constants: Vec<(String, ConstantField<O>)>,
+}
+
+/// A constant field's codec, kept `Send + Sync` so record codecs can still be shared across threads.
+pub(crate) type ConstantField<O> = Box<dyn Codec<(), O> + Send + Sync>;
+
+impl<Struct, C: Codec<Struct, O>, O: CodecOps> Codec<Struct, O> for ConstantFieldsCodec<C, O> {
+    fn encode(&self, ops: &O, value: &Struct, ctx: &mut Context) -> DataResult<O::T> {
+        let mut encoded = self.codec.encode(ops, value, ctx)?;
+        if self.constants.is_empty() {
+            return Ok(encoded);
+        }
+        let mut entries = Vec::with_capacity(self.constants.len());
+        for (name, codec) in &self.constants {
+            ctx.push_field(name);
+            entries.push(codec.encode(ops, &(), ctx)?);
+            ctx.pop();
+        }
+        {
+            let mut map = ops.get_map_mut(&mut encoded)?;
+            for ((name, _), entry) in self.constants.iter().zip(entries) {
+                map.set(name, entry);
+            }
+        }
+        Ok(encoded)
+    }
+
+    fn decode(&self, ops: &O, value: &O::T, ctx: &mut Context) -> DataResult<Struct> {
+        if self.constants.is_empty() {
+            return self.codec.decode(ops, value, ctx);
+        }
+        let map = ops.get_map(value)?;
+        for (name, codec) in &self.constants {
+            ctx.push_field(name);
+            codec.decode(ops, map.get(name)?, ctx)?;
+            ctx.pop();
+        }
+        let mut content = value.clone();
+        {
+            let mut content_map = ops.get_map_mut(&mut content)?;
+            for (name, _) in &self.constants {
+                content_map.remove(name)?;
+            }
+        }
+        self.codec.decode(ops, &content, ctx)
+    }
+
+    fn debug(&self) -> String {
+        self.codec.debug()
+    }
+
+    fn record_keys(&self) -> Option<Vec<String>> {
+        let mut keys = self.codec.record_keys()?;
+        keys.extend(self.constants.iter().map(|(name, _)| name.clone()));
+        Some(keys)
+    }
+
+    fn schema(&self) -> Schema {
+        self.constants
+            .iter()
+            .fold(self.codec.schema(), |schema, (name, codec)| {
+                schema.with_field(name, codec.schema(), true)
+            })
     }
 }
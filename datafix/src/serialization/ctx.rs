@@ -1,11 +1,34 @@
-use core::fmt::{Debug, Display};
+use core::fmt::{Debug, Display, Write};
 
-use alloc::{string::String, vec::Vec};
+use alloc::{string::String, sync::Arc, vec::Vec};
+
+use crate::result::{CodecError, DataError};
 
 #[derive(Clone)]
 pub struct Context {
     stack_trace: Vec<TracePoint>,
     cache: Vec<Context>,
+    errors: Option<Vec<CodecError>>,
+    hook: Option<Arc<Hook>>,
+}
+
+type Hook = dyn Fn(&Context, TraceEvent<'_>) + Send + Sync;
+
+/// A change to the stack trace of a [`Context`], reported to the hook set with [`Context::with_hook`].
+#[derive(Clone, Copy, Debug)]
+pub enum TraceEvent<'a> {
+    /// A codec started working on the point, such as a field of a record or an element of a list.
+    /// The point is already part of the stack trace.
+    Enter(&'a TracePoint),
+    /// A codec finished working on the point. The point is still part of the stack trace.
+    ///
+    /// When an error stops encoding or decoding, the points leading to it are not exited,
+    /// so the last point entered without being exited is where it failed.
+    Exit(&'a TracePoint),
+    /// A codec gave up on the point after an error and recovered, such as when a fallback codec is tried
+    /// or an error is recorded while collecting. Sent by [`Context::truncate`] for every point it removes,
+    /// innermost first, in place of [`TraceEvent::Exit`]. The point is still part of the stack trace.
+    Failed(&'a TracePoint),
 }
 
 impl Context {
@@ -13,34 +36,175 @@ impl Context {
         Context {
             stack_trace: [TracePoint::Root].into(),
             cache: Vec::new(),
+            errors: None,
+            hook: None,
+        }
+    }
+
+    /// Sets a function that is called whenever a codec enters or leaves a field, list element or codec,
+    /// such as to log or time where a large decode spends its time. The hook can read the current position
+    /// with [`Context::path`].
+    ///
+    /// Built-in codecs report every field and list element they work on, so a hook sees the same structure
+    /// as the paths of errors. Every [`TraceEvent::Enter`] is followed by either a [`TraceEvent::Exit`]
+    /// or a [`TraceEvent::Failed`] for the same point, unless the whole operation fails.
+    ///
+    /// Events don't say which kind of codec is running: built-in codecs and combinators never push a
+    /// [`TracePoint::Codec`], so those points only appear when a custom codec calls [`Context::push_codec`].
+    /// To see a codec in the hook, wrap it in a codec that calls [`Context::push_codec`] around it.
+    ///
+    /// datafix doesn't depend on `tracing` or any other logging crate. To report codecs as `tracing` spans,
+    /// enter a span on [`TraceEvent::Enter`] and leave it on [`TraceEvent::Exit`] or [`TraceEvent::Failed`].
+    ///
+    /// The hook belongs to this context only: contexts saved with [`Context::save`] and the spans of
+    /// [`CodecError`]s don't keep it, so errors stay cheap to clone and never call back into the hook.
+    pub fn with_hook(
+        mut self,
+        hook: impl Fn(&Context, TraceEvent<'_>) + Send + Sync + 'static,
+    ) -> Context {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns this context without its hook, to be stored in an error or a save.
+    pub(crate) fn without_hook(mut self) -> Context {
+        self.hook = None;
+        self
+    }
+
+    fn enter(&mut self, point: TracePoint) {
+        self.stack_trace.push(point);
+        if let Some(hook) = self.hook.clone() {
+            hook(self, TraceEvent::Enter(self.stack_trace.last().unwrap()));
         }
     }
 
+    fn exit(&mut self, failed: bool) -> Option<TracePoint> {
+        if let (Some(hook), Some(point)) = (self.hook.clone(), self.stack_trace.last()) {
+            let event = if failed {
+                TraceEvent::Failed(point)
+            } else {
+                TraceEvent::Exit(point)
+            };
+            hook(self, event);
+        }
+        self.stack_trace.pop()
+    }
+
+    /// Creates a context that collects errors instead of stopping at the first one, see [`Context::record_error`].
+    pub fn new_collecting() -> Context {
+        Context {
+            errors: Some(Vec::new()),
+            ..Context::new()
+        }
+    }
+
+    /// Returns whether codecs should keep going after an error and record it with [`Context::record_error`].
+    pub fn is_collecting(&self) -> bool {
+        self.errors.is_some()
+    }
+
+    /// Records an error at the current position of the stack trace.
+    /// Errors of type [`DataError::Collected`] are ignored, since the errors they refer to were already recorded.
+    pub fn record_error(&mut self, error: DataError) {
+        if let DataError::Collected { .. } = error {
+            return;
+        }
+        let span = Context {
+            stack_trace: self.stack_trace.clone(),
+            cache: Vec::new(),
+            errors: None,
+            hook: None,
+        };
+        self.errors
+            .get_or_insert_with(Vec::new)
+            .push(CodecError::new(error, span));
+    }
+
+    /// Takes all errors recorded with [`Context::record_error`].
+    pub fn take_errors(&mut self) -> Vec<CodecError> {
+        self.errors
+            .as_mut()
+            .map(core::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Returns how many errors have been recorded with [`Context::record_error`] so far.
+    pub fn error_count(&self) -> usize {
+        self.errors.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Removes and returns the errors recorded since [`Context::error_count`] returned `count`,
+    /// such as when a codec recovers from an alternative that failed while collecting errors.
+    pub fn take_errors_since(&mut self, count: usize) -> Vec<CodecError> {
+        self.errors
+            .as_mut()
+            .map(|errors| errors.split_off(count.min(errors.len())))
+            .unwrap_or_default()
+    }
+
     pub fn push_field(&mut self, name: &str) {
-        self.stack_trace
-            .push(TracePoint::Field { name: name.into() });
+        self.enter(TracePoint::Field { name: name.into() });
     }
 
     pub fn push_codec(&mut self, name: &str) {
-        self.stack_trace
-            .push(TracePoint::Codec { name: name.into() });
+        self.enter(TracePoint::Codec { name: name.into() });
     }
 
     pub fn push_array(&mut self, index: usize) {
-        self.stack_trace.push(TracePoint::Array { index });
+        self.enter(TracePoint::Array { index });
     }
 
     pub fn pop(&mut self) -> Option<TracePoint> {
-        self.stack_trace.pop()
+        self.exit(false)
+    }
+
+    /// Renders the map keys and list indices in the stack trace as a path, such as `players[2].stats.health`.
+    /// Returns an empty string if the trace is at the root.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        for element in &self.stack_trace {
+            match element {
+                TracePoint::Field { name } => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(name);
+                }
+                TracePoint::Array { index } => {
+                    let _ = write!(path, "[{index}]");
+                }
+                TracePoint::Root | TracePoint::Codec { .. } => {}
+            }
+        }
+        path
+    }
+
+    /// Returns the number of points in the stack trace.
+    pub fn depth(&self) -> usize {
+        self.stack_trace.len()
+    }
+
+    /// Removes points from the stack trace until it is `depth` points deep,
+    /// such as to recover after a nested codec returned an error without popping its points.
+    /// Each removed point is reported to the hook as [`TraceEvent::Failed`], so use [`Context::pop`]
+    /// for points a codec is done with.
+    pub fn truncate(&mut self, depth: usize) {
+        while self.stack_trace.len() > depth {
+            self.exit(true);
+        }
     }
 
     pub fn save(&mut self) {
-        self.cache.push(self.clone());
+        self.cache.push(self.clone().without_hook());
     }
 
+    /// Restores the stack trace and errors from the last [`Context::save`].
+    /// The hook isn't told about the points this adds or removes.
     pub fn load_save(&mut self) {
         let last = self.cache.pop().unwrap();
         self.stack_trace = last.stack_trace;
+        self.errors = last.errors;
     }
 
     pub fn pop_save(&mut self) -> Option<Context> {
@@ -1,6 +1,8 @@
+use alloc::{string::String, vec::Vec};
+
 use crate::result::DataResult;
 
-use super::{CodecOps, MapView, MapViewMut};
+use super::{CodecOps, ListView, ListViewMut, MapView, MapViewMut};
 
 #[derive(Debug, Clone)]
 pub struct Dynamic<O: CodecOps> {
@@ -106,4 +108,227 @@ impl<O: CodecOps> Dynamic<O> {
         self.ops.get_map_mut(&mut self.value)?.set(field, value);
         Ok(())
     }
+
+    /// Like [`Dynamic::insert_field`], but accepts anything that converts into a [`Dynamic`], such as `5` or `"text"`.
+    pub fn insert(&mut self, field: &str, value: impl Into<Dynamic<O>>) -> DataResult<()> {
+        self.insert_field(field, value.into().into_inner())
+    }
+
+    pub fn create_list(&self, values: impl IntoIterator<Item = O::T>) -> Self {
+        Dynamic {
+            value: self.ops.create_list(values),
+            ops: self.ops.clone(),
+        }
+    }
+
+    pub fn get_index(&self, index: usize) -> DataResult<Self> {
+        Ok(Dynamic::new(
+            self.ops.get_list(&self.value)?.get(index).cloned()?,
+            self.ops(),
+        ))
+    }
+
+    pub fn push(&mut self, value: O::T) -> DataResult<()> {
+        self.ops.get_list_mut(&mut self.value)?.append(value);
+        Ok(())
+    }
+
+    /// Returns a new list with `f` applied to every element of this list.
+    pub fn map_list(&self, mut f: impl FnMut(Self) -> O::T) -> DataResult<Self> {
+        let values = self
+            .ops
+            .get_list(&self.value)?
+            .into_iter()
+            .map(|value| f(Dynamic::new(value, self.ops())))
+            .collect::<Vec<_>>();
+        Ok(self.create_list(values))
+    }
+
+    /// Returns a copy of the value at a JSON-pointer style `path` such as `/players/0/name`.
+    ///
+    /// Each segment is looked up as a key in a map, or as an index in a list. As in JSON pointers,
+    /// `~1` and `~0` in a segment stand for `/` and `~`, and an empty path refers to the whole value.
+    /// Returns `None` if the path does not exist.
+    pub fn pointer(&self, path: &str) -> Option<Self> {
+        let segments = pointer_segments(path)?;
+        let value = pointer_ref(&self.ops, &self.value, &segments)?;
+        Some(Dynamic::new(value, self.ops()))
+    }
+
+    /// Calls `f` with a mutable reference to the value at a JSON-pointer style `path`, returning its result.
+    /// See [`Dynamic::pointer`] for the format of the path. Returns `None` if the path does not exist.
+    pub fn pointer_mut<R>(&mut self, path: &str, f: impl FnOnce(&mut O::T) -> R) -> Option<R> {
+        let segments = pointer_segments(path)?;
+        pointer_mut(&self.ops, &mut self.value, &segments, f)
+    }
+}
+
+macro_rules! dynamic_from {
+    ($($t:ty => $create:ident),* $(,)?) => {
+        $(impl<O: CodecOps + Default> From<$t> for Dynamic<O> {
+            fn from(value: $t) -> Self {
+                let ops = O::default();
+                Dynamic::new(ops.$create(&value), ops)
+            }
+        })*
+    };
+}
+
+dynamic_from! {
+    i32 => create_int,
+    f64 => create_double,
+    bool => create_boolean,
+    String => create_string,
+}
+
+impl<O: CodecOps + Default> From<&str> for Dynamic<O> {
+    fn from(value: &str) -> Self {
+        let ops = O::default();
+        Dynamic::new(ops.create_string(value), ops)
+    }
+}
+
+impl<O: CodecOps + Default> From<Vec<Dynamic<O>>> for Dynamic<O> {
+    fn from(values: Vec<Dynamic<O>>) -> Self {
+        let ops = O::default();
+        Dynamic::new(
+            ops.create_list(values.into_iter().map(Dynamic::into_inner)),
+            ops,
+        )
+    }
+}
+
+fn pointer_segments(path: &str) -> Option<Vec<String>> {
+    if path.is_empty() {
+        return Some(Vec::new());
+    }
+    let segments = path.strip_prefix('/')?.split('/');
+    Some(
+        segments
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+fn pointer_ref<O: CodecOps>(ops: &O, value: &O::T, segments: &[String]) -> Option<O::T> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Some(value.clone());
+    };
+    if let Ok(map) = ops.get_map(value) {
+        return pointer_ref(ops, map.get(segment).ok()?, rest);
+    }
+    let list = ops.get_list(value).ok()?;
+    pointer_ref(ops, list.get(segment.parse().ok()?).ok()?, rest)
+}
+
+fn pointer_mut<O: CodecOps, R>(
+    ops: &O,
+    value: &mut O::T,
+    segments: &[String],
+    f: impl FnOnce(&mut O::T) -> R,
+) -> Option<R> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Some(f(value));
+    };
+    if ops.get_map(value).is_ok() {
+        let mut map = ops.get_map_mut(value).ok()?;
+        return pointer_mut(ops, map.get_mut(segment).ok()?, rest, f);
+    }
+    let mut list = ops.get_list_mut(value).ok()?;
+    pointer_mut(ops, list.get_mut(segment.parse().ok()?).ok()?, rest, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use crate::serialization::{CodecOps, Dynamic, json::JsonOps};
+
+    #[test]
+    fn pointer() {
+        let mut dynamic = Dynamic::new(
+            json::object! {
+                "players": [{ "name": "a", "stats": { "hp": 1 } }, { "name": "b" }],
+                "a/b": { "~": 2 }
+            },
+            JsonOps,
+        );
+
+        let hp = dynamic.pointer("/players/0/stats/hp").unwrap();
+        assert_eq!(hp.as_int().unwrap(), 1);
+        assert_eq!(dynamic.pointer("/a~1b/~0").unwrap().as_int().unwrap(), 2);
+        assert_eq!(dynamic.pointer("").unwrap().value(), dynamic.value());
+        assert!(dynamic.pointer("/players/2").is_none());
+        assert!(dynamic.pointer("/players/x").is_none());
+        assert!(dynamic.pointer("players").is_none());
+
+        let updated = dynamic.pointer_mut("/players/1/name", |name| {
+            *name = JsonOps.create_string("c");
+        });
+        assert!(updated.is_some());
+        assert_eq!(
+            dynamic.pointer("/players/1/name").unwrap().value(),
+            &json::JsonValue::from("c")
+        );
+        assert!(dynamic.pointer_mut("/missing", |_| ()).is_none());
+    }
+
+    #[test]
+    fn lists() {
+        let mut list = Dynamic::new(json::array![1, 2], JsonOps);
+        list.push(JsonOps.create_int(&3)).unwrap();
+        assert_eq!(list.get_index(2).unwrap().as_int().unwrap(), 3);
+        assert!(list.get_index(3).is_err());
+
+        let doubled = list
+            .map_list(|value| JsonOps.create_int(&(value.as_int().unwrap() * 2)))
+            .unwrap();
+        assert_eq!(doubled.value(), &json::array![2, 4, 6]);
+
+        let mut nested = list.create_list([]);
+        nested.push(list.into_inner()).unwrap();
+        assert_eq!(nested.pointer("/0/1").unwrap().as_int().unwrap(), 2);
+
+        let mut map = Dynamic::new(json::object! {}, JsonOps);
+        assert!(map.push(JsonOps.create_int(&1)).is_err());
+        assert!(map.map_list(|value| value.into_inner()).is_err());
+    }
+
+    #[test]
+    fn from_primitives() {
+        let mut dynamic: Dynamic<JsonOps> = Dynamic::from(vec![
+            Dynamic::from(5),
+            Dynamic::from(1.5),
+            Dynamic::from(true),
+            Dynamic::from("a"),
+        ]);
+        dynamic
+            .push(Dynamic::<JsonOps>::from(String::from("b")).into_inner())
+            .unwrap();
+        assert_eq!(dynamic.value(), &json::array![5, 1.5, true, "a", "b"]);
+
+        let mut map = Dynamic::new(json::object! {}, JsonOps);
+        map.insert_field("key", Dynamic::<JsonOps>::from(5).into_inner())
+            .unwrap();
+        assert_eq!(map.get_field("key").unwrap().as_int().unwrap(), 5);
+    }
+
+    #[test]
+    fn encode_dynamic() {
+        use crate::serialization::{Codec, CodecAdapters, DefaultCodec};
+
+        let codec = i32::codec().list_of();
+        let mut encoded = codec.encode_dynamic(&JsonOps, &vec![1, 2]).unwrap();
+        assert_eq!(encoded.pointer("/1").unwrap().as_int().unwrap(), 2);
+        encoded.push(JsonOps.create_int(&3)).unwrap();
+        assert_eq!(
+            codec.decode_start(&JsonOps, encoded.value()).unwrap(),
+            [1, 2, 3]
+        );
+
+        let mut map = Dynamic::new(json::object! {}, JsonOps);
+        map.insert("name", "a").unwrap();
+        map.insert("level", 2).unwrap();
+        assert_eq!(map.value(), &json::object! { "name": "a", "level": 2 });
+    }
 }
@@ -2,31 +2,74 @@ mod builtins;
 mod ctx;
 mod dynamic;
 mod ops;
+mod schema;
 
 use alloc::{
+    borrow::{Cow, ToOwned},
     boxed::Box,
+    collections::btree_set::BTreeSet,
+    format,
     rc::Rc,
     string::{String, ToString},
     sync::Arc,
     vec::Vec,
 };
+#[cfg(feature = "std")]
+use builtins::codecs::{HashMapCodec, KeyedMapCodec, SyncArcCodec, SyncRecursiveCodec};
 use builtins::{
     codecs::{
-        ArcCodec, BoundedCodec, BoxCodec, ConstantCodec, DispatchCodec, DynamicCodec, EitherCodec,
-        FlatXMapCodec, FnCodec, ListCodec, OrElseCodec, PairCodec, TryElseCodec, XMapCodec,
+        AdjacentlyTaggedCodec, AnyOfCodec, ArcCodec, ArcValueCodec, ArrayCodec, Base64Codec,
+        BoundedCodec, BoundedListCodec, BoxCodec, CheckedXMapCodec, ClampedCodec, ConstantCodec,
+        CowCodec, CowStrCodec, DispatchCodec, DurationMillisCodec, DurationSecsCodec, DynamicCodec,
+        EitherCodec, ExternallyTaggedCodec, FlatXMapCodec, FnCodec, FromStrCodec, IntEnumCodec,
+        InternallyTaggedCodec, LazyCell, LazyCodec, LenientNumberCodec, ListCodec,
+        NonEmptyListCodec, OrElseCodec, PairCodec, RangeCodec, RcCodec, ResultCodec, SetCodec,
+        StringDispatchCodec, StringEnumCodec, StringTransformCodec, TaggedEitherCodec,
+        TryElseCodec, Tuple3Codec, Tuple4Codec, Tuple5Codec, Tuple6Codec, Tuple7Codec, Tuple8Codec,
+        ValidateCodec, VersionedCodec, XMapCodec,
     },
-    records::{DefaultField, FallibleField, OptionalField, RecordField, UnitCodec},
+    records::{DefaultField, FallibleField, FlattenField, OptionalField, RecordField, UnitCodec},
+};
+use core::{
+    cell::RefCell,
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    ops::{Range, RangeBounds, RangeInclusive},
+    str::FromStr,
+    time::Duration,
 };
-use core::{cell::RefCell, fmt::Debug, marker::PhantomData, ops::RangeBounds};
 use either::Either;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
 
 pub use ctx::*;
 pub use dynamic::*;
+use ops::json::JsonOps;
 pub use ops::*;
+pub use schema::*;
 
-use crate::result::{CodecError, DataResult};
+use crate::{
+    collections::NonEmptyVec,
+    fixers::{DataFixer, TypeRewriteRule},
+    result::{CodecError, DataError, DataResult},
+};
 pub use builtins::record_builder::MapCodecBuilder;
 
+/// A type-erased [`Codec`] for `T`, as returned by [`CodecAdapters::dynamic`]. The [`CodecOps`] is fixed,
+/// defaulting to [`JsonOps`], so codecs for different types that share a value type can be stored together,
+/// such as in a `Vec<AnyCodec<MyType>>`.
+pub type AnyCodec<T, O = JsonOps> = DynamicCodec<T, O>;
+/// A [`Codec`] for `T` that is built on first use and can be stored in a `static`, as returned by [`Codecs::lazy`]
+/// with an initializer that ends in [`CodecAdapters::sync_arc`].
+#[cfg(feature = "std")]
+pub type StaticCodec<T, O = JsonOps> =
+    LazyCodec<T, O, SyncArcCodec<T, O>, fn() -> SyncArcCodec<T, O>>;
+/// Derives [`DefaultCodec`] for a struct with named fields or an enum with unit and struct variants.
+#[cfg(feature = "derive")]
+pub use datafix_derive::Codec;
+
 /// A [`Codec<T>`] describes transformations to and from [`Dynamic`] for a type `T`.
 /// [`Codec`]s are lazy, they don't do anything by themselves.
 /// You need to call [`Codec::encode`], [`Codec::decode`] to change between `T` and [`Dynamic`].
@@ -50,6 +93,28 @@ pub trait Codec<Type, Ops: CodecOps> {
         self.encode(ops, value, &mut ctx)
             .map_err(|e| CodecError::new(e, ctx))
     }
+    /// Like [`Codec::encode_start`], but wraps the encoded value in a [`Dynamic`], so it can be inspected and modified
+    /// the same way regardless of which [`CodecOps`] produced it.
+    fn encode_dynamic(&self, ops: &Ops, value: &Type) -> Result<Dynamic<Ops>, CodecError> {
+        self.encode_start(ops, value)
+            .map(|encoded| Dynamic::new(encoded, ops.clone()))
+    }
+    /// Encodes a value into an existing `target`, such as to update a few fields of a large stored object.
+    /// If both the target and the encoded value are maps, the encoded entries are written into the target,
+    /// replacing entries with the same key and keeping all others. Otherwise, the target is replaced by the encoded value.
+    fn encode_into(&self, ops: &Ops, value: &Type, target: &mut Ops::T) -> Result<(), CodecError> {
+        let encoded = self.encode_start(ops, value)?;
+        if let (Ok(mut target_map), Ok(encoded_map)) =
+            (ops.get_map_mut(target), ops.get_map(&encoded))
+        {
+            for (key, entry) in encoded_map.entries() {
+                target_map.set(key, entry.clone());
+            }
+            return Ok(());
+        }
+        *target = encoded;
+        Ok(())
+    }
     /// Transform a value of type `T` into a `U` using the provided [`CodecOps`], optionally returning an error and associated span.
     /// Transforms a `U` value into a type `T` using the provided [`CodecOps`], optionally returning an error.
     fn encode(&self, ops: &Ops, value: &Type, ctx: &mut Context) -> DataResult<Ops::T>;
@@ -59,6 +124,54 @@ pub trait Codec<Type, Ops: CodecOps> {
         self.decode(ops, value, &mut ctx)
             .map_err(|e| CodecError::new(e, ctx))
     }
+    /// Like [`Codec::decode_start`], but record codecs built with [`MapCodecBuilder`] keep decoding after a field fails,
+    /// so every malformed field is reported at once with its own span.
+    fn decode_start_collecting(&self, ops: &Ops, value: &Ops::T) -> Result<Type, Vec<CodecError>> {
+        let mut ctx = Context::new_collecting();
+        self.decode(ops, value, &mut ctx).map_err(|e| {
+            ctx.record_error(e);
+            ctx.take_errors()
+        })
+    }
+    /// Decodes each element of a list with this codec, one at a time as the returned iterator is advanced.
+    /// Unlike decoding with [`CodecAdapters::list_of`], the elements are never collected into a `Vec`,
+    /// so a large list can be folded over without holding every decoded element at once.
+    /// The span of an element's error starts at its index in the list.
+    fn decode_iter<'a>(
+        &'a self,
+        ops: &'a Ops,
+        value: &'a Ops::T,
+    ) -> Result<impl Iterator<Item = Result<Type, CodecError>> + 'a, CodecError>
+    where
+        Self: Sized,
+    {
+        let list = ops
+            .get_list(value)
+            .map_err(|e| CodecError::new(e, Context::new()))?;
+        Ok((0..list.len()).map(move |index| {
+            let mut ctx = Context::new();
+            ctx.push_array(index);
+            list.get(index)
+                .and_then(|element| self.decode(ops, element, &mut ctx))
+                .map_err(|e| CodecError::new(e, ctx))
+        }))
+    }
+    /// Decodes `value` as a patch over `base`, such as a user's config file layered over the defaults.
+    /// Only the entries present in `value` are changed, and nested maps are patched the same way,
+    /// so required fields that are missing from `value` keep their values from `base`.
+    ///
+    /// This works by encoding `base`, merging `value` into it, and decoding the result, so `base`
+    /// is left unchanged if any step fails.
+    ///
+    /// The merge only sees encoded values, so it can't tell a nested record from a map field such as a
+    /// `BTreeMap`. Maps are patched entry by entry too: a patch can add or change their entries, but never
+    /// remove one. To replace a map wholesale, decode it on its own and assign it.
+    fn decode_patch(&self, ops: &Ops, value: &Ops::T, base: &mut Type) -> Result<(), CodecError> {
+        let mut merged = self.encode_start(ops, base)?;
+        merge_patch(ops, &mut merged, value);
+        *base = self.decode_start(ops, &merged)?;
+        Ok(())
+    }
     /// Transforms a `U` value into a type `T` using the provided [`CodecOps`], optionally returning an error.
     /// For implementors, this function should be pure and have no side effects.
     fn decode(&self, ops: &Ops, value: &Ops::T, ctx: &mut Context) -> DataResult<Type>;
@@ -66,6 +179,66 @@ pub trait Codec<Type, Ops: CodecOps> {
     fn debug(&self) -> String {
         pretty_type_name::pretty_type_name::<Self>().to_string()
     }
+
+    /// Describes the values this codec accepts, such as to generate documentation or a JSON Schema with
+    /// [`Schema::to_json_schema`]. Codecs that can't describe themselves return [`Schema::Any`].
+    fn schema(&self) -> Schema {
+        Schema::Any
+    }
+
+    /// Returns the keys this codec reads from a map, including aliases, if it only reads a known set of keys
+    /// like a record. A record with a captured rest uses this to split its entries between flattened fields.
+    /// Codecs that read any key, or that don't read a map, return `None`.
+    fn record_keys(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Describes the values this codec accepts as a compact tree, such as `{ volume: integer, gamma: integer }`.
+    /// This is the [`Display`] form of [`Codec::schema`], useful for printing what a config file expects.
+    fn describe(&self) -> String {
+        self.schema().to_string()
+    }
+
+    /// Parses `string` as JSON and decodes it with [`JsonOps`].
+    /// Errors from parsing the JSON are returned as [`DataError::Custom`] at the root of the span.
+    #[allow(clippy::wrong_self_convention)]
+    fn from_json_str(&self, string: &str) -> Result<Type, CodecError>
+    where
+        Self: Codec<Type, JsonOps> + Sized,
+    {
+        let value = ::json::parse(string).map_err(|e| {
+            CodecError::new(
+                DataError::new_custom(&format!("invalid JSON: {}", e)),
+                Context::new(),
+            )
+        })?;
+        Codec::<Type, JsonOps>::decode_start(self, &JsonOps, &value)
+    }
+
+    /// Encodes `value` with [`JsonOps`] and stringifies it as compact JSON.
+    fn to_json_string(&self, value: &Type) -> Result<String, CodecError>
+    where
+        Self: Codec<Type, JsonOps> + Sized,
+    {
+        Codec::<Type, JsonOps>::encode_start(self, &JsonOps, value).map(|value| value.dump())
+    }
+}
+
+/// Writes the entries of `patch` into `target`, merging nested maps. Any other value in `patch` replaces the value in `target`.
+fn merge_patch<O: CodecOps>(ops: &O, target: &mut O::T, patch: &O::T) {
+    let (Ok(patch_map), true) = (ops.get_map(patch), ops.get_map(target).is_ok()) else {
+        *target = patch.clone();
+        return;
+    };
+    let Ok(mut target_map) = ops.get_map_mut(target) else {
+        return;
+    };
+    for (key, entry) in patch_map.entries() {
+        match target_map.get_mut(key) {
+            Ok(existing) => merge_patch(ops, existing, entry),
+            Err(_) => target_map.set(key, entry.clone()),
+        }
+    }
 }
 
 /// Holds the adapter functions for [`Codec`] to allow codecs to do things such as:
@@ -84,6 +257,7 @@ where
     ) -> RecordField<T, Self, Struct, O> {
         RecordField {
             field_name: name.into(),
+            aliases: Vec::new(),
             getter,
             codec: self,
             _phantom: PhantomData,
@@ -99,6 +273,25 @@ where
     ) -> FallibleField<T, Self, Struct, O> {
         FallibleField {
             field_name: name.into(),
+            aliases: Vec::new(),
+            getter,
+            codec: self,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a codec of this type that is intended for a field of a record, whose map entries are inlined into the
+    /// record instead of being nested under a key. This codec must encode to a map, such as a record of its own.
+    ///
+    /// When decoding, this codec receives the entries that the other fields of the record don't claim.
+    /// Unknown keys are left for this codec to reject, so a record should only flatten one codec that rejects
+    /// unknown keys. If the record also captures the rest with `MapCodecBuilder::capture_rest`, a flattened record
+    /// only receives the keys of its own fields.
+    fn flatten_field_of<Struct>(
+        self,
+        getter: fn(&Struct) -> &T,
+    ) -> FlattenField<T, Self, Struct, O> {
+        FlattenField {
             getter,
             codec: self,
             _phantom: PhantomData,
@@ -113,6 +306,7 @@ where
     ) -> OptionalField<T, Self, Struct, O> {
         OptionalField {
             field_name: name.into(),
+            aliases: Vec::new(),
             getter,
             codec: self,
             _phantom: PhantomData,
@@ -120,6 +314,9 @@ where
     }
 
     /// Returns this codec, that is intended for an optional field of a record, except with a fallback default function.
+    ///
+    /// `default` is only used when the key is missing from the map. Unlike [`CodecAdapters::or_else`],
+    /// a key that is present but fails to decode is still an error. The value is always encoded.
     fn default_field_of<Struct, F: Fn() -> T>(
         self,
         name: impl Into<String>,
@@ -128,6 +325,7 @@ where
     ) -> DefaultField<T, Self, Struct, O, F> {
         DefaultField {
             field_name: name.into(),
+            aliases: Vec::new(),
             getter,
             codec: self,
             default,
@@ -143,6 +341,33 @@ where
         }
     }
 
+    /// Returns a codec that is a map of string keys to values of this codec.
+    /// Unlike a record built with [`MapCodecBuilder`], the keys are arbitrary and are part of the data.
+    /// Keys can be transformed on the way in and out with [`HashMapCodec::key_map`].
+    #[cfg(feature = "std")]
+    fn map_of(self) -> HashMapCodec<T, O, Self, std::hash::RandomState> {
+        HashMapCodec::new(self)
+    }
+
+    /// Returns a codec that is a list of this codec, where the length of the list must be within the provided range.
+    /// When decoding, the length is checked before any elements are decoded.
+    fn list_of_bounded(self, range: impl RangeBounds<usize>) -> impl Codec<Vec<T>, O> {
+        BoundedListCodec {
+            inner: self,
+            range,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a codec that is a fixed-size array of this codec, encoded as a list of exactly `N` elements.
+    /// Decoding fails if the list does not contain exactly `N` elements.
+    fn array_of<const N: usize>(self) -> impl Codec<[T; N], O> {
+        ArrayCodec {
+            inner: self,
+            _phantom: PhantomData,
+        }
+    }
+
     /// Maps the output of this codec between 2 transformation functions.
     /// Implementors should hold the invariant of `F(G(x)) = x` such that the functions can be used to freely convert between the two types.
     fn xmap<U, F, G>(self, to_new: F, from_new: G) -> impl Codec<U, O>
@@ -174,29 +399,174 @@ where
         }
     }
 
+    /// Like [`CodecAdapters::xmap`], but in debug builds checks the invariant `F(G(x)) = x` on every decoded value,
+    /// panicking with the path of the value if the two functions are not inverses. Release builds skip the check.
+    fn xmap_checked<U, F, G>(self, to_new: F, from_new: G) -> impl Codec<U, O>
+    where
+        F: Fn(&T) -> U,
+        G: Fn(&U) -> T,
+        U: PartialEq + Debug,
+    {
+        CheckedXMapCodec {
+            inner: XMapCodec {
+                inner: self,
+                f1: to_new,
+                f2: from_new,
+                _phantom: PhantomData,
+            },
+        }
+    }
+
+    /// Maps the output of this codec between 2 transformation functions, where only the decoding transformation may fail.
+    /// This is useful for parsing a decoded value into a type that may be invalid.
+    /// Implementors should hold the invariant of `F(G(x)) = x` such that the functions can be used to freely convert between the two types.
+    fn xmap_result<U, F, G>(self, to_new: F, from_new: G) -> impl Codec<U, O>
+    where
+        F: Fn(&T) -> DataResult<U>,
+        G: Fn(&U) -> T,
+    {
+        self.flat_xmap(to_new, move |value| Ok(from_new(value)))
+    }
+
     /// This returns a Codec that represents a pair of two codecs.
+    /// The pair is encoded as a map with the keys `"left"` and `"right"`.
     fn pair<R>(self, right: impl Codec<R, O>) -> impl Codec<(T, R), O> {
+        self.pair_named(right, "left", "right")
+    }
+
+    /// This returns a Codec that represents a pair of two codecs.
+    /// The pair is encoded as a map with the provided keys for the left and right values.
+    fn pair_named<R>(
+        self,
+        right: impl Codec<R, O>,
+        left_key: impl Into<String>,
+        right_key: impl Into<String>,
+    ) -> impl Codec<(T, R), O> {
         PairCodec {
             left: self,
             right,
+            left_key: left_key.into(),
+            right_key: right_key.into(),
             _phantom: PhantomData,
         }
     }
 
     /// This bounds the result of this codec in the range, returning an error if the value is not within the range.
+    /// The error names the value and the range in interval notation, such as `value 30 must be in range [1, 30)`.
     fn bounded(self, range: impl RangeBounds<T>) -> impl Codec<T, O>
     where
-        T: PartialOrd + Debug,
+        T: PartialOrd + Debug + 'static,
+    {
+        BoundedCodec {
+            codec: self,
+            range,
+            message: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Unlike [`CodecAdapters::bounded`], a decoded value outside of the range is clamped to the nearest bound
+    /// instead of returning an error. Values are encoded unchanged.
+    ///
+    /// # Panics
+    /// Panics if the start of the range is greater than the end.
+    fn clamped(self, range: RangeInclusive<T>) -> impl Codec<T, O>
+    where
+        T: Ord + Clone,
+    {
+        assert!(
+            range.start() <= range.end(),
+            "the start of a clamped range must not be greater than its end"
+        );
+        ClampedCodec {
+            codec: self,
+            range,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Like [`CodecAdapters::bounded`], but returns an error with the provided message if the value is not within the range.
+    fn bounded_with_message(
+        self,
+        range: impl RangeBounds<T>,
+        message: impl Into<String>,
+    ) -> impl Codec<T, O>
+    where
+        T: PartialOrd + Debug + 'static,
     {
         BoundedCodec {
             codec: self,
             range,
+            message: Some(message.into()),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// This validates the value of this codec with the provided predicate when encoding and decoding, returning an error with
+    /// the predicate's message if it fails. The value is left unchanged, so this can be chained with other adapters.
+    fn validate<F: Fn(&T) -> Result<(), String>>(self, validator: F) -> impl Codec<T, O> {
+        ValidateCodec {
+            codec: self,
+            validator,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// This trims leading and trailing whitespace from strings of this codec when decoding,
+    /// so decoded values can be compared directly. Values are encoded as they are, see [`CodecAdapters::trimmed_both`].
+    fn trimmed(self) -> impl Codec<T, O>
+    where
+        T: AsRef<str> + From<String>,
+    {
+        StringTransformCodec {
+            codec: self,
+            transform: |s| s.trim().to_string(),
+            on_encode: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Like [`CodecAdapters::trimmed`], but also trims strings when encoding, so stored data stays clean.
+    fn trimmed_both(self) -> impl Codec<T, O>
+    where
+        T: AsRef<str> + From<String>,
+    {
+        StringTransformCodec {
+            codec: self,
+            transform: |s| s.trim().to_string(),
+            on_encode: true,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Like [`CodecAdapters::trimmed`], but also collapses every run of whitespace inside the string into a single space.
+    fn normalized(self) -> impl Codec<T, O>
+    where
+        T: AsRef<str> + From<String>,
+    {
+        StringTransformCodec {
+            codec: self,
+            transform: |s| s.split_whitespace().collect::<Vec<_>>().join(" "),
+            on_encode: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Like [`CodecAdapters::normalized`], but also normalizes strings when encoding.
+    fn normalized_both(self) -> impl Codec<T, O>
+    where
+        T: AsRef<str> + From<String>,
+    {
+        StringTransformCodec {
+            codec: self,
+            transform: |s| s.split_whitespace().collect::<Vec<_>>().join(" "),
+            on_encode: true,
             _phantom: PhantomData,
         }
     }
 
     /// If this codec fails to encode or decode, it will fall back to using the second codec, only failing if both this and
-    /// the other codec fail.
+    /// the other codec fail. In that case the error is a [`DataError::AllFailed`] holding both errors.
     fn try_else(self, other: impl Codec<T, O>) -> impl Codec<T, O> {
         TryElseCodec {
             lc: self,
@@ -215,6 +585,15 @@ where
         }
     }
 
+    /// If decoding for this codec fails, use [`T::default`](Default::default) instead.
+    /// This is shorthand for `or_else(T::default)`.
+    fn or_default(self) -> impl Codec<T, O>
+    where
+        T: Default,
+    {
+        self.or_else(T::default)
+    }
+
     /// Wraps this codec in a `Box<dyn Codec<...>>`, allowing it to be used in dynamic contexts where you
     /// only know which codec will be passed in at runtime. This also creates a pointer to a codec,
     /// enabling self-referential codecs.
@@ -227,7 +606,8 @@ where
         }
     }
 
-    /// Wraps this codec in an `Arc`, allowing it to be cloned and shared across threads.
+    /// Wraps this codec in an `Arc`, allowing it to be cloned cheaply.
+    /// The returned codec is not `Send` or `Sync`, since the wrapped codec is not required to be.
     fn arc(self) -> ArcCodec<T, O>
     where
         Self: 'static,
@@ -237,6 +617,18 @@ where
         }
     }
 
+    /// Like [`CodecAdapters::arc`], but the returned codec is `Send + Sync`, so it can be shared across threads
+    /// or stored in a [`StaticCodec`].
+    #[cfg(feature = "std")]
+    fn sync_arc(self) -> SyncArcCodec<T, O>
+    where
+        Self: Send + Sync + 'static,
+    {
+        SyncArcCodec {
+            codec: Arc::new(self),
+        }
+    }
+
     /// Wraps the value being serialized or deserialized in a [`Box`].
     fn boxed(self) -> BoxCodec<T, O, Self> {
         BoxCodec {
@@ -244,6 +636,23 @@ where
             _phantom: PhantomData,
         }
     }
+
+    /// Wraps the value being serialized or deserialized in an [`Rc`], so decoded trees can share ownership of values.
+    fn rc(self) -> RcCodec<T, O, Self> {
+        RcCodec {
+            inner: self,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Wraps the value being serialized or deserialized in an [`Arc`].
+    /// Unlike [`CodecAdapters::arc`], which wraps the codec itself, this changes the type of value being serialized.
+    fn arc_value(self) -> ArcValueCodec<T, O, Self> {
+        ArcValueCodec {
+            inner: self,
+            _phantom: PhantomData,
+        }
+    }
 }
 
 impl<T, O: CodecOps, C: Codec<T, O>> CodecAdapters<T, O> for C {}
@@ -297,14 +706,18 @@ impl Codecs {
                     placeholder_clone_1
                         .borrow()
                         .as_ref()
-                        .expect("tried to decode before initialization")
+                        .ok_or_else(|| {
+                            DataError::new_custom("recursive codec used before initialization")
+                        })?
                         .encode(ops, value, ctx)
                 }),
                 decode: Box::new(move |ops, value, ctx| {
                     placeholder_clone_2
                         .borrow()
                         .as_ref()
-                        .expect("tried to decode before initialization")
+                        .ok_or_else(|| {
+                            DataError::new_custom("recursive codec used before initialization")
+                        })?
                         .decode(ops, value, ctx)
                 }),
             }),
@@ -317,6 +730,50 @@ impl Codecs {
         codec
     }
 
+    /// Like [`Codecs::recursive`], but the returned codec is `Send + Sync`, so a recursive codec can be built once and
+    /// shared across threads. The codec returned by `f` must also be `Send + Sync`.
+    #[cfg(feature = "std")]
+    pub fn recursive_sync<
+        T: 'static,
+        O: CodecOps + 'static,
+        F: FnOnce(SyncRecursiveCodec<T, O>) -> Oc,
+        Oc: Codec<T, O> + Send + Sync + 'static,
+    >(
+        f: F,
+    ) -> SyncArcCodec<T, O> {
+        let placeholder = SyncRecursiveCodec {
+            codec: Arc::new(OnceLock::new()),
+        };
+        let codec: Arc<dyn Codec<T, O> + Send + Sync> = Arc::new(f(placeholder.clone()));
+        let _ = placeholder.codec.set(Arc::downgrade(&codec));
+        SyncArcCodec { codec }
+    }
+
+    /// Defers building a codec until it is first used, then reuses it for every later encode and decode.
+    /// This is useful for large codec trees that are expensive to construct.
+    ///
+    /// With the `std` feature, the returned codec is `Sync` if the built codec is, so it can be cached in a `static`:
+    ///
+    /// ```
+    /// use datafix::serialization::{
+    ///     Codec, CodecAdapters, Codecs, DefaultCodec, StaticCodec, json::JsonOps,
+    /// };
+    ///
+    /// static PORT: StaticCodec<u16> = Codecs::lazy(|| u16::codec().bounded(1024..).sync_arc());
+    ///
+    /// assert_eq!(PORT.encode_start(&JsonOps, &8080).unwrap(), 8080);
+    /// assert!(PORT.encode_start(&JsonOps, &80).is_err());
+    /// ```
+    pub const fn lazy<T, O: CodecOps, C: Codec<T, O>, F: Fn() -> C>(
+        init: F,
+    ) -> LazyCodec<T, O, C, F> {
+        LazyCodec {
+            init,
+            codec: LazyCell::new(),
+            _phantom: PhantomData,
+        }
+    }
+
     pub fn either<
         T: 'static,
         T2: 'static,
@@ -335,6 +792,83 @@ impl Codecs {
         }
     }
 
+    /// Creates a [`Codec`] for an [`Either`] that stores which side it holds as a string under `tag_key`,
+    /// alongside the fields of the side's value in the same map. Both `left` and `right` must encode to a map.
+    ///
+    /// Unlike [`Codecs::either`], which tries `left` and then `right`, decoding is decided by the tag,
+    /// so it stays unambiguous when both sides accept the same shape.
+    pub fn either_tagged<T, T2, O: CodecOps>(
+        tag_key: impl Into<String>,
+        left_tag: impl Into<String>,
+        right_tag: impl Into<String>,
+        left: impl Codec<T, O>,
+        right: impl Codec<T2, O>,
+    ) -> impl Codec<Either<T, T2>, O> {
+        TaggedEitherCodec {
+            tag_key: tag_key.into(),
+            left_tag: left_tag.into(),
+            right_tag: right_tag.into(),
+            lc: left,
+            rc: right,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a [`NonEmptyVec`], encoded as a list. Decoding fails if the list is empty.
+    pub fn non_empty_list<T, O: CodecOps>(
+        codec: impl Codec<T, O>,
+    ) -> impl Codec<NonEmptyVec<T>, O> {
+        NonEmptyListCodec {
+            inner: codec,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a [`BTreeSet`], encoded as a list. Duplicate elements are merged when decoding,
+    /// unless [`SetCodec::strict`] is called on the returned codec.
+    pub fn btree_set<T: Ord, O: CodecOps, C: Codec<T, O>>(
+        codec: C,
+    ) -> SetCodec<T, C, BTreeSet<T>, O> {
+        SetCodec {
+            inner: codec,
+            strict: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a [`HashSet`], encoded as a list. Duplicate elements are merged when decoding,
+    /// unless [`SetCodec::strict`] is called on the returned codec.
+    #[cfg(feature = "std")]
+    pub fn hash_set<T: Eq + core::hash::Hash, O: CodecOps, C: Codec<T, O>>(
+        codec: C,
+    ) -> SetCodec<T, C, HashSet<T>, O> {
+        SetCodec {
+            inner: codec,
+            strict: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a [`Result`]. The value is encoded into a map with a single key,
+    /// `"ok"` for an [`Ok`] value or `"err"` for an [`Err`] value. Decoding fails if both or neither of the keys are present.
+    pub fn result<T, E, O: CodecOps>(
+        ok_codec: impl Codec<T, O>,
+        err_codec: impl Codec<E, O>,
+    ) -> impl Codec<Result<T, E>, O> {
+        ResultCodec {
+            ok: ok_codec,
+            err: err_codec,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] that picks the codec for each value with a function: `from_type_to_codec` when encoding,
+    /// and `from_ops_to_codec`, which inspects the encoded value, when decoding.
+    ///
+    /// When `from_ops_to_codec` finds a variant name that it doesn't know, it should return
+    /// [`DataError::unknown_variant`] with the names it accepts. If the variant is stored as a string under a key
+    /// of a map, [`Codecs::string_dispatch`] takes the variants as a map, such as a `HashMap<String, DynamicCodec<T, O>>`,
+    /// and reports unknown variants this way on its own.
     pub fn dispatch<
         T,
         O: CodecOps,
@@ -351,6 +885,151 @@ impl Codecs {
         }
     }
 
+    /// Creates a [`Codec`] that dispatches to a variant codec based on a string tag stored under `tag_key` in a map.
+    ///
+    /// When decoding, the tag is read from the map and the whole map, tag included, is decoded with the codec
+    /// registered for that tag. When encoding, `tag_of` selects the codec for the value.
+    /// The variant codecs own the tag, so each must write it, such as with [`MapCodecBuilder::constant_field`],
+    /// and encoding fails if a variant codec doesn't. Use [`Codecs::internally_tagged`] to add the tag for them instead.
+    /// Decoding an unknown tag returns an error listing all known tags.
+    pub fn string_dispatch<T, O: CodecOps, F: Fn(&T) -> &str>(
+        tag_key: impl Into<String>,
+        tag_of: F,
+        variants: impl IntoIterator<Item = (impl Into<String>, DynamicCodec<T, O>)>,
+    ) -> impl Codec<T, O> {
+        StringDispatchCodec::new(tag_key, tag_of, variants)
+    }
+
+    /// Creates a [`Codec`] for an internally-tagged value, where the tag is stored under `tag_key` alongside the
+    /// fields of the variant in the same map.
+    ///
+    /// Unlike [`Codecs::string_dispatch`], the variant codecs don't need to know about the tag. The tag is removed from
+    /// the map before it is decoded by the variant codec, and inserted into the map after it is encoded by the variant codec.
+    /// This means the variant codecs must encode to a map.
+    pub fn internally_tagged<T, O: CodecOps, F: Fn(&T) -> &str>(
+        tag_key: impl Into<String>,
+        tag_of: F,
+        variants: impl IntoIterator<Item = (impl Into<String>, DynamicCodec<T, O>)>,
+    ) -> impl Codec<T, O> {
+        InternallyTaggedCodec {
+            dispatch: StringDispatchCodec::new(tag_key, tag_of, variants),
+        }
+    }
+
+    /// Creates a [`Codec`] for an externally-tagged value, which is encoded into a map with a single key,
+    /// where the key is the tag and the value is encoded by the variant codec.
+    ///
+    /// Variants marked with [`ExternallyTaggedCodec::unit_variants`] are encoded as just the tag string instead.
+    pub fn externally_tagged<T, O: CodecOps, F: Fn(&T) -> &str>(
+        tag_of: F,
+        variants: impl IntoIterator<Item = (impl Into<String>, DynamicCodec<T, O>)>,
+    ) -> ExternallyTaggedCodec<T, O, F> {
+        ExternallyTaggedCodec {
+            dispatch: StringDispatchCodec::new(String::new(), tag_of, variants),
+            unit_variants: BTreeSet::new(),
+        }
+    }
+
+    /// Creates a [`Codec`] for an adjacently-tagged value, which is encoded into a map where the tag is stored
+    /// under `tag_key` and the value encoded by the variant codec is stored under `content_key`.
+    /// Variants marked with [`AdjacentlyTaggedCodec::unit_variants`] are encoded without content.
+    pub fn adjacently_tagged<T, O: CodecOps, F: Fn(&T) -> &str>(
+        tag_key: impl Into<String>,
+        content_key: impl Into<String>,
+        tag_of: F,
+        variants: impl IntoIterator<Item = (impl Into<String>, DynamicCodec<T, O>)>,
+    ) -> AdjacentlyTaggedCodec<T, O, F> {
+        AdjacentlyTaggedCodec {
+            dispatch: StringDispatchCodec::new(tag_key, tag_of, variants),
+            content_key: content_key.into(),
+            unit_variants: BTreeSet::new(),
+        }
+    }
+
+    /// Creates a [`Codec`] for versioned data, which stores its version under `version_field` in a map.
+    ///
+    /// Each rule in `rules_by_version` upgrades data to the version it is registered under, and the highest
+    /// version is the current version. When decoding, every rule newer than the version of the data is applied
+    /// in order with [`CodecOps::repair`], and the result is decoded with `latest_codec`.
+    /// Data without a version field is treated as version 0.
+    /// When encoding, the current version is written into the map encoded by `latest_codec`.
+    pub fn versioned<T, O: CodecOps>(
+        version_field: impl Into<String>,
+        rules_by_version: impl IntoIterator<Item = (u32, Box<dyn TypeRewriteRule<O>>)>,
+        latest_codec: impl Codec<T, O>,
+    ) -> impl Codec<T, O> {
+        Codecs::versioned_with(
+            version_field,
+            rules_by_version.into_iter().collect(),
+            latest_codec,
+        )
+    }
+
+    /// Creates a [`Codec`] for versioned data like [`Codecs::versioned`], using the rules registered in `fixer`.
+    pub fn versioned_with<T, O: CodecOps>(
+        version_field: impl Into<String>,
+        fixer: DataFixer<O>,
+        latest_codec: impl Codec<T, O>,
+    ) -> impl Codec<T, O> {
+        VersionedCodec {
+            version_field: version_field.into(),
+            fixer,
+            codec: latest_codec,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] that tries each of the given codecs in order when decoding, using the first one that succeeds.
+    /// If every codec fails, the error is a [`DataError::AllFailed`] holding the error of each codec.
+    ///
+    /// Values are encoded with the first codec, unless [`AnyOfCodec::encode_with`] is called on the returned codec.
+    pub fn any_of<T, O: CodecOps>(codecs: Vec<DynamicCodec<T, O>>) -> AnyOfCodec<T, O> {
+        AnyOfCodec {
+            codecs,
+            select: None,
+        }
+    }
+
+    /// Creates a [`Codec`] that maps each of the given strings to a value, such as a fieldless enum variant.
+    ///
+    /// Values are encoded as the string they are listed with. If `case_insensitive` is true, decoding also accepts
+    /// the strings in any casing, so `"High"`, `"high"` and `"HIGH"` decode to the same value.
+    /// Decoding an unknown string returns an error listing all valid strings.
+    pub fn string_enum<T: Clone + PartialEq + Debug, O: CodecOps>(
+        variants: impl IntoIterator<Item = (impl Into<String>, T)>,
+        case_insensitive: bool,
+    ) -> impl Codec<T, O> {
+        StringEnumCodec {
+            variants: variants
+                .into_iter()
+                .map(|(name, value)| (name.into(), value))
+                .collect(),
+            case_insensitive,
+        }
+    }
+
+    /// Creates a [`Codec`] that maps each of the given integers to a value, such as a fieldless enum variant
+    /// stored by its discriminant in a binary format.
+    ///
+    /// Values are encoded as the integer they are listed with, using the default codec of the integer type,
+    /// so `i8` discriminants are stored as bytes in NBT. Decoding an unknown integer
+    /// returns an error listing all valid integers.
+    pub fn int_enum<N, T, O>(variants: impl IntoIterator<Item = (N, T)>) -> impl Codec<T, O>
+    where
+        N: DefaultCodec<O> + PartialEq + Display,
+        T: Clone + PartialEq + Debug,
+        O: CodecOps,
+    {
+        IntEnumCodec {
+            codec: N::codec(),
+            variants: variants.into_iter().collect(),
+        }
+    }
+
+    /// Creates a [`Codec`] that only accepts a single value, such as a format marker like `"type": "player"`.
+    ///
+    /// The value is encoded and decoded with `codec`. Decoding fails if the decoded value is not `constant`,
+    /// and encoding fails if the value being encoded is not `constant`.
     pub fn constant<T: Clone + Debug + PartialEq, C: Codec<T, O>, O: CodecOps>(
         codec: C,
         constant: impl Into<T>,
@@ -362,7 +1041,236 @@ impl Codecs {
         }
     }
 
+    /// Creates a [`Codec`] for a map of arbitrary string keys to values of the provided codec.
+    /// This is the same as calling [`CodecAdapters::map_of`] on the value codec.
+    #[cfg(feature = "std")]
+    pub fn map<T, O: CodecOps, C: Codec<T, O>>(
+        codec: C,
+    ) -> HashMapCodec<T, O, C, std::hash::RandomState> {
+        codec.map_of()
+    }
+
+    /// Creates a [`Codec`] for a map whose keys are encoded with `key_codec` and whose values are encoded with `value_codec`.
+    ///
+    /// Since map keys must be strings, encoded keys are converted into strings. Keys that encode as numbers
+    /// are stringified, so a `HashMap<u32, T>` is stored as an object like `{"1": ..., "20": ...}`.
+    /// When decoding, each key string is passed back through `key_codec`.
+    #[cfg(feature = "std")]
+    pub fn map_with_key<K: Eq + core::hash::Hash, V, O: CodecOps>(
+        key_codec: impl Codec<K, O>,
+        value_codec: impl Codec<V, O>,
+    ) -> impl Codec<HashMap<K, V>, O> {
+        KeyedMapCodec {
+            key_codec,
+            value_codec,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a tuple of 3 elements, encoded as a list of 3 elements.
+    /// Decoding fails if the list does not contain exactly 3 elements.
+    pub fn tuple3<A, B, C, O: CodecOps>(
+        a: impl Codec<A, O>,
+        b: impl Codec<B, O>,
+        c: impl Codec<C, O>,
+    ) -> impl Codec<(A, B, C), O> {
+        Tuple3Codec {
+            codecs: (a, b, c),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a tuple of 4 elements. See [`Codecs::tuple3`] for more details.
+    pub fn tuple4<A, B, C, D, O: CodecOps>(
+        a: impl Codec<A, O>,
+        b: impl Codec<B, O>,
+        c: impl Codec<C, O>,
+        d: impl Codec<D, O>,
+    ) -> impl Codec<(A, B, C, D), O> {
+        Tuple4Codec {
+            codecs: (a, b, c, d),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a tuple of 5 elements. See [`Codecs::tuple3`] for more details.
+    pub fn tuple5<A, B, C, D, E, O: CodecOps>(
+        a: impl Codec<A, O>,
+        b: impl Codec<B, O>,
+        c: impl Codec<C, O>,
+        d: impl Codec<D, O>,
+        e: impl Codec<E, O>,
+    ) -> impl Codec<(A, B, C, D, E), O> {
+        Tuple5Codec {
+            codecs: (a, b, c, d, e),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a tuple of 6 elements. See [`Codecs::tuple3`] for more details.
+    pub fn tuple6<A, B, C, D, E, F, O: CodecOps>(
+        a: impl Codec<A, O>,
+        b: impl Codec<B, O>,
+        c: impl Codec<C, O>,
+        d: impl Codec<D, O>,
+        e: impl Codec<E, O>,
+        f: impl Codec<F, O>,
+    ) -> impl Codec<(A, B, C, D, E, F), O> {
+        Tuple6Codec {
+            codecs: (a, b, c, d, e, f),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a tuple of 7 elements. See [`Codecs::tuple3`] for more details.
+    pub fn tuple7<A, B, C, D, E, F, G, O: CodecOps>(
+        a: impl Codec<A, O>,
+        b: impl Codec<B, O>,
+        c: impl Codec<C, O>,
+        d: impl Codec<D, O>,
+        e: impl Codec<E, O>,
+        f: impl Codec<F, O>,
+        g: impl Codec<G, O>,
+    ) -> impl Codec<(A, B, C, D, E, F, G), O> {
+        Tuple7Codec {
+            codecs: (a, b, c, d, e, f, g),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a tuple of 8 elements. See [`Codecs::tuple3`] for more details.
+    #[allow(clippy::too_many_arguments)]
+    pub fn tuple8<A, B, C, D, E, F, G, H, O: CodecOps>(
+        a: impl Codec<A, O>,
+        b: impl Codec<B, O>,
+        c: impl Codec<C, O>,
+        d: impl Codec<D, O>,
+        e: impl Codec<E, O>,
+        f: impl Codec<F, O>,
+        g: impl Codec<G, O>,
+        h: impl Codec<H, O>,
+    ) -> impl Codec<(A, B, C, D, E, F, G, H), O> {
+        Tuple8Codec {
+            codecs: (a, b, c, d, e, f, g, h),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] that stores a [`Duration`] as a number of milliseconds.
+    /// Whole milliseconds are stored as an integer. Durations with sub-millisecond precision are stored as
+    /// fractional numbers, which are rounded to the precision of an `f64` for long durations.
+    /// Encoding fails for durations of more than `i64::MAX` milliseconds.
+    pub fn duration_millis<O: CodecOps>() -> impl Codec<Duration, O> {
+        DurationMillisCodec
+    }
+
+    /// Creates a [`Codec`] that stores a [`Duration`] as a fractional number of seconds.
+    pub fn duration_secs<O: CodecOps>() -> impl Codec<Duration, O> {
+        DurationSecsCodec
+    }
+
+    /// Creates a [`Codec`] that stores a byte array as a base64 string, using the standard alphabet with padding.
+    /// This is far more compact than encoding the bytes as a list of numbers.
+    pub fn bytes_base64<O: CodecOps>() -> impl Codec<Vec<u8>, O> {
+        Base64Codec
+    }
+
+    /// Creates a [`Codec`] that stores a number as a string of its decimal form, such as `"-1.5"`.
+    /// This is useful for APIs that send 64-bit IDs as strings, since not every consumer can read them as numbers exactly.
+    /// Decoding fails if the string can not be parsed as an `N`.
+    pub fn number_as_string<N: Display + FromStr, O: CodecOps>() -> impl Codec<N, O>
+    where
+        N::Err: Display,
+    {
+        FromStrCodec {
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a number that also accepts the number written as a string, such as `"42"`,
+    /// which is common in hand-edited files. Numbers are always encoded with the number's [`DefaultCodec`].
+    /// Decoding still fails if a string can not be parsed as an `N`.
+    pub fn lenient_number<N: DefaultCodec<O> + Display + FromStr, O: CodecOps>() -> impl Codec<N, O>
+    where
+        N::Err: Display,
+    {
+        LenientNumberCodec {
+            inner: N::codec(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] for a [`Range`] that is stored as a map with `start` and `end` keys, each using `inner`.
+    /// Encoding or decoding a range whose start is greater than its end returns an error.
+    pub fn range<T: PartialOrd + Debug, O: CodecOps>(
+        inner: impl Codec<T, O>,
+    ) -> impl Codec<Range<T>, O> {
+        RangeCodec {
+            inner,
+            new: |start, end| start..end,
+            bounds: |range: &Range<T>| (&range.start, &range.end),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Like [`Codecs::range`], but for a [`RangeInclusive`], where `end` is part of the range.
+    pub fn range_inclusive<T: PartialOrd + Debug, O: CodecOps>(
+        inner: impl Codec<T, O>,
+    ) -> impl Codec<RangeInclusive<T>, O> {
+        RangeCodec {
+            inner,
+            new: RangeInclusive::new,
+            bounds: |range: &RangeInclusive<T>| (range.start(), range.end()),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a [`Codec`] from a pair of functions, without defining a type that implements [`Codec`].
+    /// This is useful for one-off codecs and for prototyping, such as a format that needs to inspect the raw value.
+    ///
+    /// ```
+    /// use datafix::serialization::{Codec, CodecOps, Codecs, json::JsonOps};
+    ///
+    /// // Encodes a `bool` as `1` or `0`.
+    /// let codec = Codecs::of_fns(
+    ///     |ops: &JsonOps, value: &bool, _ctx| Ok(ops.create_int(&(*value as i32))),
+    ///     |ops: &JsonOps, value, _ctx| Ok(ops.get_int(value)? != 0),
+    /// );
+    /// assert_eq!(codec.encode_start(&JsonOps, &true).unwrap(), 1);
+    /// ```
+    pub fn of_fns<T, O: CodecOps>(
+        encode: impl Fn(&O, &T, &mut Context) -> DataResult<O::T> + 'static,
+        decode: impl Fn(&O, &O::T, &mut Context) -> DataResult<T> + 'static,
+    ) -> impl Codec<T, O> {
+        FnCodec {
+            encode: Box::new(encode),
+            decode: Box::new(decode),
+        }
+    }
+
     pub fn unit<O: CodecOps>() -> impl Codec<(), O> {
         UnitCodec {}
     }
+
+    /// Returns a codec for `Cow<'static, str>`, which lets string constants be encoded without allocating.
+    ///
+    /// A decoded value can not borrow from the input, so decoding always produces [`Cow::Owned`].
+    /// It reads the string with [`CodecOps::get_str`], which avoids an extra copy for formats that can lend one.
+    /// To avoid allocating entirely, call [`CodecOps::get_str`] directly.
+    pub fn cow_str<O: CodecOps>() -> impl Codec<Cow<'static, str>, O> {
+        CowStrCodec
+    }
+
+    /// Returns a codec for `Cow<'static, T>` that uses `inner` to serialize the owned form of `T`.
+    ///
+    /// Decoding always produces [`Cow::Owned`]. Encoding accepts either variant, but a [`Cow::Borrowed`]
+    /// value is converted to its owned form first, so prefer [`Codecs::cow_str`] for strings.
+    pub fn cow<T: ToOwned + ?Sized + 'static, O: CodecOps>(
+        inner: impl Codec<T::Owned, O>,
+    ) -> impl Codec<Cow<'static, T>, O> {
+        CowCodec {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
 }
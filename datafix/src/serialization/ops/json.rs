@@ -1,4 +1,6 @@
 use alloc::{
+    borrow::Cow,
+    format,
     string::{String, ToString},
     vec::Vec,
 };
@@ -9,11 +11,85 @@ use crate::{
     serialization::{CodecOps, ListView, MapView},
 };
 
-use super::{ListViewMut, MapViewMut};
+use super::{ListViewMut, MapViewMut, checked_integer};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct JsonOps;
 
+impl JsonOps {
+    /// Returns a [`PrettyJsonOps`], which produces the same values but stringifies them with indentation.
+    pub fn pretty() -> PrettyJsonOps {
+        PrettyJsonOps
+    }
+
+    /// Stringifies a value produced by this ops as compact JSON.
+    pub fn stringify(&self, value: &JsonValue) -> String {
+        value.dump()
+    }
+}
+
+/// A [`CodecOps`] that behaves exactly like [`JsonOps`], except that [`PrettyJsonOps::stringify`]
+/// renders values with 2-space indentation. This is useful for files that are edited by humans.
+#[derive(Debug, Clone, Default)]
+pub struct PrettyJsonOps;
+
+impl PrettyJsonOps {
+    /// Stringifies a value produced by this ops as JSON indented with 2 spaces.
+    pub fn stringify(&self, value: &JsonValue) -> String {
+        value.pretty(2)
+    }
+}
+
+macro_rules! delegate_to_json_ops {
+    ($($name:ident$(<$lifetime:lifetime>)?($($arg:ident: $arg_type:ty),*) -> $ret:ty;)*) => {
+        impl CodecOps for PrettyJsonOps {
+            type T = JsonValue;
+
+            $(fn $name$(<$lifetime>)?(&self, $($arg: $arg_type),*) -> $ret {
+                JsonOps.$name($($arg),*)
+            })*
+        }
+    };
+}
+
+delegate_to_json_ops! {
+    create_double(value: &f64) -> JsonValue;
+    create_float(value: &f32) -> JsonValue;
+    create_byte(value: &i8) -> JsonValue;
+    create_short(value: &i16) -> JsonValue;
+    create_int(value: &i32) -> JsonValue;
+    create_long(value: &i64) -> JsonValue;
+    create_unsigned_byte(value: &u8) -> DataResult<JsonValue>;
+    create_unsigned_short(value: &u16) -> DataResult<JsonValue>;
+    create_unsigned_int(value: &u32) -> DataResult<JsonValue>;
+    create_unsigned_long(value: &u64) -> DataResult<JsonValue>;
+    create_string(value: &str) -> JsonValue;
+    create_boolean(value: &bool) -> JsonValue;
+    create_list(value: impl IntoIterator<Item = JsonValue>) -> JsonValue;
+    create_map(pairs: impl IntoIterator<Item = (String, JsonValue)>) -> JsonValue;
+    create_unit() -> JsonValue;
+    create_none() -> JsonValue;
+    get_float(value: &JsonValue) -> DataResult<f32>;
+    get_double(value: &JsonValue) -> DataResult<f64>;
+    get_byte(value: &JsonValue) -> DataResult<i8>;
+    get_short(value: &JsonValue) -> DataResult<i16>;
+    get_int(value: &JsonValue) -> DataResult<i32>;
+    get_long(value: &JsonValue) -> DataResult<i64>;
+    get_unsigned_byte(value: &JsonValue) -> DataResult<u8>;
+    get_unsigned_short(value: &JsonValue) -> DataResult<u16>;
+    get_unsigned_int(value: &JsonValue) -> DataResult<u32>;
+    get_unsigned_long(value: &JsonValue) -> DataResult<u64>;
+    get_string(value: &JsonValue) -> DataResult<String>;
+    get_str<'a>(value: &'a JsonValue) -> DataResult<Cow<'a, str>>;
+    get_boolean(value: &JsonValue) -> DataResult<bool>;
+    get_list(value: &JsonValue) -> DataResult<impl ListView<JsonValue>>;
+    get_list_mut(value: &mut JsonValue) -> DataResult<impl ListViewMut<JsonValue>>;
+    get_map(value: &JsonValue) -> DataResult<impl MapView<JsonValue>>;
+    get_map_mut(value: &mut JsonValue) -> DataResult<impl MapViewMut<JsonValue>>;
+    get_unit(value: &JsonValue) -> DataResult<()>;
+    get_none(value: &JsonValue) -> DataResult<()>;
+}
+
 impl CodecOps for JsonOps {
     type T = JsonValue;
     fn create_double(&self, value: &f64) -> JsonValue {
@@ -29,12 +105,7 @@ impl CodecOps for JsonOps {
     }
 
     fn create_list(&self, value: impl IntoIterator<Item = JsonValue>) -> JsonValue {
-        let iter = value.into_iter();
-        let mut vec = Vec::with_capacity(iter.size_hint().1.unwrap_or(0));
-        for element in iter {
-            vec.push(element.clone());
-        }
-        JsonValue::Array(vec)
+        JsonValue::Array(value.into_iter().collect())
     }
 
     fn create_map(
@@ -44,7 +115,7 @@ impl CodecOps for JsonOps {
         let iter = pairs.into_iter();
         let mut obj = Object::with_capacity(iter.size_hint().1.unwrap_or(0));
         for (key, element) in iter {
-            obj.insert(&key, element.clone());
+            obj.insert(&key, element);
         }
         JsonValue::Object(obj)
     }
@@ -53,10 +124,14 @@ impl CodecOps for JsonOps {
         JsonValue::Object(Object::new())
     }
 
+    fn create_none(&self) -> JsonValue {
+        JsonValue::Null
+    }
+
     fn get_double(&self, value: &JsonValue) -> crate::result::DataResult<f64> {
         match value {
             JsonValue::Number(number) => Ok((*number).into()),
-            _ => Err(DataError::unexpected_type("number")),
+            _ => Err(type_mismatch("number", value)),
         }
     }
 
@@ -64,14 +139,22 @@ impl CodecOps for JsonOps {
         match value {
             JsonValue::String(string) => Ok(string.clone()),
             JsonValue::Short(short) => Ok(short.to_string()),
-            _ => Err(DataError::unexpected_type("string")),
+            _ => Err(type_mismatch("string", value)),
+        }
+    }
+
+    fn get_str<'a>(&self, value: &'a JsonValue) -> DataResult<Cow<'a, str>> {
+        match value {
+            JsonValue::String(string) => Ok(Cow::Borrowed(string)),
+            JsonValue::Short(short) => Ok(Cow::Borrowed(short.as_str())),
+            _ => Err(type_mismatch("string", value)),
         }
     }
 
     fn get_boolean(&self, value: &JsonValue) -> crate::result::DataResult<bool> {
         match value {
             JsonValue::Boolean(boolean) => Ok(*boolean),
-            _ => Err(DataError::unexpected_type("boolean")),
+            _ => Err(type_mismatch("boolean", value)),
         }
     }
 
@@ -81,7 +164,7 @@ impl CodecOps for JsonOps {
     ) -> crate::result::DataResult<impl crate::serialization::ListView<JsonValue>> {
         match value {
             JsonValue::Array(_) => Ok(JsonListView { inner: value }),
-            _ => Err(DataError::unexpected_type("array")),
+            _ => Err(type_mismatch("array", value)),
         }
     }
 
@@ -91,7 +174,7 @@ impl CodecOps for JsonOps {
     ) -> crate::result::DataResult<impl crate::serialization::ListViewMut<JsonValue>> {
         match value {
             JsonValue::Array(_) => Ok(JsonListViewMut { inner: value }),
-            _ => Err(DataError::unexpected_type("array")),
+            _ => Err(type_mismatch("array", value)),
         }
     }
 
@@ -101,7 +184,7 @@ impl CodecOps for JsonOps {
     ) -> crate::result::DataResult<impl crate::serialization::MapView<JsonValue>> {
         match value {
             JsonValue::Object(_) => Ok(JsonObjectView { inner: value }),
-            _ => Err(DataError::unexpected_type("object")),
+            _ => Err(type_mismatch("object", value)),
         }
     }
 
@@ -111,13 +194,21 @@ impl CodecOps for JsonOps {
     ) -> crate::result::DataResult<impl crate::serialization::MapViewMut<JsonValue>> {
         match value {
             JsonValue::Object(_) => Ok(JsonObjectViewMut { inner: value }),
-            _ => Err(DataError::unexpected_type("object")),
+            _ => Err(type_mismatch("object", value)),
+        }
+    }
+
+    fn get_none(&self, value: &JsonValue) -> crate::result::DataResult<()> {
+        if value.is_null() {
+            Ok(())
+        } else {
+            Err(type_mismatch("null", value))
         }
     }
 
     fn get_unit(&self, value: &JsonValue) -> crate::result::DataResult<()> {
         let JsonValue::Object(object) = value else {
-            return Err(DataError::unexpected_type("object"));
+            return Err(type_mismatch("object", value));
         };
         if object.is_empty() {
             Ok(())
@@ -126,6 +217,9 @@ impl CodecOps for JsonOps {
         }
     }
 
+    /// JSON has a single number type, so `f32`s are widened to `f64`, and may be written with extra digits
+    /// (`0.1f32` is written as `0.10000000149011612`). Widening is exact, so [`CodecOps::get_float`] narrows
+    /// the value back to the same bit pattern.
     fn create_float(&self, value: &f32) -> JsonValue {
         JsonValue::Number(Number::from(*value))
     }
@@ -146,40 +240,112 @@ impl CodecOps for JsonOps {
         JsonValue::Number(Number::from(*value))
     }
 
+    fn create_unsigned_byte(&self, value: &u8) -> DataResult<JsonValue> {
+        Ok(JsonValue::Number(Number::from(*value)))
+    }
+
+    fn create_unsigned_short(&self, value: &u16) -> DataResult<JsonValue> {
+        Ok(JsonValue::Number(Number::from(*value)))
+    }
+
+    fn create_unsigned_int(&self, value: &u32) -> DataResult<JsonValue> {
+        Ok(JsonValue::Number(Number::from(*value)))
+    }
+
+    fn create_unsigned_long(&self, value: &u64) -> DataResult<JsonValue> {
+        Ok(JsonValue::Number(Number::from(*value)))
+    }
+
     fn get_float(&self, value: &JsonValue) -> DataResult<f32> {
         match value {
-            JsonValue::Number(number) => Ok((*number).into()),
-            _ => Err(DataError::unexpected_type("number")),
+            // Narrowing through `f64` rounds correctly, while converting the decimal straight to `f32` can lose
+            // subnormal values.
+            JsonValue::Number(number) => Ok(f64::from(*number) as f32),
+            _ => Err(type_mismatch("number", value)),
         }
     }
 
     fn get_byte(&self, value: &JsonValue) -> DataResult<i8> {
-        match value {
-            JsonValue::Number(number) => Ok(Into::<f64>::into(*number) as i8),
-            _ => Err(DataError::unexpected_type("number")),
-        }
+        get_integer(value)
     }
 
     fn get_short(&self, value: &JsonValue) -> DataResult<i16> {
-        match value {
-            JsonValue::Number(number) => Ok(Into::<f64>::into(*number) as i16),
-            _ => Err(DataError::unexpected_type("number")),
-        }
+        get_integer(value)
     }
 
     fn get_int(&self, value: &JsonValue) -> DataResult<i32> {
-        match value {
-            JsonValue::Number(number) => Ok(Into::<f64>::into(*number) as i32),
-            _ => Err(DataError::unexpected_type("number")),
-        }
+        get_integer(value)
     }
 
     fn get_long(&self, value: &JsonValue) -> DataResult<i64> {
-        match value {
-            JsonValue::Number(number) => Ok(Into::<f64>::into(*number) as i64),
-            _ => Err(DataError::unexpected_type("number")),
-        }
+        get_integer(value)
+    }
+
+    fn get_unsigned_byte(&self, value: &JsonValue) -> DataResult<u8> {
+        get_integer(value)
+    }
+
+    fn get_unsigned_short(&self, value: &JsonValue) -> DataResult<u16> {
+        get_integer(value)
+    }
+
+    fn get_unsigned_int(&self, value: &JsonValue) -> DataResult<u32> {
+        get_integer(value)
     }
+
+    fn get_unsigned_long(&self, value: &JsonValue) -> DataResult<u64> {
+        get_integer(value)
+    }
+}
+
+/// Converts a JSON number into an integer exactly, without rounding it through an `f64` first.
+/// Fails if the number has a fractional part or does not fit in `I`.
+fn get_integer<I: TryFrom<i128>>(value: &JsonValue) -> DataResult<I> {
+    let JsonValue::Number(number) = value else {
+        return Err(type_mismatch("number", value));
+    };
+    let not_an_integer =
+        || DataError::new_custom(&format!("expected an integer, found {}", number));
+    if number.is_nan() {
+        return Err(not_an_integer());
+    }
+
+    let (positive, mut mantissa, mut exponent) = number.as_parts();
+    while exponent < 0 && mantissa != 0 && mantissa % 10 == 0 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+    if mantissa == 0 {
+        return checked_integer(0);
+    }
+    if exponent < 0 {
+        return Err(not_an_integer());
+    }
+
+    let mut magnitude = mantissa as i128;
+    for _ in 0..exponent {
+        magnitude = magnitude.checked_mul(10).ok_or_else(|| {
+            DataError::out_of_range(&format!(
+                "{} does not fit in {}",
+                number,
+                core::any::type_name::<I>()
+            ))
+        })?;
+    }
+    checked_integer(if positive { magnitude } else { -magnitude })
+}
+
+/// Creates a [`DataError::TypeMismatch`] for a value that was expected to be of type `expected`.
+fn type_mismatch(expected: &str, found: &JsonValue) -> DataError {
+    let found = match found {
+        JsonValue::Null => "null",
+        JsonValue::Short(_) | JsonValue::String(_) => "string",
+        JsonValue::Number(_) => "number",
+        JsonValue::Boolean(_) => "boolean",
+        JsonValue::Object(_) => "object",
+        JsonValue::Array(_) => "array",
+    };
+    DataError::type_mismatch(expected, found)
 }
 
 struct JsonObjectView<'a> {
@@ -203,6 +369,25 @@ impl MapView<JsonValue> for JsonObjectView<'_> {
         };
         Vec::new()
     }
+
+    fn entries<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a JsonValue)>
+    where
+        JsonValue: 'a,
+    {
+        match &self.inner {
+            JsonValue::Object(object) => Some(object.iter()),
+            _ => None,
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    fn len(&self) -> usize {
+        match &self.inner {
+            JsonValue::Object(object) => object.len(),
+            _ => 0,
+        }
+    }
 }
 
 struct JsonObjectViewMut<'a> {
@@ -226,6 +411,25 @@ impl MapView<JsonValue> for JsonObjectViewMut<'_> {
         };
         Vec::new()
     }
+
+    fn entries<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a JsonValue)>
+    where
+        JsonValue: 'a,
+    {
+        match &self.inner {
+            JsonValue::Object(object) => Some(object.iter()),
+            _ => None,
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    fn len(&self) -> usize {
+        match &self.inner {
+            JsonValue::Object(object) => object.len(),
+            _ => 0,
+        }
+    }
 }
 
 impl MapViewMut<JsonValue> for JsonObjectViewMut<'_> {
@@ -239,6 +443,18 @@ impl MapViewMut<JsonValue> for JsonObjectViewMut<'_> {
         }
     }
 
+    fn entries_mut<'a>(&'a mut self) -> impl Iterator<Item = (&'a str, &'a mut JsonValue)>
+    where
+        JsonValue: 'a,
+    {
+        match &mut self.inner {
+            JsonValue::Object(object) => Some(object.iter_mut()),
+            _ => None,
+        }
+        .into_iter()
+        .flatten()
+    }
+
     fn set(&mut self, name: &str, value: JsonValue) {
         let JsonValue::Object(object) = self.inner else {
             return;
@@ -274,6 +490,13 @@ impl ListView<JsonValue> for JsonListView<'_> {
         }
     }
 
+    fn len(&self) -> usize {
+        match self.inner {
+            JsonValue::Array(array) => array.len(),
+            _ => 0,
+        }
+    }
+
     fn into_iter(self) -> impl Iterator<Item = JsonValue> {
         let JsonValue::Array(array) = self.inner else {
             panic!();
@@ -304,14 +527,126 @@ impl ListViewMut<JsonValue> for JsonListViewMut<'_> {
 #[cfg(test)]
 mod tests {
 
-    use crate::serialization::{Codec, DefaultCodec};
+    use alloc::vec::Vec;
+
+    use crate::serialization::{
+        Codec, CodecAdapters, CodecOps, DefaultCodec, ListView, MapCodecBuilder, MapView,
+        MapViewMut,
+    };
+
+    use crate::result::DataError;
 
     use super::JsonOps;
 
+    #[test]
+    fn pretty_round_trip() {
+        let codec = MapCodecBuilder::new()
+            .field(f64::codec().field_of("a", |x: &(f64, bool)| &x.0))
+            .field(bool::codec().field_of("b", |x: &(f64, bool)| &x.1))
+            .build(|a, b| (a, b));
+        let ops = JsonOps::pretty();
+        let encoded = codec.encode_start(&ops, &(1.5, true)).unwrap();
+        let string = ops.stringify(&encoded);
+        assert_eq!(string, "{\n  \"a\": 1.5,\n  \"b\": true\n}");
+        assert_eq!(JsonOps.stringify(&encoded), "{\"a\":1.5,\"b\":true}");
+
+        let parsed = json::parse(&string).unwrap();
+        assert_eq!(codec.decode_start(&ops, &parsed).unwrap(), (1.5, true));
+    }
+
     #[test]
     fn simple_encode_decode() {
         let encoded = f64::codec().encode_start(&JsonOps, &10.0).unwrap();
         let decoded = f64::codec().decode_start(&JsonOps, &encoded).unwrap();
         assert_eq!(decoded, 10.0);
     }
+
+    #[test]
+    fn list_len() {
+        let array = json::array![1, 2, 3];
+        assert_eq!(JsonOps.get_list(&array).unwrap().len(), 3);
+        assert!(JsonOps.get_list(&json::array![]).unwrap().is_empty());
+
+        let decoded = i32::codec()
+            .list_of()
+            .decode_start(&JsonOps, &array)
+            .unwrap();
+        assert_eq!(decoded, [1, 2, 3]);
+    }
+
+    #[test]
+    fn map_len() {
+        let object = json::object! { "a": 1, "b": 2 };
+        let view = JsonOps.get_map(&object).unwrap();
+        assert_eq!(view.len(), 2);
+        assert!(!view.is_empty());
+        assert!(view.contains_key("a"));
+        assert!(!view.contains_key("c"));
+        assert!(JsonOps.get_map(&json::object! {}).unwrap().is_empty());
+    }
+
+    #[test]
+    fn map_entries() {
+        let mut object = json::object! { "a": 1, "b": 2 };
+        {
+            let mut view = JsonOps.get_map_mut(&mut object).unwrap();
+            for (_, value) in view.entries_mut() {
+                *value = (value.as_i32().unwrap() * 10).into();
+            }
+        }
+        let view = JsonOps.get_map(&object).unwrap();
+        let entries = view
+            .entries()
+            .map(|(key, value)| (key, value.as_i32().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [("a", 10), ("b", 20)]);
+    }
+
+    #[test]
+    fn exact_integers() {
+        let big = i64::MAX - 1;
+        let encoded = i64::codec().encode_start(&JsonOps, &big).unwrap();
+        assert_eq!(i64::codec().decode_start(&JsonOps, &encoded).unwrap(), big);
+
+        let parse = |string| json::parse(string).unwrap();
+        assert_eq!(
+            i32::codec().decode_start(&JsonOps, &parse("1.0")).unwrap(),
+            1
+        );
+        assert_eq!(
+            i32::codec().decode_start(&JsonOps, &parse("1e3")).unwrap(),
+            1000
+        );
+        assert_eq!(
+            i32::codec().decode_start(&JsonOps, &parse("-0")).unwrap(),
+            0
+        );
+        assert!(i32::codec().decode_start(&JsonOps, &parse("1.5")).is_err());
+        assert!(matches!(
+            i8::codec()
+                .decode_start(&JsonOps, &parse("300"))
+                .unwrap_err()
+                .error(),
+            DataError::OutOfRange { .. }
+        ));
+        assert!(i64::codec().decode_start(&JsonOps, &parse("1e30")).is_err());
+    }
+
+    #[test]
+    fn f32_round_trip() {
+        for value in [0.1f32, 1.0e-38, f32::MAX, f32::MIN_POSITIVE, -3.3] {
+            let encoded = f32::codec().encode_start(&JsonOps, &value).unwrap();
+            let parsed = json::parse(&encoded.dump()).unwrap();
+            let decoded = f32::codec().decode_start(&JsonOps, &parsed).unwrap();
+            assert_eq!(decoded.to_bits(), value.to_bits());
+        }
+    }
+
+    #[test]
+    fn unit_and_none() {
+        assert!(JsonOps.get_unit(&json::object! {}).is_ok());
+        assert!(JsonOps.get_unit(&json::JsonValue::Null).is_err());
+        assert!(JsonOps.get_none(&json::JsonValue::Null).is_ok());
+        assert!(JsonOps.get_none(&json::object! {}).is_err());
+    }
 }
@@ -1,8 +1,14 @@
 pub mod json;
+#[cfg(feature = "nbt")]
+pub mod nbt;
+pub mod value;
 
-use alloc::{string::String, vec::Vec};
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
 
-use crate::{fixers::TypeRewriteRule, result::DataResult};
+use crate::{
+    fixers::TypeRewriteRule,
+    result::{DataError, DataResult},
+};
 
 /// A [`CodecOps`] represents a way of converting Rust values into the target datatype and vice-versa.
 /// [`CodecOps`] is the recommended way to do this when interacting with [`Codec`].
@@ -33,6 +39,24 @@ pub trait CodecOps: Clone {
     /// Creates a new numeric value of type `T`.
     fn create_long(&self, value: &i64) -> Self::T;
 
+    /// Creates a new numeric value of type `T` from an unsigned integer. Formats with only signed integers store it
+    /// at the same width, so the default implementation fails for values above `i8::MAX`.
+    fn create_unsigned_byte(&self, value: &u8) -> DataResult<Self::T> {
+        Ok(self.create_byte(&checked_integer(*value as i128)?))
+    }
+    /// Creates a new numeric value of type `T` from an unsigned integer, like [`CodecOps::create_unsigned_byte`].
+    fn create_unsigned_short(&self, value: &u16) -> DataResult<Self::T> {
+        Ok(self.create_short(&checked_integer(*value as i128)?))
+    }
+    /// Creates a new numeric value of type `T` from an unsigned integer, like [`CodecOps::create_unsigned_byte`].
+    fn create_unsigned_int(&self, value: &u32) -> DataResult<Self::T> {
+        Ok(self.create_int(&checked_integer(*value as i128)?))
+    }
+    /// Creates a new numeric value of type `T` from an unsigned integer, like [`CodecOps::create_unsigned_byte`].
+    fn create_unsigned_long(&self, value: &u64) -> DataResult<Self::T> {
+        Ok(self.create_long(&checked_integer(*value as i128)?))
+    }
+
     /// Creates a new string value of type `T`.
     fn create_string(&self, value: &str) -> Self::T;
     /// Creates a new boolean value of type `T`.
@@ -43,6 +67,11 @@ pub trait CodecOps: Clone {
     fn create_map(&self, pairs: impl IntoIterator<Item = (String, Self::T)>) -> Self::T;
     /// Creates a new map type of type `T`. The value should have no associated fields or value. An empty map is a valid example of a representation.
     fn create_unit(&self) -> Self::T;
+    /// Creates the value an absent value such as `None` is encoded as. Formats with a null value, like JSON,
+    /// should use it. The default implementation falls back to [`CodecOps::create_unit`].
+    fn create_none(&self) -> Self::T {
+        self.create_unit()
+    }
 
     /// This converts a value of type `T` into a value of type `f32`.
     fn get_float(&self, value: &Self::T) -> DataResult<f32>;
@@ -58,8 +87,31 @@ pub trait CodecOps: Clone {
     /// This converts a value of type `T` into a value of type `i64`.
     fn get_long(&self, value: &Self::T) -> DataResult<i64>;
 
+    /// This converts a value of type `T` into a value of type `u8`.
+    /// The default implementation accepts any integer that fits.
+    fn get_unsigned_byte(&self, value: &Self::T) -> DataResult<u8> {
+        checked_integer(self.get_long(value)? as i128)
+    }
+    /// This converts a value of type `T` into a value of type `u16`, like [`CodecOps::get_unsigned_byte`].
+    fn get_unsigned_short(&self, value: &Self::T) -> DataResult<u16> {
+        checked_integer(self.get_long(value)? as i128)
+    }
+    /// This converts a value of type `T` into a value of type `u32`, like [`CodecOps::get_unsigned_byte`].
+    fn get_unsigned_int(&self, value: &Self::T) -> DataResult<u32> {
+        checked_integer(self.get_long(value)? as i128)
+    }
+    /// This converts a value of type `T` into a value of type `u64`, like [`CodecOps::get_unsigned_byte`].
+    fn get_unsigned_long(&self, value: &Self::T) -> DataResult<u64> {
+        checked_integer(self.get_long(value)? as i128)
+    }
+
     /// This converts a value of type `T` into a value of type `String`.
     fn get_string(&self, value: &Self::T) -> DataResult<String>;
+    /// This converts a value of type `T` into a string, borrowing from `value` when the underlying format allows it.
+    /// The default implementation falls back to [`CodecOps::get_string`], so it always allocates.
+    fn get_str<'a>(&self, value: &'a Self::T) -> DataResult<Cow<'a, str>> {
+        self.get_string(value).map(Cow::Owned)
+    }
     /// This converts a value of type `T` into a value of type `bool`.
     fn get_boolean(&self, value: &Self::T) -> DataResult<bool>;
     /// This converts a value of type `T` into a view into a list's contents.
@@ -72,6 +124,11 @@ pub trait CodecOps: Clone {
     fn get_map_mut(&self, value: &mut Self::T) -> DataResult<impl MapViewMut<Self::T>>;
     /// This converts a value of type `T` into a unit value with no fields or associated values.
     fn get_unit(&self, value: &Self::T) -> DataResult<()>;
+    /// This checks that a value of type `T` is the absent value created by [`CodecOps::create_none`].
+    /// The default implementation falls back to [`CodecOps::get_unit`].
+    fn get_none(&self, value: &Self::T) -> DataResult<()> {
+        self.get_unit(value)
+    }
 
     /// This purely exists for Optional Fields. The `Option` represents if a field is present,
     /// the `DataResult` represents the actual field data.
@@ -100,6 +157,20 @@ pub trait MapView<T> {
     fn get(&self, name: &str) -> DataResult<&T>;
     /// Obtains a mutable reference to an underlying value. May return a DataError::KeyNotFoundInMap if the key is not present in the map.
     fn keys(&self) -> Vec<String>;
+    /// Iterates over the keys and values of the map together, without copying the keys.
+    fn entries<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a T)>
+    where
+        T: 'a;
+    /// Returns the number of entries in the map.
+    fn len(&self) -> usize;
+    /// Returns `true` if the map has no entries.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns `true` if the map has an entry under the key.
+    fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_ok()
+    }
 }
 
 /// Represents a mutable lens into an map type from a [`CodecOps`]. Methods in this should be assumed to mutate - modifying the value using a [`MapView`]
@@ -107,6 +178,10 @@ pub trait MapView<T> {
 pub trait MapViewMut<T>: MapView<T> {
     /// Obtains a mutable reference to an underlying value. May return a DataError::KeyNotFoundInMap if the key is not present in the map.
     fn get_mut(&mut self, name: &str) -> DataResult<&mut T>;
+    /// Iterates over the keys of the map together with mutable references to their values.
+    fn entries_mut<'a>(&'a mut self) -> impl Iterator<Item = (&'a str, &'a mut T)>
+    where
+        T: 'a;
     /// Sets a key-value pair in the map to a certain value.
     fn set(&mut self, name: &str, value: T);
     /// Removes a certain key from the map, returning it's old value if the value was present. May return a DataError::KeyNotFoundInMap if the key
@@ -124,6 +199,12 @@ pub trait ListView<T> {
     /// Gets a mutable reference to a value at an index inside of a list. May return a DataError::ListIndexOutOfBounds if the index is out of bounds.
     /// This is up to the implementor of this method to check.
     fn get(&self, index: usize) -> DataResult<&T>;
+    /// Returns the number of elements in the list.
+    fn len(&self) -> usize;
+    /// Returns `true` if the list has no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
     /// This consumes the value inside of the ListView and turns it into an iterator. This method may change in the near future.
     fn into_iter(self) -> impl Iterator<Item = T>;
 }
@@ -137,3 +218,27 @@ pub trait ListViewMut<T> {
     /// This is up to the implementor of this method to check.
     fn get_mut(&mut self, index: usize) -> DataResult<&mut T>;
 }
+
+/// Converts an integer read by a [`CodecOps`] into the integer type that was asked for, failing if it does not fit.
+pub(crate) fn checked_integer<I: TryFrom<i128>>(value: i128) -> DataResult<I> {
+    I::try_from(value).map_err(|_| {
+        DataError::out_of_range(&format!(
+            "{} does not fit in {}",
+            value,
+            core::any::type_name::<I>()
+        ))
+    })
+}
+
+/// Converts a floating point number read by a [`CodecOps`] into an integer type,
+/// failing if it has a fractional part or does not fit.
+pub(crate) fn integer_from_float<I: TryFrom<i128>>(value: f64) -> DataResult<I> {
+    let integer = value as i128;
+    if integer as f64 != value {
+        return Err(DataError::new_custom(&format!(
+            "expected an integer, found {}",
+            value
+        )));
+    }
+    checked_integer(integer)
+}
@@ -0,0 +1,845 @@
+use alloc::{
+    borrow::Cow,
+    collections::btree_map::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    result::{DataError, DataResult},
+    serialization::{CodecOps, ListView, MapView},
+};
+
+use super::{ListViewMut, MapViewMut, checked_integer, integer_from_float};
+
+/// A value in Minecraft's Named Binary Tag format.
+///
+/// Unlike JSON, NBT distinguishes between numeric types, so [`NbtOps`] preserves the type chosen by a codec,
+/// such as an `i8` being stored as a [`NbtTag::Byte`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtTag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<NbtTag>),
+    Compound(BTreeMap<String, NbtTag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    /// Returns the ID used for this tag's type in the binary format.
+    pub fn id(&self) -> u8 {
+        match self {
+            NbtTag::Byte(_) => 1,
+            NbtTag::Short(_) => 2,
+            NbtTag::Int(_) => 3,
+            NbtTag::Long(_) => 4,
+            NbtTag::Float(_) => 5,
+            NbtTag::Double(_) => 6,
+            NbtTag::ByteArray(_) => 7,
+            NbtTag::String(_) => 8,
+            NbtTag::List(_) => 9,
+            NbtTag::Compound(_) => 10,
+            NbtTag::IntArray(_) => 11,
+            NbtTag::LongArray(_) => 12,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            NbtTag::Byte(_) => "byte",
+            NbtTag::Short(_) => "short",
+            NbtTag::Int(_) => "int",
+            NbtTag::Long(_) => "long",
+            NbtTag::Float(_) => "float",
+            NbtTag::Double(_) => "double",
+            NbtTag::ByteArray(_) => "byte array",
+            NbtTag::String(_) => "string",
+            NbtTag::List(_) => "list",
+            NbtTag::Compound(_) => "compound",
+            NbtTag::IntArray(_) => "int array",
+            NbtTag::LongArray(_) => "long array",
+        }
+    }
+
+    /// Returns the elements of a list or typed array, converting the elements of typed arrays into tags.
+    fn elements(&self) -> Option<Cow<'_, [NbtTag]>> {
+        match self {
+            NbtTag::List(list) => Some(Cow::Borrowed(list)),
+            NbtTag::ByteArray(array) => Some(array.iter().map(|x| NbtTag::Byte(*x)).collect()),
+            NbtTag::IntArray(array) => Some(array.iter().map(|x| NbtTag::Int(*x)).collect()),
+            NbtTag::LongArray(array) => Some(array.iter().map(|x| NbtTag::Long(*x)).collect()),
+            _ => None,
+        }
+    }
+
+    /// Writes this tag as an uncompressed named root tag in the binary NBT format.
+    ///
+    /// Files such as `level.dat` are usually gzip-compressed, which has to be handled by the caller.
+    pub fn write(&self, name: &str, out: &mut Vec<u8>) -> DataResult<()> {
+        out.push(self.id());
+        write_string(name, out)?;
+        self.write_payload(out)
+    }
+
+    /// Reads an uncompressed named root tag in the binary NBT format, returning its name and value.
+    pub fn read(bytes: &[u8]) -> DataResult<(String, NbtTag)> {
+        let mut reader = Reader { bytes, position: 0 };
+        let id = reader.u8()?;
+        let name = reader.string()?;
+        let tag = reader.payload(id, 0)?;
+        Ok((name, tag))
+    }
+
+    fn write_payload(&self, out: &mut Vec<u8>) -> DataResult<()> {
+        match self {
+            NbtTag::Byte(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Short(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Int(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Long(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Float(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::Double(value) => out.extend_from_slice(&value.to_be_bytes()),
+            NbtTag::ByteArray(array) => {
+                write_length(array.len(), out)?;
+                out.extend(array.iter().map(|x| *x as u8));
+            }
+            NbtTag::String(string) => write_string(string, out)?,
+            NbtTag::List(list) => {
+                let id = list.first().map(NbtTag::id).unwrap_or(0);
+                if list.iter().any(|element| element.id() != id) {
+                    return Err(DataError::new_custom(
+                        "all elements of an NBT list must have the same type",
+                    ));
+                }
+                out.push(id);
+                write_length(list.len(), out)?;
+                for element in list {
+                    element.write_payload(out)?;
+                }
+            }
+            NbtTag::Compound(compound) => {
+                for (name, tag) in compound {
+                    tag.write(name, out)?;
+                }
+                out.push(0);
+            }
+            NbtTag::IntArray(array) => {
+                write_length(array.len(), out)?;
+                for value in array {
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+            NbtTag::LongArray(array) => {
+                write_length(array.len(), out)?;
+                for value in array {
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_length(length: usize, out: &mut Vec<u8>) -> DataResult<()> {
+    let length = i32::try_from(length)
+        .map_err(|_| DataError::out_of_range("NBT lists can have at most i32::MAX elements"))?;
+    out.extend_from_slice(&length.to_be_bytes());
+    Ok(())
+}
+
+/// Writes a string in Java's modified UTF-8, which NBT uses: NUL is written as two bytes,
+/// and characters outside the Basic Multilingual Plane as a surrogate pair of three bytes each.
+fn write_string(string: &str, out: &mut Vec<u8>) -> DataResult<()> {
+    let length = string
+        .encode_utf16()
+        .map(|unit| match unit {
+            0x01..=0x7F => 1,
+            0x00 | 0x80..=0x7FF => 2,
+            _ => 3,
+        })
+        .sum::<usize>();
+    let length = u16::try_from(length)
+        .map_err(|_| DataError::out_of_range("NBT strings can have at most 65535 bytes"))?;
+    out.extend_from_slice(&length.to_be_bytes());
+    for unit in string.encode_utf16() {
+        match unit {
+            0x01..=0x7F => out.push(unit as u8),
+            0x00 | 0x80..=0x7FF => {
+                out.push(0xC0 | (unit >> 6) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                out.push(0xE0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// NBT nests compounds and lists recursively, so the depth is limited to stop malicious input from overflowing the stack.
+const MAX_DEPTH: usize = 512;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> DataResult<[u8; N]> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + N)
+            .ok_or_else(|| DataError::new_custom("unexpected end of NBT data"))?;
+        self.position += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> DataResult<u8> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn length(&mut self) -> DataResult<usize> {
+        let length = i32::from_be_bytes(self.take()?);
+        usize::try_from(length)
+            .map_err(|_| DataError::new_custom(&format!("invalid NBT length {}", length)))
+    }
+
+    /// Reads a string in Java's modified UTF-8, see [`write_string`].
+    fn string(&mut self) -> DataResult<String> {
+        let length = u16::from_be_bytes(self.take()?) as usize;
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + length)
+            .ok_or_else(|| DataError::new_custom("unexpected end of NBT data"))?;
+        self.position += length;
+        let invalid = || DataError::new_custom("NBT string is not valid modified UTF-8");
+        let continuation = |byte: Option<&u8>| match byte {
+            Some(byte) if byte & 0xC0 == 0x80 => Ok((byte & 0x3F) as u16),
+            _ => Err(invalid()),
+        };
+        let mut units = Vec::with_capacity(length);
+        let mut bytes = bytes.iter();
+        while let Some(&byte) = bytes.next() {
+            let unit = match byte {
+                0x00..=0x7F => byte as u16,
+                0xC0..=0xDF => ((byte & 0x1F) as u16) << 6 | continuation(bytes.next())?,
+                0xE0..=0xEF => {
+                    ((byte & 0x0F) as u16) << 12
+                        | continuation(bytes.next())? << 6
+                        | continuation(bytes.next())?
+                }
+                _ => return Err(invalid()),
+            };
+            units.push(unit);
+        }
+        String::from_utf16(&units).map_err(|_| invalid())
+    }
+
+    fn array<T, const N: usize>(&mut self, f: fn([u8; N]) -> T) -> DataResult<Vec<T>> {
+        let length = self.length()?;
+        let mut array = Vec::with_capacity(length.min(self.bytes.len() / N));
+        for _ in 0..length {
+            array.push(f(self.take()?));
+        }
+        Ok(array)
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> DataResult<NbtTag> {
+        if depth > MAX_DEPTH {
+            return Err(DataError::new_custom("NBT data is nested too deeply"));
+        }
+        Ok(match id {
+            1 => NbtTag::Byte(i8::from_be_bytes(self.take()?)),
+            2 => NbtTag::Short(i16::from_be_bytes(self.take()?)),
+            3 => NbtTag::Int(i32::from_be_bytes(self.take()?)),
+            4 => NbtTag::Long(i64::from_be_bytes(self.take()?)),
+            5 => NbtTag::Float(f32::from_be_bytes(self.take()?)),
+            6 => NbtTag::Double(f64::from_be_bytes(self.take()?)),
+            7 => NbtTag::ByteArray(self.array(i8::from_be_bytes)?),
+            8 => NbtTag::String(self.string()?),
+            9 => {
+                let element_id = self.u8()?;
+                let length = self.length()?;
+                let mut list = Vec::new();
+                for _ in 0..length {
+                    list.push(self.payload(element_id, depth + 1)?);
+                }
+                NbtTag::List(list)
+            }
+            10 => {
+                let mut compound = BTreeMap::new();
+                loop {
+                    let id = self.u8()?;
+                    if id == 0 {
+                        break;
+                    }
+                    let name = self.string()?;
+                    compound.insert(name, self.payload(id, depth + 1)?);
+                }
+                NbtTag::Compound(compound)
+            }
+            11 => NbtTag::IntArray(self.array(i32::from_be_bytes)?),
+            12 => NbtTag::LongArray(self.array(i64::from_be_bytes)?),
+            _ => return Err(DataError::new_custom(&format!("unknown NBT tag id {}", id))),
+        })
+    }
+}
+
+fn type_mismatch(expected: &str, found: &NbtTag) -> DataError {
+    DataError::type_mismatch(expected, found.type_name())
+}
+
+macro_rules! get_number {
+    ($value:expr, $t:ty) => {
+        match $value {
+            NbtTag::Byte(value) => Ok(*value as $t),
+            NbtTag::Short(value) => Ok(*value as $t),
+            NbtTag::Int(value) => Ok(*value as $t),
+            NbtTag::Long(value) => Ok(*value as $t),
+            NbtTag::Float(value) => Ok(*value as $t),
+            NbtTag::Double(value) => Ok(*value as $t),
+            value => Err(type_mismatch("number", value)),
+        }
+    };
+}
+
+macro_rules! get_integer {
+    ($value:expr) => {
+        match $value {
+            NbtTag::Byte(value) => checked_integer(*value as i128),
+            NbtTag::Short(value) => checked_integer(*value as i128),
+            NbtTag::Int(value) => checked_integer(*value as i128),
+            NbtTag::Long(value) => checked_integer(*value as i128),
+            NbtTag::Float(value) => integer_from_float(*value as f64),
+            NbtTag::Double(value) => integer_from_float(*value),
+            value => Err(type_mismatch("number", value)),
+        }
+    };
+}
+
+/// A [`CodecOps`] for [`NbtTag`]s.
+///
+/// Lists whose elements are all bytes, ints or longs are created as the matching typed array,
+/// and typed arrays can be read as lists of their element type.
+#[derive(Debug, Clone, Default)]
+pub struct NbtOps;
+
+impl CodecOps for NbtOps {
+    type T = NbtTag;
+
+    fn create_double(&self, value: &f64) -> NbtTag {
+        NbtTag::Double(*value)
+    }
+
+    fn create_float(&self, value: &f32) -> NbtTag {
+        NbtTag::Float(*value)
+    }
+
+    fn create_byte(&self, value: &i8) -> NbtTag {
+        NbtTag::Byte(*value)
+    }
+
+    fn create_short(&self, value: &i16) -> NbtTag {
+        NbtTag::Short(*value)
+    }
+
+    fn create_int(&self, value: &i32) -> NbtTag {
+        NbtTag::Int(*value)
+    }
+
+    fn create_long(&self, value: &i64) -> NbtTag {
+        NbtTag::Long(*value)
+    }
+
+    fn create_string(&self, value: &str) -> NbtTag {
+        NbtTag::String(value.to_string())
+    }
+
+    fn create_boolean(&self, value: &bool) -> NbtTag {
+        NbtTag::Byte(*value as i8)
+    }
+
+    fn create_list(&self, value: impl IntoIterator<Item = NbtTag>) -> NbtTag {
+        let list = value.into_iter().collect::<Vec<_>>();
+        if list.is_empty() {
+            return NbtTag::List(list);
+        }
+        let bytes = list.iter().map(|x| match x {
+            NbtTag::Byte(value) => Some(*value),
+            _ => None,
+        });
+        if let Some(array) = bytes.collect() {
+            return NbtTag::ByteArray(array);
+        }
+        let ints = list.iter().map(|x| match x {
+            NbtTag::Int(value) => Some(*value),
+            _ => None,
+        });
+        if let Some(array) = ints.collect() {
+            return NbtTag::IntArray(array);
+        }
+        let longs = list.iter().map(|x| match x {
+            NbtTag::Long(value) => Some(*value),
+            _ => None,
+        });
+        if let Some(array) = longs.collect() {
+            return NbtTag::LongArray(array);
+        }
+        NbtTag::List(list)
+    }
+
+    fn create_map(&self, pairs: impl IntoIterator<Item = (String, NbtTag)>) -> NbtTag {
+        NbtTag::Compound(pairs.into_iter().collect())
+    }
+
+    fn create_unit(&self) -> NbtTag {
+        NbtTag::Compound(BTreeMap::new())
+    }
+
+    fn get_float(&self, value: &NbtTag) -> DataResult<f32> {
+        get_number!(value, f32)
+    }
+
+    fn get_double(&self, value: &NbtTag) -> DataResult<f64> {
+        get_number!(value, f64)
+    }
+
+    fn get_byte(&self, value: &NbtTag) -> DataResult<i8> {
+        get_integer!(value)
+    }
+
+    fn get_short(&self, value: &NbtTag) -> DataResult<i16> {
+        get_integer!(value)
+    }
+
+    fn get_int(&self, value: &NbtTag) -> DataResult<i32> {
+        get_integer!(value)
+    }
+
+    fn get_long(&self, value: &NbtTag) -> DataResult<i64> {
+        get_integer!(value)
+    }
+
+    fn get_string(&self, value: &NbtTag) -> DataResult<String> {
+        match value {
+            NbtTag::String(string) => Ok(string.clone()),
+            _ => Err(type_mismatch("string", value)),
+        }
+    }
+
+    fn get_str<'a>(&self, value: &'a NbtTag) -> DataResult<Cow<'a, str>> {
+        match value {
+            NbtTag::String(string) => Ok(Cow::Borrowed(string)),
+            _ => Err(type_mismatch("string", value)),
+        }
+    }
+
+    fn get_boolean(&self, value: &NbtTag) -> DataResult<bool> {
+        match value {
+            NbtTag::Byte(byte) => Ok(*byte != 0),
+            _ => Err(type_mismatch("byte", value)),
+        }
+    }
+
+    fn get_list(&self, value: &NbtTag) -> DataResult<impl ListView<NbtTag>> {
+        match value.elements() {
+            Some(elements) => Ok(NbtListView { elements }),
+            None => Err(type_mismatch("list", value)),
+        }
+    }
+
+    fn get_list_mut(&self, value: &mut NbtTag) -> DataResult<impl ListViewMut<NbtTag>> {
+        match value {
+            NbtTag::List(_) | NbtTag::ByteArray(_) | NbtTag::IntArray(_) | NbtTag::LongArray(_) => {
+                Ok(NbtListViewMut { inner: value })
+            }
+            _ => Err(type_mismatch("list", value)),
+        }
+    }
+
+    fn get_map(&self, value: &NbtTag) -> DataResult<impl MapView<NbtTag>> {
+        match value {
+            NbtTag::Compound(compound) => Ok(NbtCompoundView { inner: compound }),
+            _ => Err(type_mismatch("compound", value)),
+        }
+    }
+
+    fn get_map_mut(&self, value: &mut NbtTag) -> DataResult<impl MapViewMut<NbtTag>> {
+        match value {
+            NbtTag::Compound(compound) => Ok(NbtCompoundViewMut { inner: compound }),
+            _ => Err(type_mismatch("compound", value)),
+        }
+    }
+
+    fn get_unit(&self, value: &NbtTag) -> DataResult<()> {
+        match value {
+            NbtTag::Compound(compound) if compound.is_empty() => Ok(()),
+            NbtTag::Compound(_) => Err(DataError::new_custom("compound must have 0 fields")),
+            _ => Err(type_mismatch("compound", value)),
+        }
+    }
+}
+
+struct NbtCompoundView<'a> {
+    inner: &'a BTreeMap<String, NbtTag>,
+}
+
+impl MapView<NbtTag> for NbtCompoundView<'_> {
+    fn get(&self, name: &str) -> DataResult<&NbtTag> {
+        self.inner
+            .get(name)
+            .ok_or_else(|| DataError::key_not_found(name))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.inner.keys().cloned().collect()
+    }
+
+    fn entries<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a NbtTag)>
+    where
+        NbtTag: 'a,
+    {
+        self.inner.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+struct NbtCompoundViewMut<'a> {
+    inner: &'a mut BTreeMap<String, NbtTag>,
+}
+
+impl MapView<NbtTag> for NbtCompoundViewMut<'_> {
+    fn get(&self, name: &str) -> DataResult<&NbtTag> {
+        self.inner
+            .get(name)
+            .ok_or_else(|| DataError::key_not_found(name))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.inner.keys().cloned().collect()
+    }
+
+    fn entries<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a NbtTag)>
+    where
+        NbtTag: 'a,
+    {
+        self.inner.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl MapViewMut<NbtTag> for NbtCompoundViewMut<'_> {
+    fn get_mut(&mut self, name: &str) -> DataResult<&mut NbtTag> {
+        self.inner
+            .get_mut(name)
+            .ok_or_else(|| DataError::key_not_found(name))
+    }
+
+    fn entries_mut<'a>(&'a mut self) -> impl Iterator<Item = (&'a str, &'a mut NbtTag)>
+    where
+        NbtTag: 'a,
+    {
+        self.inner
+            .iter_mut()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    fn set(&mut self, name: &str, value: NbtTag) {
+        self.inner.insert(name.to_string(), value);
+    }
+
+    fn remove(&mut self, key: &str) -> DataResult<NbtTag> {
+        self.inner
+            .remove(key)
+            .ok_or_else(|| DataError::key_not_found(key))
+    }
+}
+
+struct NbtListView<'a> {
+    elements: Cow<'a, [NbtTag]>,
+}
+
+impl ListView<NbtTag> for NbtListView<'_> {
+    fn get(&self, index: usize) -> DataResult<&NbtTag> {
+        self.elements
+            .get(index)
+            .ok_or_else(|| DataError::list_index_out_of_bounds(index, self.elements.len()))
+    }
+
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    fn into_iter(self) -> impl Iterator<Item = NbtTag> {
+        self.elements.into_owned().into_iter()
+    }
+}
+
+struct NbtListViewMut<'a> {
+    inner: &'a mut NbtTag,
+}
+
+impl ListViewMut<NbtTag> for NbtListViewMut<'_> {
+    fn append(&mut self, value: NbtTag) {
+        match (&mut *self.inner, &value) {
+            (NbtTag::List(list), _) => list.push(value),
+            (NbtTag::ByteArray(array), NbtTag::Byte(byte)) => array.push(*byte),
+            (NbtTag::IntArray(array), NbtTag::Int(int)) => array.push(*int),
+            (NbtTag::LongArray(array), NbtTag::Long(long)) => array.push(*long),
+            // The value does not fit into the typed array, so it is widened into a regular list.
+            (inner, _) => {
+                if let Some(elements) = inner.elements() {
+                    let mut list = elements.into_owned();
+                    list.push(value);
+                    *self.inner = NbtTag::List(list);
+                }
+            }
+        }
+    }
+
+    fn get_mut(&mut self, index: usize) -> DataResult<&mut NbtTag> {
+        let NbtTag::List(list) = &mut *self.inner else {
+            return Err(DataError::new_custom(
+                "elements of typed NBT arrays can not be borrowed mutably",
+            ));
+        };
+        let len = list.len();
+        list.get_mut(index)
+            .ok_or_else(|| DataError::list_index_out_of_bounds(index, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        collections::btree_map::BTreeMap,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use crate::serialization::{
+        Codec, CodecAdapters, CodecOps, Codecs, DefaultCodec, ListView, ListViewMut,
+        MapCodecBuilder,
+    };
+
+    use super::{NbtOps, NbtTag};
+
+    #[derive(Debug, PartialEq)]
+    struct Level {
+        name: String,
+        version: i32,
+        seed: i64,
+        flags: Vec<i8>,
+        spawn: Vec<i32>,
+    }
+
+    fn level_codec() -> impl Codec<Level, NbtOps> {
+        MapCodecBuilder::new()
+            .field(String::codec().field_of("name", |l: &Level| &l.name))
+            .field(i32::codec().field_of("version", |l: &Level| &l.version))
+            .field(i64::codec().field_of("seed", |l: &Level| &l.seed))
+            .field(
+                i8::codec()
+                    .list_of()
+                    .field_of("flags", |l: &Level| &l.flags),
+            )
+            .field(
+                i32::codec()
+                    .list_of()
+                    .field_of("spawn", |l: &Level| &l.spawn),
+            )
+            .build(|name, version, seed, flags, spawn| Level {
+                name,
+                version,
+                seed,
+                flags,
+                spawn,
+            })
+    }
+
+    #[test]
+    fn typed_values() {
+        let level = Level {
+            name: "world".to_string(),
+            version: 19133,
+            seed: -4_000_000_000,
+            flags: vec![1, 0, 1],
+            spawn: vec![0, 64, 0],
+        };
+        let encoded = level_codec().encode_start(&NbtOps, &level).unwrap();
+        let NbtTag::Compound(compound) = &encoded else {
+            panic!("expected a compound");
+        };
+        assert_eq!(compound["version"], NbtTag::Int(19133));
+        assert_eq!(compound["seed"], NbtTag::Long(-4_000_000_000));
+        assert_eq!(compound["flags"], NbtTag::ByteArray(vec![1, 0, 1]));
+        assert_eq!(compound["spawn"], NbtTag::IntArray(vec![0, 64, 0]));
+        assert_eq!(
+            level_codec().decode_start(&NbtOps, &encoded).unwrap(),
+            level
+        );
+
+        let mut bytes = Vec::new();
+        encoded.write("", &mut bytes).unwrap();
+        let (name, read) = NbtTag::read(&bytes).unwrap();
+        assert_eq!(name, "");
+        assert_eq!(read, encoded);
+    }
+
+    #[test]
+    fn typed_array_views() {
+        let mut array = NbtTag::ByteArray(vec![1, 2]);
+        {
+            let list = NbtOps.get_list(&array).unwrap();
+            assert_eq!(list.get(1).unwrap(), &NbtTag::Byte(2));
+        }
+
+        {
+            let mut view = NbtOps.get_list_mut(&mut array).unwrap();
+            view.append(NbtTag::Byte(3));
+            assert!(view.get_mut(0).is_err());
+            view.append(NbtTag::String("a".to_string()));
+        }
+        assert_eq!(
+            array,
+            NbtTag::List(vec![
+                NbtTag::Byte(1),
+                NbtTag::Byte(2),
+                NbtTag::Byte(3),
+                NbtTag::String("a".to_string())
+            ])
+        );
+
+        assert!(NbtTag::read(&[10, 0, 0, 1, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn f32_stays_single_precision() {
+        let value = 0.1f32;
+        let encoded = f32::codec().encode_start(&NbtOps, &value).unwrap();
+        assert_eq!(encoded, NbtTag::Float(value));
+        let decoded = f32::codec().decode_start(&NbtOps, &encoded).unwrap();
+        assert_eq!(decoded.to_bits(), value.to_bits());
+    }
+
+    #[test]
+    fn integer_widths() {
+        let value = (1i8, 2i16, 3i32, 4i64, 100u8, 5u16);
+        let codec = Codecs::tuple6(
+            i8::codec(),
+            i16::codec(),
+            i32::codec(),
+            i64::codec(),
+            u8::codec(),
+            u16::codec(),
+        );
+        let encoded = codec.encode_start(&NbtOps, &value).unwrap();
+        assert_eq!(
+            encoded,
+            NbtTag::List(vec![
+                NbtTag::Byte(1),
+                NbtTag::Short(2),
+                NbtTag::Int(3),
+                NbtTag::Long(4),
+                NbtTag::Byte(100),
+                NbtTag::Short(5),
+            ])
+        );
+        assert_eq!(codec.decode_start(&NbtOps, &encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn unsigned_integer_range() {
+        assert_eq!(
+            u32::codec()
+                .encode_start(&NbtOps, &(i32::MAX as u32))
+                .unwrap(),
+            NbtTag::Int(i32::MAX)
+        );
+        assert!(u32::codec().encode_start(&NbtOps, &u32::MAX).is_err());
+        assert!(u8::codec().encode_start(&NbtOps, &200).is_err());
+        assert_eq!(
+            u8::codec().decode_start(&NbtOps, &NbtTag::Byte(5)).unwrap(),
+            5
+        );
+        assert!(
+            u8::codec()
+                .decode_start(&NbtOps, &NbtTag::Byte(-56))
+                .is_err()
+        );
+        assert!(
+            u16::codec()
+                .decode_start(&NbtOps, &NbtTag::Int(65536))
+                .is_err()
+        );
+        assert_eq!(
+            u16::codec()
+                .decode_start(&NbtOps, &NbtTag::Int(40000))
+                .unwrap(),
+            40000
+        );
+        assert!(u64::codec().encode_start(&NbtOps, &u64::MAX).is_err());
+    }
+
+    #[test]
+    fn option_without_null() {
+        let codec = Option::<i32>::codec();
+        let encoded = codec.encode_start(&NbtOps, &None).unwrap();
+        assert_eq!(encoded, NbtTag::Compound(BTreeMap::new()));
+        assert_eq!(codec.decode_start(&NbtOps, &encoded).unwrap(), None);
+        assert_eq!(
+            codec.decode_start(&NbtOps, &NbtTag::Int(3)).unwrap(),
+            Some(3)
+        );
+
+        // NBT has no null, so `Some(())` is encoded the same as `None`.
+        let codec = crate::serialization::builtins::codecs::OptionCodec {
+            inner: Codecs::unit(),
+            _phantom: core::marker::PhantomData,
+        };
+        let encoded = codec.encode_start(&NbtOps, &Some(())).unwrap();
+        assert_eq!(codec.decode_start(&NbtOps, &encoded).unwrap(), None);
+    }
+
+    #[test]
+    fn modified_utf8_strings() {
+        let mut bytes = Vec::new();
+        NbtTag::String("a\0b".to_string())
+            .write("", &mut bytes)
+            .unwrap();
+        assert_eq!(bytes, [8, 0, 0, 0, 4, b'a', 0xC0, 0x80, b'b']);
+        assert_eq!(
+            NbtTag::read(&bytes).unwrap().1,
+            NbtTag::String("a\0b".to_string())
+        );
+
+        let mut bytes = Vec::new();
+        NbtTag::String("\u{1F600}".to_string())
+            .write("", &mut bytes)
+            .unwrap();
+        assert_eq!(bytes, [8, 0, 0, 0, 6, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+        assert_eq!(
+            NbtTag::read(&bytes).unwrap().1,
+            NbtTag::String("\u{1F600}".to_string())
+        );
+
+        // Standard UTF-8 for a supplementary character isn't valid modified UTF-8.
+        assert!(NbtTag::read(&[8, 0, 0, 0, 4, 0xF0, 0x9F, 0x98, 0x80]).is_err());
+    }
+}
@@ -0,0 +1,413 @@
+use alloc::{
+    borrow::Cow,
+    collections::btree_map::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    result::{DataError, DataResult},
+    serialization::{CodecOps, ListView, MapView},
+};
+
+use super::{ListViewMut, MapViewMut, checked_integer, integer_from_float};
+
+/// An in-memory value that can represent everything a [`CodecOps`] can create, without depending on a data format.
+///
+/// Numeric types are kept distinct, so a value encoded with [`DynamicOps`] remembers which numeric type a codec chose.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Boolean(bool),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Boolean(_) => "boolean",
+            Value::Byte(_) => "byte",
+            Value::Short(_) => "short",
+            Value::Int(_) => "int",
+            Value::Long(_) => "long",
+            Value::Float(_) => "float",
+            Value::Double(_) => "double",
+            Value::String(_) => "string",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+        }
+    }
+}
+
+fn type_mismatch(expected: &str, found: &Value) -> DataError {
+    DataError::type_mismatch(expected, found.type_name())
+}
+
+macro_rules! get_number {
+    ($value:expr, $t:ty) => {
+        match $value {
+            Value::Byte(value) => Ok(*value as $t),
+            Value::Short(value) => Ok(*value as $t),
+            Value::Int(value) => Ok(*value as $t),
+            Value::Long(value) => Ok(*value as $t),
+            Value::Float(value) => Ok(*value as $t),
+            Value::Double(value) => Ok(*value as $t),
+            value => Err(type_mismatch("number", value)),
+        }
+    };
+}
+
+macro_rules! get_integer {
+    ($value:expr) => {
+        match $value {
+            Value::Byte(value) => checked_integer(*value as i128),
+            Value::Short(value) => checked_integer(*value as i128),
+            Value::Int(value) => checked_integer(*value as i128),
+            Value::Long(value) => checked_integer(*value as i128),
+            Value::Float(value) => integer_from_float(*value as f64),
+            Value::Double(value) => integer_from_float(*value),
+            value => Err(type_mismatch("number", value)),
+        }
+    };
+}
+
+/// A [`CodecOps`] for the in-memory [`Value`] type.
+///
+/// This is useful for tests and for transforming data with a [`Dynamic`](crate::serialization::Dynamic)
+/// without going through a data format such as JSON.
+#[derive(Debug, Clone, Default)]
+pub struct DynamicOps;
+
+impl CodecOps for DynamicOps {
+    type T = Value;
+
+    fn create_double(&self, value: &f64) -> Value {
+        Value::Double(*value)
+    }
+
+    fn create_float(&self, value: &f32) -> Value {
+        Value::Float(*value)
+    }
+
+    fn create_byte(&self, value: &i8) -> Value {
+        Value::Byte(*value)
+    }
+
+    fn create_short(&self, value: &i16) -> Value {
+        Value::Short(*value)
+    }
+
+    fn create_int(&self, value: &i32) -> Value {
+        Value::Int(*value)
+    }
+
+    fn create_long(&self, value: &i64) -> Value {
+        Value::Long(*value)
+    }
+
+    fn create_string(&self, value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    fn create_boolean(&self, value: &bool) -> Value {
+        Value::Boolean(*value)
+    }
+
+    fn create_list(&self, value: impl IntoIterator<Item = Value>) -> Value {
+        Value::List(value.into_iter().collect())
+    }
+
+    fn create_map(&self, pairs: impl IntoIterator<Item = (String, Value)>) -> Value {
+        Value::Map(pairs.into_iter().collect())
+    }
+
+    fn create_unit(&self) -> Value {
+        Value::Map(BTreeMap::new())
+    }
+
+    fn get_float(&self, value: &Value) -> DataResult<f32> {
+        get_number!(value, f32)
+    }
+
+    fn get_double(&self, value: &Value) -> DataResult<f64> {
+        get_number!(value, f64)
+    }
+
+    fn get_byte(&self, value: &Value) -> DataResult<i8> {
+        get_integer!(value)
+    }
+
+    fn get_short(&self, value: &Value) -> DataResult<i16> {
+        get_integer!(value)
+    }
+
+    fn get_int(&self, value: &Value) -> DataResult<i32> {
+        get_integer!(value)
+    }
+
+    fn get_long(&self, value: &Value) -> DataResult<i64> {
+        get_integer!(value)
+    }
+
+    fn get_string(&self, value: &Value) -> DataResult<String> {
+        match value {
+            Value::String(string) => Ok(string.clone()),
+            _ => Err(type_mismatch("string", value)),
+        }
+    }
+
+    fn get_str<'a>(&self, value: &'a Value) -> DataResult<Cow<'a, str>> {
+        match value {
+            Value::String(string) => Ok(Cow::Borrowed(string)),
+            _ => Err(type_mismatch("string", value)),
+        }
+    }
+
+    fn get_boolean(&self, value: &Value) -> DataResult<bool> {
+        match value {
+            Value::Boolean(boolean) => Ok(*boolean),
+            _ => Err(type_mismatch("boolean", value)),
+        }
+    }
+
+    fn get_list(&self, value: &Value) -> DataResult<impl ListView<Value>> {
+        match value {
+            Value::List(list) => Ok(ValueListView { inner: list }),
+            _ => Err(type_mismatch("list", value)),
+        }
+    }
+
+    fn get_list_mut(&self, value: &mut Value) -> DataResult<impl ListViewMut<Value>> {
+        match value {
+            Value::List(list) => Ok(ValueListViewMut { inner: list }),
+            _ => Err(type_mismatch("list", value)),
+        }
+    }
+
+    fn get_map(&self, value: &Value) -> DataResult<impl MapView<Value>> {
+        match value {
+            Value::Map(map) => Ok(ValueMapView { inner: map }),
+            _ => Err(type_mismatch("map", value)),
+        }
+    }
+
+    fn get_map_mut(&self, value: &mut Value) -> DataResult<impl MapViewMut<Value>> {
+        match value {
+            Value::Map(map) => Ok(ValueMapViewMut { inner: map }),
+            _ => Err(type_mismatch("map", value)),
+        }
+    }
+
+    fn get_unit(&self, value: &Value) -> DataResult<()> {
+        match value {
+            Value::Map(map) if map.is_empty() => Ok(()),
+            Value::Map(_) => Err(DataError::new_custom("map must have 0 fields")),
+            _ => Err(type_mismatch("map", value)),
+        }
+    }
+}
+
+struct ValueMapView<'a> {
+    inner: &'a BTreeMap<String, Value>,
+}
+
+impl MapView<Value> for ValueMapView<'_> {
+    fn get(&self, name: &str) -> DataResult<&Value> {
+        self.inner
+            .get(name)
+            .ok_or_else(|| DataError::key_not_found(name))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.inner.keys().cloned().collect()
+    }
+
+    fn entries<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a Value)>
+    where
+        Value: 'a,
+    {
+        self.inner.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+struct ValueMapViewMut<'a> {
+    inner: &'a mut BTreeMap<String, Value>,
+}
+
+impl MapView<Value> for ValueMapViewMut<'_> {
+    fn get(&self, name: &str) -> DataResult<&Value> {
+        self.inner
+            .get(name)
+            .ok_or_else(|| DataError::key_not_found(name))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.inner.keys().cloned().collect()
+    }
+
+    fn entries<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a Value)>
+    where
+        Value: 'a,
+    {
+        self.inner.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl MapViewMut<Value> for ValueMapViewMut<'_> {
+    fn get_mut(&mut self, name: &str) -> DataResult<&mut Value> {
+        self.inner
+            .get_mut(name)
+            .ok_or_else(|| DataError::key_not_found(name))
+    }
+
+    fn entries_mut<'a>(&'a mut self) -> impl Iterator<Item = (&'a str, &'a mut Value)>
+    where
+        Value: 'a,
+    {
+        self.inner
+            .iter_mut()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    fn set(&mut self, name: &str, value: Value) {
+        self.inner.insert(name.to_string(), value);
+    }
+
+    fn remove(&mut self, key: &str) -> DataResult<Value> {
+        self.inner
+            .remove(key)
+            .ok_or_else(|| DataError::key_not_found(key))
+    }
+}
+
+struct ValueListView<'a> {
+    inner: &'a Vec<Value>,
+}
+
+impl ListView<Value> for ValueListView<'_> {
+    fn get(&self, index: usize) -> DataResult<&Value> {
+        self.inner
+            .get(index)
+            .ok_or_else(|| DataError::list_index_out_of_bounds(index, self.inner.len()))
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn into_iter(self) -> impl Iterator<Item = Value> {
+        self.inner.clone().into_iter()
+    }
+}
+
+struct ValueListViewMut<'a> {
+    inner: &'a mut Vec<Value>,
+}
+
+impl ListViewMut<Value> for ValueListViewMut<'_> {
+    fn append(&mut self, value: Value) {
+        self.inner.push(value);
+    }
+
+    fn get_mut(&mut self, index: usize) -> DataResult<&mut Value> {
+        let len = self.inner.len();
+        self.inner
+            .get_mut(index)
+            .ok_or_else(|| DataError::list_index_out_of_bounds(index, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        collections::btree_map::BTreeMap,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use crate::serialization::{
+        Codec, CodecAdapters, Codecs, DefaultCodec, Dynamic, MapCodecBuilder,
+    };
+
+    use super::{DynamicOps, Value};
+
+    #[test]
+    fn encode_to_dynamic() {
+        let codec = MapCodecBuilder::new()
+            .field(String::codec().field_of("name", |x: &(String, Vec<i16>)| &x.0))
+            .field(
+                i16::codec()
+                    .list_of()
+                    .field_of("scores", |x: &(String, Vec<i16>)| &x.1),
+            )
+            .build(|name, scores| (name, scores));
+
+        let value = ("a".to_string(), vec![1, 2]);
+        let encoded = codec.encode_start(&DynamicOps, &value).unwrap();
+        let dynamic = Dynamic::new(encoded, DynamicOps);
+        assert_eq!(
+            dynamic.get_field("scores").unwrap().into_inner(),
+            Value::List(vec![Value::Short(1), Value::Short(2)])
+        );
+        assert_eq!(
+            codec.decode_start(&DynamicOps, dynamic.value()).unwrap(),
+            value
+        );
+
+        assert!(
+            f64::codec()
+                .decode_start(&DynamicOps, &Value::String("a".to_string()))
+                .is_err()
+        );
+        let unit = Codecs::unit().encode_start(&DynamicOps, &()).unwrap();
+        assert_eq!(unit, Value::Map(BTreeMap::new()));
+        assert!(Codecs::unit().decode_start(&DynamicOps, &unit).is_ok());
+    }
+
+    #[test]
+    fn checked_integers() {
+        assert_eq!(
+            i8::codec()
+                .decode_start(&DynamicOps, &Value::Long(100))
+                .unwrap(),
+            100
+        );
+        assert!(
+            i8::codec()
+                .decode_start(&DynamicOps, &Value::Long(300))
+                .is_err()
+        );
+        assert_eq!(
+            i32::codec()
+                .decode_start(&DynamicOps, &Value::Double(2.0))
+                .unwrap(),
+            2
+        );
+        assert!(
+            i32::codec()
+                .decode_start(&DynamicOps, &Value::Double(2.5))
+                .is_err()
+        );
+        assert!(
+            i64::codec()
+                .decode_start(&DynamicOps, &Value::Double(f64::NAN))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use core::{
+    fmt::{Display, Formatter},
+    ops::Bound,
+};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use json::JsonValue;
+
+/// Describes the shape of the values a [`Codec`](super::Codec) accepts, as returned by [`Codec::schema`](super::Codec::schema).
+///
+/// Built-in codecs describe themselves, and adapters such as [`CodecAdapters::xmap`](super::CodecAdapters::xmap)
+/// pass through the schema of the codec they wrap. Codecs that can't describe themselves, such as codecs
+/// built from functions or the placeholder of a recursive codec, report [`Schema::Any`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Schema {
+    /// Any value is accepted.
+    Any,
+    /// A value with no fields, see [`CodecOps::create_unit`](super::CodecOps::create_unit).
+    Unit,
+    Boolean,
+    /// A number, which must be a whole number if `integer` is true, and must be within the bounds.
+    Number {
+        integer: bool,
+        minimum: Bound<f64>,
+        maximum: Bound<f64>,
+    },
+    String,
+    /// A list where every element matches the schema.
+    List(Box<Schema>),
+    /// A map of arbitrary string keys, where every value matches the schema.
+    Map(Box<Schema>),
+    /// A map with a fixed set of fields, such as a codec built with [`MapCodecBuilder`](super::MapCodecBuilder).
+    /// Keys other than the fields are rejected.
+    Record(Vec<SchemaField>),
+    /// A record that also accepts keys other than its fields, where the value of every other key matches `rest`,
+    /// such as a record that flattens a map.
+    OpenRecord {
+        fields: Vec<SchemaField>,
+        rest: Box<Schema>,
+    },
+    /// One of a fixed set of strings.
+    Enum(Vec<String>),
+    /// A value matching at least one of the schemas.
+    OneOf(Vec<Schema>),
+}
+
+/// A field of a [`Schema::Record`] or [`Schema::OpenRecord`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaField {
+    pub name: String,
+    pub schema: Schema,
+    /// Whether decoding fails if the field is missing.
+    pub required: bool,
+}
+
+impl Schema {
+    /// A number with no bounds.
+    pub fn number() -> Schema {
+        Schema::Number {
+            integer: false,
+            minimum: Bound::Unbounded,
+            maximum: Bound::Unbounded,
+        }
+    }
+
+    /// A whole number with no bounds.
+    pub fn integer() -> Schema {
+        Schema::Number {
+            integer: true,
+            minimum: Bound::Unbounded,
+            maximum: Bound::Unbounded,
+        }
+    }
+
+    /// Adds a field to this schema if it is a [`Schema::Record`] or [`Schema::OpenRecord`],
+    /// such as a field written by a codec wrapping a record.
+    pub(crate) fn with_field(self, name: &str, schema: Schema, required: bool) -> Schema {
+        let field = SchemaField {
+            name: name.into(),
+            schema,
+            required,
+        };
+        match self {
+            Schema::Record(mut fields) => {
+                fields.push(field);
+                Schema::Record(fields)
+            }
+            Schema::OpenRecord { mut fields, rest } => {
+                fields.push(field);
+                Schema::OpenRecord { fields, rest }
+            }
+            schema => schema,
+        }
+    }
+
+    /// Converts the fields of a record into a JSON Schema object, with `additional` as `additionalProperties`.
+    fn record_to_json_schema(fields: &[SchemaField], additional: JsonValue) -> JsonValue {
+        let mut properties = JsonValue::new_object();
+        let mut required = JsonValue::new_array();
+        for field in fields {
+            properties[field.name.as_str()] = field.schema.to_json_schema();
+            if field.required {
+                let _ = required.push(field.name.as_str());
+            }
+        }
+        json::object! {
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": additional,
+        }
+    }
+
+    /// Converts this schema into a [JSON Schema](https://json-schema.org/) describing the values [`JsonOps`](super::json::JsonOps) accepts.
+    pub fn to_json_schema(&self) -> JsonValue {
+        match self {
+            Schema::Any => JsonValue::new_object(),
+            Schema::Unit => json::object! { "type": "object", "maxProperties": 0 },
+            Schema::Boolean => json::object! { "type": "boolean" },
+            Schema::Number {
+                integer,
+                minimum,
+                maximum,
+            } => {
+                let mut schema =
+                    json::object! { "type": if *integer { "integer" } else { "number" } };
+                match minimum {
+                    Bound::Included(minimum) => schema["minimum"] = (*minimum).into(),
+                    Bound::Excluded(minimum) => schema["exclusiveMinimum"] = (*minimum).into(),
+                    Bound::Unbounded => {}
+                }
+                match maximum {
+                    Bound::Included(maximum) => schema["maximum"] = (*maximum).into(),
+                    Bound::Excluded(maximum) => schema["exclusiveMaximum"] = (*maximum).into(),
+                    Bound::Unbounded => {}
+                }
+                schema
+            }
+            Schema::String => json::object! { "type": "string" },
+            Schema::List(items) => {
+                json::object! { "type": "array", "items": items.to_json_schema() }
+            }
+            Schema::Map(values) => json::object! {
+                "type": "object",
+                "additionalProperties": values.to_json_schema(),
+            },
+            Schema::Record(fields) => Schema::record_to_json_schema(fields, false.into()),
+            Schema::OpenRecord { fields, rest } => {
+                Schema::record_to_json_schema(fields, rest.to_json_schema())
+            }
+            Schema::Enum(variants) => json::object! { "enum": variants.clone() },
+            Schema::OneOf(schemas) => json::object! {
+                "anyOf": schemas.iter().map(Schema::to_json_schema).collect::<Vec<_>>(),
+            },
+        }
+    }
+}
+
+/// Renders a schema as a compact, human-readable tree, such as `{ volume: integer, name?: string, tags: [string] }`.
+/// Optional fields are marked with `?`, and bounded numbers list their range, such as `integer in [0, 100]`.
+impl Display for Schema {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Schema::Any => write!(f, "any"),
+            Schema::Unit => write!(f, "unit"),
+            Schema::Boolean => write!(f, "boolean"),
+            Schema::Number {
+                integer,
+                minimum,
+                maximum,
+            } => {
+                write!(f, "{}", if *integer { "integer" } else { "number" })?;
+                if let (Bound::Unbounded, Bound::Unbounded) = (minimum, maximum) {
+                    return Ok(());
+                }
+                match minimum {
+                    Bound::Included(minimum) => write!(f, " in [{}, ", minimum)?,
+                    Bound::Excluded(minimum) => write!(f, " in ({}, ", minimum)?,
+                    Bound::Unbounded => write!(f, " in (-inf, ")?,
+                }
+                match maximum {
+                    Bound::Included(maximum) => write!(f, "{}]", maximum),
+                    Bound::Excluded(maximum) => write!(f, "{})", maximum),
+                    Bound::Unbounded => write!(f, "inf)"),
+                }
+            }
+            Schema::String => write!(f, "string"),
+            Schema::List(items) => write!(f, "[{}]", items),
+            Schema::Map(values) => write!(f, "{{ [string]: {} }}", values),
+            Schema::Record(fields) if fields.is_empty() => write!(f, "{{}}"),
+            Schema::Record(fields) => {
+                write!(f, "{{ ")?;
+                write_fields(f, fields)?;
+                write!(f, " }}")
+            }
+            Schema::OpenRecord { fields, rest } => {
+                write!(f, "{{ ")?;
+                write_fields(f, fields)?;
+                if !fields.is_empty() {
+                    write!(f, ", ")?;
+                }
+                write!(f, "[string]: {} }}", rest)
+            }
+            Schema::Enum(variants) => {
+                for (index, variant) in variants.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{:?}", variant)?;
+                }
+                Ok(())
+            }
+            Schema::OneOf(schemas) => {
+                for (index, schema) in schemas.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{}", schema)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn write_fields(f: &mut Formatter<'_>, fields: &[SchemaField]) -> core::fmt::Result {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        let optional = if field.required { "" } else { "?" };
+        write!(f, "{}{}: {}", field.name, optional, field.schema)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use core::fmt::Debug;
+
+use crate::serialization::{Codec, CodecOps};
+
+/// Encodes `value` with `codec`, decodes the result, and asserts that the decoded value equals `value`.
+///
+/// On failure, the panic message includes the intermediate encoded form, so a mismatch can be traced to
+/// either the encoding or the decoding half of the codec.
+///
+/// ```
+/// use datafix::{serialization::{DefaultCodec, json::JsonOps}, testing::assert_round_trip};
+///
+/// assert_round_trip(&Vec::<i32>::codec(), &JsonOps, &vec![1, 2, 3]);
+/// ```
+#[track_caller]
+pub fn assert_round_trip<T, O>(codec: &impl Codec<T, O>, ops: &O, value: &T)
+where
+    T: Debug + PartialEq,
+    O: CodecOps,
+    O::T: Debug,
+{
+    let encoded = match codec.encode_start(ops, value) {
+        Ok(encoded) => encoded,
+        Err(error) => panic!("failed to encode {:?}: {}", value, error),
+    };
+    let decoded = match codec.decode_start(ops, &encoded) {
+        Ok(decoded) => decoded,
+        Err(error) => panic!(
+            "failed to decode {:?}, encoded from {:?}: {}",
+            encoded, value, error
+        ),
+    };
+    assert!(
+        decoded == *value,
+        "round trip changed the value\n  original: {:?}\n   encoded: {:?}\n   decoded: {:?}",
+        value,
+        encoded,
+        decoded
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec,
+    };
+
+    use crate::serialization::{CodecAdapters, DefaultCodec, json::JsonOps};
+
+    use super::assert_round_trip;
+
+    #[test]
+    fn round_trip() {
+        assert_round_trip(&i32::codec().list_of(), &JsonOps, &vec![1, 2, 3]);
+        assert_round_trip(&String::codec(), &JsonOps, &"text".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "round trip changed the value")]
+    fn lossy_round_trip() {
+        let codec = i32::codec().xmap(|x| x / 2, |x| *x);
+        assert_round_trip(&codec, &JsonOps, &4);
+    }
+}
@@ -45,10 +45,10 @@ impl<O: CodecOps> DefaultCodec<O> for GameConfig {
 fn main() -> CodecResult<()> {
     let config = GameConfig::new(100, 50, 12);
     println!("{:?}", config);
-    let mut encoded = GameConfig::codec().encode_start(&JsonOps, &config)?;
-    println!("{}", encoded);
+    let mut encoded = GameConfig::codec().encode_dynamic(&JsonOps, &config)?;
+    println!("{}", encoded.value());
     encoded.insert("wrender_distance", "ok").unwrap();
-    let decoded = GameConfig::codec().decode_start(&JsonOps, &encoded)?;
+    let decoded = GameConfig::codec().decode_start(&JsonOps, encoded.value())?;
     println!("{:?}", decoded);
 
     assert_eq!(config, decoded);